@@ -0,0 +1,8889 @@
+//! Streaming profiling of tabular data: column types, summary statistics and
+//! data quality diagnostics for CSV, JSON, YAML, TOML and (behind features) Parquet,
+//! Arrow IPC and Excel input. The `datainspect` binary is a thin CLI over this crate.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::path::Path;
+use csv::ReaderBuilder;
+use encoding_rs::Encoding;
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+use flate2::bufread::MultiGzDecoder;
+use indicatif::{ProgressBar, ProgressStyle};
+use owo_colors::OwoColorize;
+use regex::Regex;
+use serde::Serialize;
+
+/// Inspects a file (or an http(s) URL with the `http` feature), picking the
+/// format from its extension unless `opts.format` is set; a `.gz` suffix is
+/// decompressed on the fly.
+pub fn inspect_file(filename: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    let report = inspect_source(filename, opts)?;
+    check_coercions(&report, opts)?;
+    let known = |column: &String| report.columns.iter().any(|c| &c.name == column);
+    if let Some((column, _)) = opts.validate.iter().find(|(column, _)| !known(column)) {
+        return Err(InspectError::UnknownColumn(column.clone()));
+    }
+    Ok(report)
+}
+
+// A --coerce column must exist, and a numeric one fails the run when fewer
+// than --infer-threshold of its values parse (a few bad ones are reported
+// like any parse failure instead).
+fn check_coercions(report: &Report, opts: &InspectOptions) -> Result<(), InspectError> {
+    for (column, kind) in &opts.coerce {
+        let index = report
+            .columns
+            .iter()
+            .position(|c| &c.name == column)
+            .ok_or_else(|| InspectError::UnknownColumn(column.clone()))?;
+        let Some(stats) = &report.stats[index] else {
+            continue;
+        };
+        let values = stats.total - stats.missing;
+        // large integers parse, but stay out of the stats just like failures
+        let failures = stats.numeric_parse_failures + stats.large_integers;
+        let parsed_share = (values - failures) as f64 / values.max(1) as f64;
+        if stats.kind == ColumnType::Numeric && failures > 0 && parsed_share < opts.infer_threshold {
+            return Err(InspectError::CoercionFailed {
+                column: column.clone(),
+                kind: *kind,
+                failures,
+                values,
+                example: stats
+                    .failure_examples
+                    .first()
+                    .or(stats.large_integer_example.as_ref())
+                    .map(|(row, value)| (row + opts.skip, value.clone())),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn inspect_source(filename: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    if is_url(filename) {
+        return inspect_url(filename, opts);
+    }
+
+    // a .gz suffix is decompressed on the fly; the inner extension picks the format
+    let compressed = filename.ends_with(".gz");
+    let extension = file_extension(filename);
+
+    // row formats are checked in read_input
+    let forced = opts.format.is_some() || opts.fixed_width.is_some();
+    if opts.group_by.is_some() && !forced && matches!(extension, "parquet" | "arrow" | "feather" | "xlsx") {
+        return Err(group_by_unsupported());
+    }
+
+    match extension {
+        _ if forced => inspect_rows(filename, opts),
+        "parquet" if compressed => Err(InspectError::Usage(
+            "gzip-compressed Parquet files are not supported".to_string(),
+        )),
+        #[cfg(feature = "parquet")]
+        "parquet" => inspect_parquet(filename, opts),
+        #[cfg(not(feature = "parquet"))]
+        "parquet" => Err(InspectError::Usage(
+            "parquet support is not enabled (rebuild with --features parquet)".to_string(),
+        )),
+        "arrow" | "feather" if compressed => Err(InspectError::Usage(
+            "gzip-compressed Arrow files are not supported".to_string(),
+        )),
+        #[cfg(feature = "arrow")]
+        "arrow" | "feather" => inspect_arrow(filename, opts),
+        #[cfg(not(feature = "arrow"))]
+        "arrow" | "feather" => Err(InspectError::Usage(
+            "arrow support is not enabled (rebuild with --features arrow)".to_string(),
+        )),
+        "xlsx" if compressed => Err(InspectError::Usage(
+            "gzip-compressed Excel files are not supported".to_string(),
+        )),
+        #[cfg(feature = "xlsx")]
+        "xlsx" => inspect_xlsx(filename, opts),
+        #[cfg(not(feature = "xlsx"))]
+        "xlsx" => Err(InspectError::Usage(
+            "xlsx support is not enabled (rebuild with --features xlsx)".to_string(),
+        )),
+        _ => inspect_rows(filename, opts),
+    }
+}
+
+fn group_by_unsupported() -> InspectError {
+    InspectError::Usage("--group-by only works on CSV, TSV and fixed-width input".to_string())
+}
+
+// extension under an optional .gz suffix; for a URL, of its path without the
+// query or fragment
+fn file_extension(filename: &str) -> &str {
+    let path = match filename.split_once("://") {
+        Some((_, rest)) => {
+            let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+            rest.find('/').map_or("", |i| &rest[i..])
+        }
+        None => filename,
+    };
+    Path::new(path.strip_suffix(".gz").unwrap_or(path))
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+}
+
+// --format wins over the extension
+fn row_format(filename: &str, opts: &InspectOptions) -> Result<InputFormat, InspectError> {
+    if opts.fixed_width.is_some() {
+        return Ok(InputFormat::FixedWidth);
+    }
+    let extension = file_extension(filename);
+    opts.format
+        .or_else(|| InputFormat::from_extension(extension))
+        .ok_or_else(|| InspectError::Usage(format!("unsupported file type: {}", extension)))
+}
+
+fn inspect_rows(filename: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    let format = row_format(filename, opts)?;
+    let progress = start_progress(filename, opts);
+    let report = if splits_into_chunks(filename, format, opts) {
+        let mut opts = opts.clone();
+        if format == InputFormat::Tsv {
+            opts.delimiter.get_or_insert(b'\t');
+        }
+        read_csv_parallel(filename, &opts, progress.as_ref())
+    } else {
+        let input = open_input(filename, progress.as_ref())?;
+        read_input(input, format, opts, progress.as_ref(), filename)
+    };
+    if let Some(bar) = progress {
+        bar.finish_and_clear();
+    }
+    report
+}
+
+fn is_url(filename: &str) -> bool {
+    filename.starts_with("http://") || filename.starts_with("https://")
+}
+
+// The response body is streamed into the parser like a local file. Columnar
+// formats need random access, so only the row-oriented ones work here.
+#[cfg(feature = "http")]
+fn inspect_url(url: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    let extension = file_extension(url);
+    if opts.format.is_none() && opts.fixed_width.is_none() && matches!(extension, "parquet" | "arrow" | "feather" | "xlsx") {
+        return Err(InspectError::Usage(format!(
+            "{} files can't be read from a URL; download it first",
+            extension
+        )));
+    }
+    let fixed_width = opts.fixed_width.as_ref().map(|_| InputFormat::FixedWidth);
+    let format = match fixed_width.or(opts.format).or_else(|| InputFormat::from_extension(extension)) {
+        Some(format) => format,
+        None => {
+            return Err(InspectError::Usage(format!(
+                "can't tell the format of {} from its path; pass --format",
+                url
+            )));
+        }
+    };
+
+    let response = reqwest::blocking::get(url)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(InspectError::HttpStatus {
+            url: url.to_string(),
+            status: status.to_string(),
+        });
+    }
+
+    let compressed = url.split(['?', '#']).next().is_some_and(|path| path.ends_with(".gz"));
+    let body: Box<dyn Read> = if compressed {
+        Box::new(MultiGzDecoder::new(BufReader::new(response)))
+    } else {
+        Box::new(response)
+    };
+    read_input(body, format, opts, None, url)
+}
+
+#[cfg(not(feature = "http"))]
+fn inspect_url(_url: &str, _opts: &InspectOptions) -> Result<Report, InspectError> {
+    Err(InspectError::Usage(
+        "URL support is not enabled (rebuild with --features http)".to_string(),
+    ))
+}
+
+/// Row-oriented formats that can be inspected from any reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Csv,
+    Tsv,
+    Json,
+    JsonLines,
+    Yaml,
+    Toml,
+    // columns at fixed positions, set up by `opts.fixed_width`
+    FixedWidth,
+}
+
+impl InputFormat {
+    /// The format for a file extension (without the dot), e.g. `"ndjson"`.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "csv" => Some(InputFormat::Csv),
+            "tsv" => Some(InputFormat::Tsv),
+            "json" => Some(InputFormat::Json),
+            "jsonl" | "ndjson" => Some(InputFormat::JsonLines),
+            "yaml" | "yml" => Some(InputFormat::Yaml),
+            "toml" => Some(InputFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Inspects already-decompressed input of the given format. Columnar formats
+/// (Parquet, Excel) need random access and are only read from files.
+pub fn inspect_reader<R: Read>(
+    reader: R,
+    format: InputFormat,
+    opts: &InspectOptions,
+) -> Result<Report, InspectError> {
+    read_input(reader, format, opts, None, "input")
+}
+
+// Memory planning for --dry-run: the first rows of the file are inspected and
+// the tracked state scaled up to the projected row count. Distinct values are
+// projected with Heaps' law (d = n^b, fitted to the sample), so a column that
+// keeps producing new values grows almost linearly; one whose values repeat a
+// hundred times over in the sample is taken to have seen them all. Only
+// line-based formats are sampled: JSON arrays, YAML and TOML are parsed whole
+// whatever the limit.
+
+const DRY_RUN_ROWS: usize = 5_000;
+
+// a tracked distinct value: its text plus the String, the count and the hash
+// map's bucket and spare capacity
+const UNIQUE_ENTRY_BYTES: u64 = 64;
+// below this share of distinct values the column's categories are all known
+const SATURATED_DISTINCT_SHARE: f64 = 0.01;
+// a duplicate-row hash in the HashSet, with its spare capacity
+const ROW_HASH_BYTES: u64 = 16;
+
+/// Projected memory use of a full inspection, from a sample of the file.
+#[derive(Debug, Serialize)]
+pub struct MemoryEstimate {
+    pub sampled_rows: usize,
+    // true when the sample covered the whole file, so the rows are exact
+    pub complete: bool,
+    pub file_bytes: u64,
+    pub projected_rows: usize,
+    // duplicate-row detection
+    pub row_hashes: u64,
+    // numeric values buffered for percentiles, histograms and outliers
+    pub value_buffers: u64,
+    pub columns: Vec<ColumnMemory>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnMemory {
+    pub name: String,
+    pub sampled_distinct: usize,
+    pub projected_distinct: usize,
+    // --max-uniques (or --approx-distinct) stops the map before that
+    pub capped: bool,
+    pub bytes: u64,
+}
+
+/// Samples the first rows of a CSV, TSV or JSON Lines file and projects how
+/// much memory inspecting all of it with `opts` would take.
+pub fn estimate_memory(filename: &str, opts: &InspectOptions) -> Result<MemoryEstimate, InspectError> {
+    let format = row_format(filename, opts)?;
+    if !matches!(format, InputFormat::Csv | InputFormat::Tsv | InputFormat::JsonLines) {
+        return Err(InspectError::Usage(
+            "--dry-run samples CSV, TSV and JSON Lines input".to_string(),
+        ));
+    }
+    let file_bytes = open_file(filename)?.metadata().map_err(io_error(filename))?.len();
+
+    let mut sample_opts = opts.clone();
+    let sample_limit = opts.limit.unwrap_or(usize::MAX).min(DRY_RUN_ROWS);
+    sample_opts.limit = Some(sample_limit);
+    // the window's rows are taken to look like the first ones
+    sample_opts.skip = 0;
+    // a hidden bar still counts the bytes read, compressed ones for .gz
+    let counter = ProgressBar::hidden();
+    let input = open_input(filename, Some(&counter))?;
+    let report = read_input(input, format, &sample_opts, None, filename)?;
+    let sampled_bytes = counter.position().max(1);
+
+    let sampled_rows = report.rows;
+    // only a reader that ran out of rows before the limit saw the whole file;
+    // the bytes read say nothing, a buffered reader reads ahead
+    let complete = sampled_rows < sample_limit;
+    let projected_rows = if complete {
+        sampled_rows
+    } else {
+        let projected = (sampled_rows as f64 * file_bytes as f64 / sampled_bytes as f64) as usize;
+        projected.saturating_sub(opts.skip).min(opts.limit.unwrap_or(usize::MAX))
+    };
+    let scale = projected_rows as f64 / sampled_rows.max(1) as f64;
+
+    let cap = match (opts.max_uniques, opts.approx_distinct) {
+        (Some(cap), _) => Some(cap),
+        (None, true) => Some(APPROX_TRACKED_VALUES),
+        (None, false) => None,
+    };
+    let mut value_buffers = 0;
+    let mut columns = Vec::new();
+    for column in &report.columns {
+        let Some(summary) = &column.summary else {
+            continue;
+        };
+        let values = summary.count as f64 * scale;
+        if column.kind == Some(ColumnType::Numeric) {
+            if opts.buffers_values() {
+                value_buffers += values as u64 * 8;
+            }
+            continue;
+        }
+        let Some(distinct) = summary.unique else {
+            continue;
+        };
+        let saturated = (distinct as f64) < summary.count as f64 * SATURATED_DISTINCT_SHARE;
+        let projected = if complete || saturated || distinct <= 1 || summary.count <= 1 {
+            distinct
+        } else {
+            let exponent = (distinct as f64).ln() / (summary.count as f64).ln();
+            (values.powf(exponent) as usize).max(distinct)
+        };
+        let capped = cap.is_some_and(|cap| projected > cap);
+        let tracked = cap.map_or(projected, |cap| projected.min(cap));
+        let length = summary.avg_length.unwrap_or(20.0).ceil() as u64;
+        columns.push(ColumnMemory {
+            name: column.name.clone(),
+            sampled_distinct: distinct,
+            projected_distinct: projected,
+            capped,
+            bytes: tracked as u64 * (length + UNIQUE_ENTRY_BYTES),
+        });
+    }
+
+    let row_hashes = projected_rows as u64 * ROW_HASH_BYTES;
+    let total_bytes = row_hashes + value_buffers + columns.iter().map(|c| c.bytes).sum::<u64>();
+    Ok(MemoryEstimate {
+        sampled_rows,
+        complete,
+        file_bytes,
+        projected_rows,
+        row_hashes,
+        value_buffers,
+        columns,
+        total_bytes,
+    })
+}
+
+/// Row and column counts of a file, for --count-only.
+#[derive(Debug, Serialize)]
+pub struct RowCount {
+    pub file_type: &'static str,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+/// Counts the rows and columns of a file without computing any column stats.
+/// CSV and TSV records are only split into fields, honoring quotes, so a
+/// quoted newline doesn't start a row; other formats are inspected in full
+/// and reduced to their counts.
+pub fn count_rows(filename: &str, opts: &InspectOptions) -> Result<RowCount, InspectError> {
+    let format = match row_format(filename, opts) {
+        Ok(format @ (InputFormat::Csv | InputFormat::Tsv)) if !is_url(filename) => format,
+        _ => {
+            let report = inspect_file(filename, opts)?;
+            return Ok(RowCount {
+                file_type: report.file_type,
+                rows: report.rows,
+                columns: report.columns.len(),
+            });
+        }
+    };
+
+    let mut opts = opts.clone();
+    if format == InputFormat::Tsv {
+        opts.delimiter.get_or_insert(b'\t');
+    }
+    let progress = start_progress(filename, &opts);
+    let mut input = BufReader::new(decode_input(open_input(filename, progress.as_ref())?, &opts)?);
+    if input.fill_buf().map_err(io_error(filename))?.is_empty() {
+        return Err(InspectError::EmptyInput(filename.to_string()));
+    }
+    let mut reader = csv_builder(&opts)
+        .has_headers(!opts.no_header)
+        .flexible(!opts.strict)
+        .from_reader(input);
+    let columns = reader.byte_headers()?.len();
+
+    // --skip and --limit still pick the window that gets counted
+    let end = opts.skip.saturating_add(opts.limit.unwrap_or(usize::MAX));
+    let mut record = csv::ByteRecord::new();
+    let mut rows = 0;
+    while rows < end && reader.read_byte_record(&mut record)? {
+        rows += 1;
+        report_rows(progress.as_ref(), rows);
+    }
+    if let Some(bar) = progress {
+        bar.finish_and_clear();
+    }
+
+    Ok(RowCount {
+        file_type: "CSV",
+        rows: rows.saturating_sub(opts.skip),
+        columns,
+    })
+}
+
+// `name` labels read errors: the file name, or "input" for a bare reader
+fn read_input(
+    input: impl Read,
+    format: InputFormat,
+    opts: &InspectOptions,
+    progress: Option<&ProgressBar>,
+    name: &str,
+) -> Result<Report, InspectError> {
+    if opts.group_by.is_some() && !matches!(format, InputFormat::Csv | InputFormat::Tsv | InputFormat::FixedWidth) {
+        return Err(group_by_unsupported());
+    }
+    let mut input = BufReader::new(decode_input(input, opts)?);
+    if input.fill_buf().map_err(io_error(name))?.is_empty() {
+        return Err(InspectError::EmptyInput(name.to_string()));
+    }
+    match format {
+        InputFormat::Csv => read_csv(input, opts, progress),
+        InputFormat::Tsv => {
+            let mut opts = opts.clone();
+            opts.delimiter.get_or_insert(b'\t');
+            read_csv(input, &opts, progress)
+        }
+        InputFormat::Json => read_json(input, opts, progress),
+        InputFormat::JsonLines => read_jsonl(input, opts, progress, name),
+        InputFormat::Yaml => read_yaml(input, opts, name),
+        InputFormat::Toml => read_toml(input, opts, name),
+        InputFormat::FixedWidth => read_fixed_width(input, opts, progress, name),
+    }
+}
+
+#[derive(Debug)]
+pub enum InspectError {
+    Io { path: String, source: io::Error },
+    Output { path: String, source: io::Error },
+    Csv(csv::Error),
+    Json(serde_json::Error),
+    JsonLine { line: usize, message: String },
+    UnsupportedJson,
+    Yaml(serde_yaml::Error),
+    Toml(toml::de::Error),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+    #[cfg(any(feature = "parquet", feature = "arrow"))]
+    Arrow(arrow_schema::ArrowError),
+    #[cfg(feature = "http")]
+    Http(reqwest::Error),
+    #[cfg(feature = "http")]
+    HttpStatus { url: String, status: String },
+    #[cfg(feature = "xlsx")]
+    Xlsx(calamine::XlsxError),
+    #[cfg(feature = "xlsx")]
+    UnknownSheet { name: String, available: Vec<String> },
+    UnknownColumn(String),
+    EmptyInput(String),
+    // a --coerce numeric column with too many values that don't parse
+    CoercionFailed { column: String, kind: CoercedType, failures: usize, values: usize, example: Option<(usize, String)> },
+    TooManyGroups { column: String, limit: usize },
+    // a record whose field count differs from the header, found while reading in chunks
+    FieldCount { record: u64, line: u64, byte: u64, expected: usize, found: usize },
+    Usage(String),
+    ChecksFailed { count: usize, level: Severity },
+    SchemaMismatch(usize),
+    StatsDiffer(usize),
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InspectError::Io { path, source } => match source.kind() {
+                io::ErrorKind::NotFound => write!(f, "file not found: {}", path),
+                io::ErrorKind::PermissionDenied => write!(f, "permission denied: {}", path),
+                _ => write!(f, "failed to read {}: {}", path, source),
+            },
+            InspectError::Output { path, source } => write!(f, "failed to write {}: {}", path, source),
+            InspectError::Csv(err) => match err.kind() {
+                // e.g. a corrupt gzip stream underneath the CSV reader
+                csv::ErrorKind::Io(source) => write!(f, "failed to read input: {}", source),
+                csv::ErrorKind::Utf8 { pos, err } => write!(
+                    f,
+                    "invalid UTF-8 in record {} (line {}, field {}); pass --encoding for other encodings, e.g. --encoding windows-1252",
+                    pos.as_ref().map_or(0, csv::Position::record),
+                    pos.as_ref().map_or(0, csv::Position::line),
+                    err.field() + 1
+                ),
+                _ => write!(f, "{}", err),
+            },
+            // streamed JSON surfaces read errors (e.g. corrupt gzip) as JSON errors
+            InspectError::Json(err) if err.is_io() => write!(f, "failed to read input: {}", err),
+            InspectError::Json(err) => write!(f, "invalid JSON: {}", err),
+            InspectError::JsonLine { line, message } => {
+                write!(f, "invalid JSON on line {}: {}", line, message)
+            }
+            InspectError::UnsupportedJson => {
+                write!(f, "unsupported JSON structure (expected an object or an array)")
+            }
+            InspectError::Yaml(err) => write!(f, "invalid YAML: {}", err),
+            InspectError::Toml(err) => write!(f, "invalid TOML: {}", err.message()),
+            #[cfg(feature = "parquet")]
+            InspectError::Parquet(err) => write!(f, "invalid Parquet file: {}", err),
+            #[cfg(any(feature = "parquet", feature = "arrow"))]
+            InspectError::Arrow(err) => write!(f, "{}", err),
+            #[cfg(feature = "http")]
+            InspectError::Http(err) => {
+                // the useful part (DNS, TLS, refused connection) is in the source chain
+                write!(f, "request failed: {}", err)?;
+                let mut source = std::error::Error::source(err);
+                while let Some(cause) = source {
+                    write!(f, ": {}", cause)?;
+                    source = cause.source();
+                }
+                Ok(())
+            }
+            #[cfg(feature = "http")]
+            InspectError::HttpStatus { url, status } => write!(f, "{} returned HTTP {}", url, status),
+            #[cfg(feature = "xlsx")]
+            InspectError::Xlsx(err) => write!(f, "invalid Excel file: {}", err),
+            #[cfg(feature = "xlsx")]
+            InspectError::UnknownSheet { name, available } => write!(
+                f,
+                "unknown sheet: {} (available: {})",
+                name,
+                available.join(", ")
+            ),
+            InspectError::UnknownColumn(name) => write!(f, "unknown column: {}", name),
+            InspectError::EmptyInput(name) => write!(f, "{}: empty file, nothing to inspect", name),
+            InspectError::CoercionFailed { column, kind, failures, values, example } => {
+                write!(
+                    f,
+                    "--coerce {}={}: {} of {} values ({:.1}%) do not parse as {}",
+                    column,
+                    kind.as_str(),
+                    failures,
+                    values,
+                    *failures as f64 / *values as f64 * 100.0,
+                    kind.values_name()
+                )?;
+                if let Some((row, value)) = example {
+                    write!(f, ", e.g. {:?} on row {}", value, row)?;
+                }
+                write!(f, " (lower --infer-threshold to allow more)")
+            }
+            InspectError::TooManyGroups { column, limit } => write!(
+                f,
+                "--group-by {}: more than {} distinct values (group by a column with fewer)",
+                column, limit
+            ),
+            // worded like the csv crate's own error for the sequential reader
+            InspectError::FieldCount { record, line, byte, expected, found } => write!(
+                f,
+                "CSV error: record {} (line: {}, byte: {}): found record with {} fields, but the previous record has {} fields",
+                record, line, byte, found, expected
+            ),
+            InspectError::Usage(msg) => write!(f, "{}", msg),
+            InspectError::ChecksFailed { count, level } => write!(
+                f,
+                "{} diagnostic{} at or above {} level",
+                count,
+                if *count == 1 { "" } else { "s" },
+                level.as_str()
+            ),
+            InspectError::SchemaMismatch(files) => write!(
+                f,
+                "schema differs in {} file{}",
+                files,
+                if *files == 1 { "" } else { "s" }
+            ),
+            InspectError::StatsDiffer(count) => write!(
+                f,
+                "{} difference{} beyond the tolerance",
+                count,
+                if *count == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InspectError {}
+
+impl From<csv::Error> for InspectError {
+    fn from(err: csv::Error) -> Self {
+        InspectError::Csv(err)
+    }
+}
+
+impl From<serde_json::Error> for InspectError {
+    fn from(err: serde_json::Error) -> Self {
+        InspectError::Json(err)
+    }
+}
+
+impl From<serde_yaml::Error> for InspectError {
+    fn from(err: serde_yaml::Error) -> Self {
+        InspectError::Yaml(err)
+    }
+}
+
+impl From<toml::de::Error> for InspectError {
+    fn from(err: toml::de::Error) -> Self {
+        InspectError::Toml(err)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for InspectError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        InspectError::Parquet(err)
+    }
+}
+
+#[cfg(any(feature = "parquet", feature = "arrow"))]
+impl From<arrow_schema::ArrowError> for InspectError {
+    fn from(err: arrow_schema::ArrowError) -> Self {
+        InspectError::Arrow(err)
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<reqwest::Error> for InspectError {
+    fn from(err: reqwest::Error) -> Self {
+        InspectError::Http(err)
+    }
+}
+
+#[cfg(feature = "xlsx")]
+impl From<calamine::XlsxError> for InspectError {
+    fn from(err: calamine::XlsxError) -> Self {
+        InspectError::Xlsx(err)
+    }
+}
+
+impl InspectError {
+    /// Process exit status for the error: 2 for empty input, so pipelines
+    /// can tell "nothing arrived" apart from a failure, and 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            InspectError::EmptyInput(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+fn io_error(path: &str) -> impl FnOnce(io::Error) -> InspectError + '_ {
+    move |source| InspectError::Io {
+        path: path.to_string(),
+        source,
+    }
+}
+
+// Transcodes to UTF-8 from --encoding, or from the encoding a UTF-8/UTF-16 BOM
+// names (which wins when both are there). Without either, bytes go through
+// untouched and invalid UTF-8 is an error downstream rather than silently
+// replaced.
+fn decode_input<R: Read>(input: R, opts: &InspectOptions) -> Result<DecodeReaderBytes<R, Vec<u8>>, InspectError> {
+    let encoding = match &opts.encoding {
+        Some(label) => Some(
+            Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| InspectError::Usage(format!("unknown encoding: {}", label)))?,
+        ),
+        None => None,
+    };
+    Ok(DecodeReaderBytesBuilder::new()
+        .encoding(encoding)
+        .bom_override(true)
+        .utf8_passthru(true)
+        .strip_bom(true)
+        .build(input))
+}
+
+fn open_file(filename: &str) -> Result<File, InspectError> {
+    File::open(filename).map_err(io_error(filename))
+}
+
+// row-oriented input, transparently gunzipped when the name ends in .gz
+// The progress bar counts bytes read from disk, so for .gz input it tracks
+// the compressed size and the percentage stays accurate.
+fn open_input(filename: &str, progress: Option<&ProgressBar>) -> Result<Box<dyn Read>, InspectError> {
+    let file: Box<dyn Read> = match progress {
+        Some(bar) => Box::new(bar.wrap_read(open_file(filename)?)),
+        None => Box::new(open_file(filename)?),
+    };
+    if filename.ends_with(".gz") {
+        Ok(Box::new(MultiGzDecoder::new(BufReader::new(file))))
+    } else {
+        Ok(file)
+    }
+}
+
+// smaller files are done before a progress bar would tell anyone anything
+const PROGRESS_MIN_BYTES: u64 = 64 * 1024 * 1024;
+
+// Progress bar on stderr for large inputs, only when stderr is a terminal so
+// redirected output and CI logs stay clean.
+fn start_progress(filename: &str, opts: &InspectOptions) -> Option<ProgressBar> {
+    if opts.no_progress || !io::stderr().is_terminal() {
+        return None;
+    }
+
+    let size = std::fs::metadata(filename).ok()?.len();
+    if size < PROGRESS_MIN_BYTES {
+        return None;
+    }
+
+    let bar = ProgressBar::new(size);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "[{bar:30}] {percent:>3}% {bytes}/{total_bytes} {msg} (eta {eta})",
+        )
+        .expect("valid progress template")
+        .progress_chars("=> "),
+    );
+    Some(bar)
+}
+
+// updating the message on every row would dominate the loop
+fn report_rows(progress: Option<&ProgressBar>, rows: usize) {
+    if let Some(bar) = progress
+        && rows.is_multiple_of(10_000)
+    {
+        bar.set_message(format!("{} rows", rows));
+    }
+}
+
+/// Everything that shapes a report, from the analysis settings to how the
+/// text report is printed. `Default` matches the CLI defaults.
+#[derive(Debug, Clone)]
+pub struct InspectOptions {
+    pub show_types: bool,
+    pub show_summary: bool,
+    pub show_percentiles: bool,
+    pub show_diagnose: bool,
+    pub json: bool,
+    pub delimiter: Option<u8>,
+    // CSV dialect: the quote character (`"` by default), an escape character
+    // for quotes inside quoted fields (instead of doubling them), and whether
+    // quotes are special at all
+    pub quote: Option<u8>,
+    pub escape: Option<u8>,
+    pub no_quoting: bool,
+    pub top: Option<usize>,
+    pub max_uniques: Option<usize>,
+    pub columns: Option<String>,
+    pub exclude_columns: Option<String>,
+    pub infer_sample: Option<usize>,
+    pub infer_threshold: f64,
+    // --coerce: columns whose type is given instead of inferred
+    pub coerce: Vec<(String, CoercedType)>,
+    // --validate: columns whose every value must match a pattern
+    pub validate: Vec<(String, Regex)>,
+    pub outlier_sigma: f64,
+    pub missing_threshold: f64,
+    pub cardinality_threshold: f64,
+    // JSON fields present in fewer than this share of the records are sparse
+    pub sparse_threshold: f64,
+    // numeric columns with a larger share of zeros are zero-inflated
+    pub zero_threshold: f64,
+    pub limit: Option<usize>,
+    // records passed over before the first inspected one; with limit, a
+    // window into the file
+    pub skip: usize,
+    pub histogram_bins: Option<usize>,
+    pub no_header: bool,
+    pub correlate: bool,
+    pub fail_on: Option<Severity>,
+    // worksheet to read from .xlsx input; the first one by default
+    pub sheet: Option<String>,
+    pub quiet: bool,
+    pub color: bool,
+    pub max_length: Option<usize>,
+    pub no_progress: bool,
+    pub compare_schema: bool,
+    pub sample_rows: Option<usize>,
+    // nested JSON objects become dotted columns (`user.name`) down to this
+    // many levels; deeper ones keep their JSON text. No limit when unset, and
+    // 0 leaves every object as text
+    pub max_depth: Option<usize>,
+    // n rows drawn uniformly from the whole input, and the seed for the draw
+    // (from the clock when unset)
+    pub random_sample: Option<usize>,
+    pub seed: Option<u64>,
+    // the last n rows, kept in a ring buffer so pipes work too
+    pub tail_rows: Option<usize>,
+    pub outlier_method: OutlierMethod,
+    // sentinels treated like empty cells, on top of the empty string itself
+    pub na_values: Vec<String>,
+    pub output: Option<String>,
+    pub clean_numbers: bool,
+    // `,` for numbers written 1.234,56, which swaps the thousands separator
+    // to `.`; the default `.` leaves plain numbers as they are
+    pub decimal_separator: char,
+    // symbols --clean-numbers strips before or after a number
+    pub currency_symbols: Vec<char>,
+    // characters accepted between groups of three digits; by default `,`,
+    // or `.` with a comma decimal separator
+    pub thousands_separators: Option<Vec<char>>,
+    // overrides the file extension, e.g. for URLs without one
+    pub format: Option<InputFormat>,
+    // --fixed-width: `name:start:length` fields (0-based character offsets),
+    // comma-separated, sliced out of each line; the cells are trimmed unless
+    // no_trim is set
+    pub fixed_width: Option<String>,
+    pub no_trim: bool,
+    // summary as an aligned table instead of one line per column
+    pub pretty: bool,
+    // estimate categorical cardinality with a HyperLogLog sketch
+    pub approx_distinct: bool,
+    // worker threads for plain CSV/TSV files; 1 reads sequentially
+    pub threads: usize,
+    // a CSV row with more or fewer fields than the header is an error
+    // rather than a diagnostic
+    pub strict: bool,
+    // encoding label of row-oriented input (e.g. "windows-1252"); UTF-8, or
+    // whatever a BOM says, when unset
+    pub encoding: Option<String>,
+    // tally how every value parses, to show why each column got its type
+    pub explain: bool,
+    // names from CHECKS: when only_checks is non-empty just those run, and
+    // disabled_checks never do
+    pub only_checks: Vec<String>,
+    pub disabled_checks: Vec<String>,
+    // column (name or 0-based index) whose values split CSV rows into groups,
+    // each summarized on its own
+    pub group_by: Option<String>,
+    // an order-independent hash of each column's values
+    pub fingerprint: bool,
+    // compare the leading digits of numeric columns with Benford's law
+    pub benford: bool,
+    // indented JSON instead of one line per document
+    pub json_pretty: bool,
+    // plausible datetimes, as epoch seconds (see parse_timestamp); 1970-01-01
+    // and a year from now when unset
+    pub date_min: Option<i64>,
+    pub date_max: Option<i64>,
+    // numeric values outside these bounds are flagged; no check when unset
+    pub range_min: Option<f64>,
+    pub range_max: Option<f64>,
+}
+
+impl Default for InspectOptions {
+    fn default() -> Self {
+        Self {
+            show_types: false,
+            show_summary: false,
+            show_percentiles: false,
+            show_diagnose: false,
+            json: false,
+            delimiter: None,
+            quote: None,
+            escape: None,
+            no_quoting: false,
+            top: None,
+            max_uniques: None,
+            columns: None,
+            exclude_columns: None,
+            infer_sample: None,
+            coerce: Vec::new(),
+            validate: Vec::new(),
+            infer_threshold: 0.9,
+            outlier_sigma: 5.0,
+            missing_threshold: 0.05,
+            cardinality_threshold: 0.95,
+            sparse_threshold: 0.1,
+            zero_threshold: 0.9,
+            limit: None,
+            skip: 0,
+            histogram_bins: None,
+            no_header: false,
+            correlate: false,
+            fail_on: None,
+            sheet: None,
+            quiet: false,
+            color: false,
+            max_length: None,
+            no_progress: false,
+            compare_schema: false,
+            sample_rows: None,
+            max_depth: None,
+            random_sample: None,
+            seed: None,
+            tail_rows: None,
+            outlier_method: OutlierMethod::ZScore,
+            na_values: Vec::new(),
+            output: None,
+            clean_numbers: false,
+            decimal_separator: '.',
+            currency_symbols: CURRENCY_SYMBOLS.to_vec(),
+            thousands_separators: None,
+            format: None,
+            fixed_width: None,
+            no_trim: false,
+            pretty: false,
+            approx_distinct: false,
+            threads: 1,
+            strict: false,
+            encoding: None,
+            explain: false,
+            only_checks: Vec::new(),
+            disabled_checks: Vec::new(),
+            group_by: None,
+            fingerprint: false,
+            benford: false,
+            json_pretty: false,
+            date_min: None,
+            date_max: None,
+            range_min: None,
+            range_max: None,
+        }
+    }
+}
+
+impl InspectOptions {
+    // --only-check and --disable-check
+    fn runs_check(&self, check: &str) -> bool {
+        (self.only_checks.is_empty() || self.only_checks.iter().any(|c| c == check))
+            && !self.disabled_checks.iter().any(|c| c == check)
+    }
+
+    // --date-min and --date-max, with their defaults filled in
+    fn date_bounds(&self) -> (i64, i64) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        (
+            self.date_min.unwrap_or(0),
+            self.date_max.unwrap_or(now + 365 * 86_400),
+        )
+    }
+
+    // percentiles, histograms and the outlier pass all need every numeric value; the
+    // plain summary streams with constant memory per column
+    fn buffers_values(&self) -> bool {
+        self.show_percentiles
+            || self.show_diagnose
+            || self.json
+            || self.histogram_bins.is_some()
+            || self.fail_on.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Numeric,
+    Categorical,
+    DateTime,
+    Boolean,
+}
+
+impl ColumnType {
+    pub const ALL: [ColumnType; 4] = [
+        ColumnType::Numeric,
+        ColumnType::Categorical,
+        ColumnType::DateTime,
+        ColumnType::Boolean,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColumnType::Numeric => "numeric",
+            ColumnType::Categorical => "categorical",
+            ColumnType::DateTime => "datetime",
+            ColumnType::Boolean => "boolean",
+        }
+    }
+}
+
+// share of non-empty values that must parse as dates for a datetime column
+const DATETIME_MAJORITY: f64 = 0.9;
+
+/// A type forced on a column with --coerce, bypassing inference. Integer and
+/// float are numeric columns that also fix the reported numeric type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoercedType {
+    Numeric,
+    Integer,
+    Float,
+    Categorical,
+    Boolean,
+    DateTime,
+}
+
+impl CoercedType {
+    pub const ALL: [CoercedType; 6] = [
+        CoercedType::Numeric,
+        CoercedType::Integer,
+        CoercedType::Float,
+        CoercedType::Categorical,
+        CoercedType::Boolean,
+        CoercedType::DateTime,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CoercedType::Numeric => "numeric",
+            CoercedType::Integer => "integer",
+            CoercedType::Float => "float",
+            CoercedType::Categorical => "categorical",
+            CoercedType::Boolean => "boolean",
+            CoercedType::DateTime => "datetime",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<CoercedType> {
+        CoercedType::ALL.into_iter().find(|t| t.as_str() == name)
+    }
+
+    fn column_type(self) -> ColumnType {
+        match self {
+            CoercedType::Numeric | CoercedType::Integer | CoercedType::Float => ColumnType::Numeric,
+            CoercedType::Categorical => ColumnType::Categorical,
+            CoercedType::Boolean => ColumnType::Boolean,
+            CoercedType::DateTime => ColumnType::DateTime,
+        }
+    }
+
+    // what the values of a numeric coercion failed to parse as
+    fn values_name(self) -> &'static str {
+        match self {
+            CoercedType::Integer => "integers",
+            _ => "numbers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierMethod {
+    // |x - mean| / stddev at or above --outlier-sigma
+    ZScore,
+    // outside the Tukey fences Q1 - 1.5·IQR and Q3 + 1.5·IQR
+    Iqr,
+}
+
+// share of a categorical column's values that must match one pattern for
+// the column to be reported as holding it
+const SEMANTIC_MAJORITY: f64 = 0.9;
+
+/// What the text of a categorical column appears to be, from simple pattern
+/// checks on each value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemanticType {
+    Email,
+    Url,
+    Uuid,
+    Ip,
+    // integers too large for a float, so kept as text: almost always IDs
+    #[serde(rename = "large-integer")]
+    LargeInteger,
+}
+
+impl SemanticType {
+    pub const ALL: [SemanticType; 5] = [
+        SemanticType::Email,
+        SemanticType::Url,
+        SemanticType::Uuid,
+        SemanticType::Ip,
+        SemanticType::LargeInteger,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SemanticType::Email => "email",
+            SemanticType::Url => "url",
+            SemanticType::Uuid => "uuid",
+            SemanticType::Ip => "ip address",
+            SemanticType::LargeInteger => "large integer id",
+        }
+    }
+
+    fn detect(value: &str) -> Option<SemanticType> {
+        if is_email(value) {
+            Some(SemanticType::Email)
+        } else if is_web_address(value) {
+            Some(SemanticType::Url)
+        } else if is_uuid(value) {
+            Some(SemanticType::Uuid)
+        } else if value.parse::<std::net::IpAddr>().is_ok() {
+            Some(SemanticType::Ip)
+        } else if is_large_integer(value) {
+            Some(SemanticType::LargeInteger)
+        } else {
+            None
+        }
+    }
+}
+
+// local@domain.tld: one @, no spaces, and a dotted domain ending in letters
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty()
+        && local.chars().all(|c| c.is_alphanumeric() || "._%+-'".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    local_ok
+        && labels.len() >= 2
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_alphanumeric() || c == '-'))
+        && labels[labels.len() - 1].len() >= 2
+        && labels[labels.len() - 1].chars().all(char::is_alphabetic)
+}
+
+// an http(s) or ftp scheme followed by a host, with no whitespace anywhere
+fn is_web_address(value: &str) -> bool {
+    let lower = value.get(..8).unwrap_or(value).to_ascii_lowercase();
+    let rest = ["https://", "http://", "ftp://"]
+        .iter()
+        .find(|scheme| lower.starts_with(*scheme))
+        .map(|scheme| &value[scheme.len()..]);
+    rest.is_some_and(|rest| {
+        let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+        !host.is_empty() && !value.chars().any(char::is_whitespace)
+    })
+}
+
+// 8-4-4-4-12 hex digits
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(g, len)| g.len() == len && g.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+// unparsable values remembered per column, to point at the offending rows
+const MAX_FAILURE_EXAMPLES: usize = 10;
+
+// at most this share of negative numbers in a column reads as stray errors
+// (a negative age or quantity) rather than legitimately signed data
+const STRAY_NEGATIVE_SHARE: f64 = 0.05;
+
+#[derive(Debug)]
+pub struct ColumnStats {
+    name: String,
+    kind: ColumnType,
+
+    total: usize,
+    missing: usize,
+
+    // num stats 
+    count: usize, // values that parsed as numbers
+    integers_only: bool, // every number so far parsed as an i64
+    min: Option<f64>,
+    max: Option<f64>, 
+    mean: f64,
+    m2: f64, 
+    // third and fourth central moment sums, for skewness and kurtosis
+    m3: f64,
+    m4: f64,
+    // sign of each number (NaN is none of them), and (1-based row, value) of
+    // the first MAX_FAILURE_EXAMPLES negative ones
+    negatives: usize,
+    zeros: usize,
+    positives: usize,
+    negative_examples: Vec<(usize, f64)>,
+
+    // categorical stats: value -> frequency
+    uniques: HashMap<String, usize>,
+    max_uniques: Option<usize>,
+    // occurrences of values not tracked once max_uniques was reached
+    other_count: usize,
+    // distinct-count estimate used instead of uniques.len() with --approx-distinct
+    sketch: Option<HyperLogLog>,
+    // string lengths in characters, over every non-numeric value
+    max_length: usize,
+    total_length: usize,
+    // extremes of the non-numeric values: by code point order, and by length
+    // in characters (the first one seen on ties)
+    min_text: Option<String>,
+    max_text: Option<String>,
+    shortest: Option<String>,
+    longest: Option<String>,
+
+    // datetime candidates among categorical values, as epoch seconds
+    date_count: usize,
+    min_date: Option<i64>,
+    max_date: Option<i64>,
+    date_has_time: bool,
+    // some value carried Z or an offset, so the timestamps are UTC
+    date_has_zone: bool,
+    // calendar days (UTC) with at least one date, for gaps in the range
+    date_days: HashSet<i64>,
+
+    // boolean-looking values (true/false, yes/no, 1/0, t/f), whatever the type
+    true_count: usize,
+    false_count: usize,
+
+    //diagnostics helpers
+    numeric_parse_failures: usize,
+    // (1-based data row, raw value) of the first MAX_FAILURE_EXAMPLES failures
+    failure_examples: Vec<(usize, String)>,
+    // numbers past ±2^53, which would lose precision as f64 and are left
+    // out of the stats, and the first one with its row
+    large_integers: usize,
+    large_integer_example: Option<(usize, String)>,
+    // of those, the ones that don't fit in an i64 either
+    large_integers_past_i64: usize,
+    // non-missing values dropped by the categorical → numeric upgrade, which
+    // happens on the row of the first number
+    before_promotion: usize,
+    first_number: Option<usize>,
+    // the first values seen while categorical, in the same form as
+    // failure_examples: they become failures when merged after numeric rows
+    text_examples: Vec<(usize, String)>,
+    // how each value parses on its own, kept for --explain
+    evidence: Option<TypeEvidence>,
+    // values that had NUL bytes stripped, and the first one as it was read
+    null_bytes: usize,
+    null_byte_example: Option<String>,
+    // order-independent digest of every value, kept for --fingerprint
+    fingerprint: Option<ContentFingerprint>,
+    // leading digits of the positive numbers, kept for --benford
+    benford: Option<BenfordTally>,
+    // what the field held in each record, for JSON-like input
+    json_kinds: Option<JsonKinds>,
+
+    //outliers 
+    outlier_count: usize,
+    outlier_method: OutlierMethod,
+    outlier_sigma: f64,
+    // Tukey fences, set when the IQR method ran
+    outlier_fences: Option<(f64, f64)>,
+    min_z: Option<f64>,
+    max_z: Option<f64>,
+
+    // buffered numeric values, only kept when percentiles or outlier
+    // detection need the full distribution
+    values: Option<Vec<f64>>,
+
+    // sampled type inference: the first values are held back until the
+    // column type is decided by majority, then replayed
+    sample: Option<Vec<(String, Option<String>)>>,
+    sample_size: usize,
+    infer_threshold: f64,
+    type_locked: bool,
+    // set by --coerce, which also locks the type
+    coerced: Option<CoercedType>,
+    na_values: Vec<String>,
+    // --validate: the pattern non-missing values must match, how many don't
+    // and (1-based data row, value) of the first few
+    validator: Option<Regex>,
+    validation_failures: usize,
+    validation_examples: Vec<(usize, String)>,
+
+    // row order of the non-missing numbers and dates, in the order seen
+    number_order: ValueOrder,
+    date_order: ValueOrder,
+
+    // zero-padded codes ("007", "012"): every value all digits, one shared
+    // width, and at least one leading zero
+    code_width: Option<usize>,
+    digits_only: bool,
+    leading_zero: bool,
+
+    // text values matching each SemanticType, in SemanticType::ALL order
+    semantic_counts: [usize; 5],
+
+    // dates outside the plausible range and numbers outside the expected
+    // bounds, with the first MAX_FAILURE_EXAMPLES of each
+    date_range: Option<(i64, i64)>,
+    dates_out_of_range: usize,
+    date_range_examples: Vec<(usize, i64)>,
+    value_range: (Option<f64>, Option<f64>),
+    out_of_range: usize,
+    range_examples: Vec<(usize, f64)>,
+}
+
+// Whether a column's values only ever go up, e.g. an auto-increment id or a
+// time index. Missing values are skipped.
+#[derive(Debug, Clone, Copy)]
+struct ValueOrder {
+    first: Option<f64>,
+    last: Option<f64>,
+    increasing: bool,
+    non_decreasing: bool,
+}
+
+impl Default for ValueOrder {
+    fn default() -> Self {
+        Self {
+            first: None,
+            last: None,
+            increasing: true,
+            non_decreasing: true,
+        }
+    }
+}
+
+impl ValueOrder {
+    fn update(&mut self, x: f64) {
+        if let Some(last) = self.last {
+            self.increasing &= x > last;
+            self.non_decreasing &= x >= last;
+        }
+        self.first = self.first.or(Some(x));
+        self.last = Some(x);
+    }
+
+    // `other` covers the values right after these
+    fn merge(&mut self, other: &ValueOrder) {
+        let (Some(last), Some(first)) = (self.last, other.first) else {
+            if self.last.is_none() {
+                *self = *other;
+            }
+            return;
+        };
+        self.increasing &= other.increasing && first > last;
+        self.non_decreasing &= other.non_decreasing && first >= last;
+        self.last = other.last;
+    }
+
+    fn as_str(&self) -> &'static str {
+        if self.increasing {
+            "increasing"
+        } else if self.non_decreasing {
+            "non-decreasing"
+        } else {
+            "unordered"
+        }
+    }
+}
+
+// HyperLogLog distinct-count sketch: 2^14 one-byte registers (16 KiB per
+// column) whatever the number of values, at about 0.8% standard error.
+const HLL_PRECISION: u32 = 14;
+
+// values still counted exactly (for mode, --top, and the distinct count while
+// below it) when the sketch is on and no --max-uniques is given
+const APPROX_TRACKED_VALUES: usize = 10_000;
+
+#[derive(Debug, Clone)]
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+
+    fn insert(&mut self, value: &str) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // the top bits pick the register, the rest give the run of zeros
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        // linear counting is more accurate while many registers are still empty
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as usize
+        } else {
+            raw.round() as usize
+        }
+    }
+
+    // the union of both value sets
+    fn merge(&mut self, other: &HyperLogLog) {
+        for (register, &rank) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(rank);
+        }
+    }
+
+    // relative standard error of the estimate
+    fn error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+}
+
+// Order-independent digest of a column's values: the wrapping sum of one
+// 64-bit hash per value, so the same values in any row order (duplicates
+// included) give the same fingerprint. FNV-1a with a splitmix64 finish is
+// spelled out here because std's hashers may change between Rust releases,
+// and fingerprints are meant to be compared across runs.
+#[derive(Debug, Clone, Copy, Default)]
+struct ContentFingerprint {
+    sum: u64,
+}
+
+impl ContentFingerprint {
+    fn add(&mut self, value: &str) {
+        self.add_times(value, 1);
+    }
+
+    fn add_times(&mut self, value: &str, times: usize) {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in value.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        // FNV alone mixes the high bits poorly, which a plain sum would keep
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^= hash >> 31;
+        self.sum = self.sum.wrapping_add(hash.wrapping_mul(times as u64));
+    }
+
+    fn merge(&mut self, other: &ContentFingerprint) {
+        self.sum = self.sum.wrapping_add(other.sum);
+    }
+
+    fn hex(&self) -> String {
+        format!("{:016x}", self.sum)
+    }
+}
+
+// Benford's law: in data spanning several orders of magnitude (amounts,
+// populations, counts) the leading digit d shows up with share log10(1 + 1/d),
+// so 1 leads about 30% of the values and 9 under 5%. Fabricated or truncated
+// numbers tend to stray from it.
+
+// chi-square at the 5% level for 8 degrees of freedom (nine digits)
+const BENFORD_CRITICAL: f64 = 15.507;
+// too few values, or too narrow a range, and the law says nothing
+const BENFORD_MIN_VALUES: usize = 100;
+const BENFORD_MIN_RATIO: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct BenfordTally {
+    // positive numbers by leading digit 1-9
+    digits: [usize; 9],
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl BenfordTally {
+    fn add(&mut self, x: f64) {
+        if !(x > 0.0 && x.is_finite()) {
+            return;
+        }
+        // scientific notation puts the leading significant digit first,
+        // without the rounding slips of dividing by a power of ten
+        let digit = format!("{:e}", x).as_bytes()[0] - b'0';
+        self.digits[usize::from(digit) - 1] += 1;
+        self.min = Some(self.min.map_or(x, |m| m.min(x)));
+        self.max = Some(self.max.map_or(x, |m| m.max(x)));
+    }
+
+    fn merge(&mut self, other: &BenfordTally) {
+        for (count, other) in self.digits.iter_mut().zip(other.digits) {
+            *count += other;
+        }
+        self.min = self.min.into_iter().chain(other.min).reduce(f64::min);
+        self.max = self.max.into_iter().chain(other.max).reduce(f64::max);
+    }
+
+    // None when there are too few values or they span under two orders of magnitude
+    fn test(&self) -> Option<BenfordTest> {
+        let values: usize = self.digits.iter().sum();
+        let (min, max) = (self.min?, self.max?);
+        if values < BENFORD_MIN_VALUES || max / min < BENFORD_MIN_RATIO {
+            return None;
+        }
+        let n = values as f64;
+        let mut observed = [0.0; 9];
+        let mut chi_square = 0.0;
+        for (d, &count) in self.digits.iter().enumerate() {
+            let expected = n * (1.0 + 1.0 / (d as f64 + 1.0)).log10();
+            chi_square += (count as f64 - expected).powi(2) / expected;
+            observed[d] = count as f64 / n;
+        }
+        Some(BenfordTest {
+            values,
+            observed,
+            chi_square,
+            pass: chi_square <= BENFORD_CRITICAL,
+        })
+    }
+}
+
+/// How often a JSON field held each kind of value, across all records.
+/// `absent` counts the records without the field at all.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct JsonKinds {
+    pub number: usize,
+    pub string: usize,
+    pub boolean: usize,
+    pub null: usize,
+    pub array: usize,
+    pub object: usize,
+    pub absent: usize,
+}
+
+impl JsonKinds {
+    fn record(&mut self, value: Option<&serde_json::Value>) {
+        let count = match value {
+            Some(serde_json::Value::Number(_)) => &mut self.number,
+            Some(serde_json::Value::String(_)) => &mut self.string,
+            Some(serde_json::Value::Bool(_)) => &mut self.boolean,
+            Some(serde_json::Value::Null) => &mut self.null,
+            Some(serde_json::Value::Array(_)) => &mut self.array,
+            Some(serde_json::Value::Object(_)) => &mut self.object,
+            None => &mut self.absent,
+        };
+        *count += 1;
+    }
+
+    fn merge(&mut self, other: &JsonKinds) {
+        self.number += other.number;
+        self.string += other.string;
+        self.boolean += other.boolean;
+        self.null += other.null;
+        self.array += other.array;
+        self.object += other.object;
+        self.absent += other.absent;
+    }
+
+    fn counts(&self) -> [(&'static str, usize); 7] {
+        [
+            ("number", self.number),
+            ("string", self.string),
+            ("boolean", self.boolean),
+            ("null", self.null),
+            ("array", self.array),
+            ("object", self.object),
+            ("absent", self.absent),
+        ]
+    }
+
+    /// Share of the records that have the field at all, `null` included.
+    pub fn presence(&self) -> f64 {
+        let total: usize = self.counts().iter().map(|(_, count)| count).sum();
+        (total - self.absent) as f64 / total.max(1) as f64
+    }
+
+    // more than one kind of actual value; null and absent don't count
+    fn is_mixed(&self) -> bool {
+        self.counts()[..6]
+            .iter()
+            .filter(|&&(kind, count)| kind != "null" && count > 0)
+            .count()
+            > 1
+    }
+
+    // "number 80.0%, string 15.0%, null 5.0%", largest first
+    fn describe(&self) -> String {
+        let total: usize = self.counts().iter().map(|(_, count)| count).sum();
+        let mut counts: Vec<(&str, usize)> = self.counts().into_iter().filter(|&(_, count)| count > 0).collect();
+        counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        counts
+            .iter()
+            .map(|(kind, count)| format!("{} {:.1}%", kind, *count as f64 / total.max(1) as f64 * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Leading-digit distribution of a numeric column's positive values against
+/// Benford's law, with a chi-square goodness-of-fit test at the 5% level.
+#[derive(Debug, Serialize)]
+pub struct BenfordTest {
+    pub values: usize,
+    // share of the values led by each digit, 1 through 9
+    pub observed: [f64; 9],
+    pub chi_square: f64,
+    pub pass: bool,
+}
+
+// What `infer_type` makes of each non-missing value on its own, whatever type
+// the column ends up with, and what the sampled inference saw.
+#[derive(Debug, Clone, Default)]
+struct TypeEvidence {
+    integers: usize,
+    large_integers: usize,
+    floats: usize,
+    booleans: usize,
+    datetimes: usize,
+    strings: usize,
+    // (numeric, non-empty) values among the --infer-sample ones
+    sampled: Option<(usize, usize)>,
+}
+
+impl TypeEvidence {
+    fn record(&mut self, value: &str) {
+        match infer_type(value) {
+            "integer" => self.integers += 1,
+            "large integer" => self.large_integers += 1,
+            "float" => self.floats += 1,
+            "boolean" => self.booleans += 1,
+            "datetime" => self.datetimes += 1,
+            _ => self.strings += 1,
+        }
+    }
+
+    fn merge(&mut self, other: &TypeEvidence) {
+        self.integers += other.integers;
+        self.large_integers += other.large_integers;
+        self.floats += other.floats;
+        self.booleans += other.booleans;
+        self.datetimes += other.datetimes;
+        self.strings += other.strings;
+        self.sampled = self.sampled.or(other.sampled);
+    }
+}
+
+// Welford's ALGORITHM -> streaming mean + variance
+
+impl ColumnStats {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> ColumnType {
+        self.kind
+    }
+
+    pub fn summary(&self, opts: &InspectOptions) -> ColumnSummary {
+        summarize_column(self, opts)
+    }
+
+    pub fn diagnostics(&self, total_rows: usize, opts: &InspectOptions) -> Vec<Diagnostic> {
+        diagnose_column(self, total_rows, opts)
+    }
+
+    /// Empty stats for a column of the given type; cells go in through `update`.
+    pub fn new(name: &str, kind: ColumnType) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            total: 0,
+            missing: 0,
+            count: 0,
+            integers_only: true,
+            min: None,
+            max: None,
+            mean: 0.0,
+            m2: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+            negatives: 0,
+            zeros: 0,
+            positives: 0,
+            negative_examples: Vec::new(),
+            uniques: HashMap::new(),
+            max_uniques: None,
+            other_count: 0,
+            sketch: None,
+            max_length: 0,
+            total_length: 0,
+            min_text: None,
+            max_text: None,
+            shortest: None,
+            longest: None,
+            date_count: 0,
+            min_date: None,
+            max_date: None,
+            date_has_time: false,
+            date_has_zone: false,
+            date_days: HashSet::new(),
+            true_count: 0,
+            false_count: 0,
+            numeric_parse_failures: 0,
+            failure_examples: Vec::new(),
+            large_integers: 0,
+            large_integer_example: None,
+            large_integers_past_i64: 0,
+            before_promotion: 0,
+            first_number: None,
+            text_examples: Vec::new(),
+            evidence: None,
+            null_bytes: 0,
+            null_byte_example: None,
+            fingerprint: None,
+            benford: None,
+            json_kinds: None,
+            outlier_count: 0,
+            outlier_method: OutlierMethod::ZScore,
+            outlier_sigma: 5.0,
+            outlier_fences: None,
+            min_z: None,
+            max_z: None,
+            values: None,
+            sample: None,
+            sample_size: 0,
+            infer_threshold: 1.0,
+            type_locked: false,
+            coerced: None,
+            na_values: Vec::new(),
+            validator: None,
+            validation_failures: 0,
+            validation_examples: Vec::new(),
+            number_order: ValueOrder::default(),
+            date_order: ValueOrder::default(),
+            code_width: None,
+            digits_only: true,
+            leading_zero: false,
+            semantic_counts: [0; 5],
+            date_range: None,
+            dates_out_of_range: 0,
+            date_range_examples: Vec::new(),
+            value_range: (None, None),
+            out_of_range: 0,
+            range_examples: Vec::new(),
+        }
+    }
+
+    fn with_na_values(mut self, na_values: &[String]) -> Self {
+        self.na_values = na_values.to_vec();
+        self
+    }
+
+    // empty cells and the configured --na-values sentinels
+    fn is_missing(&self, value: &str) -> bool {
+        value.is_empty() || self.na_values.iter().any(|na| na == value)
+    }
+
+    fn with_sampled_inference(mut self, sample_size: usize, threshold: f64) -> Self {
+        if sample_size > 0 && !self.type_locked {
+            self.sample = Some(Vec::with_capacity(sample_size));
+            self.sample_size = sample_size;
+            self.infer_threshold = threshold;
+        }
+        self
+    }
+
+    fn with_max_uniques(mut self, max_uniques: Option<usize>) -> Self {
+        self.max_uniques = max_uniques;
+        self
+    }
+
+    fn with_approx_distinct(mut self) -> Self {
+        self.sketch = Some(HyperLogLog::new());
+        self.max_uniques = self.max_uniques.or(Some(APPROX_TRACKED_VALUES));
+        self
+    }
+
+    fn with_outlier_sigma(mut self, sigma: f64) -> Self {
+        self.outlier_sigma = sigma;
+        self
+    }
+
+    fn with_outlier_method(mut self, method: OutlierMethod) -> Self {
+        self.outlier_method = method;
+        self
+    }
+
+    // for typed sources: the type comes from the schema, not from the values
+    #[cfg(any(feature = "parquet", feature = "arrow"))]
+    fn with_locked_type(mut self) -> Self {
+        self.type_locked = true;
+        self
+    }
+
+    // --coerce: the type is given, so neither the first value, the upgrade
+    // heuristic nor finalize gets to pick another
+    fn with_coerced_type(mut self, kind: CoercedType) -> Self {
+        self.kind = kind.column_type();
+        self.coerced = Some(kind);
+        self.type_locked = true;
+        self
+    }
+
+    fn with_validator(mut self, pattern: Regex) -> Self {
+        self.validator = Some(pattern);
+        self
+    }
+
+    fn with_date_range(mut self, min: i64, max: i64) -> Self {
+        self.date_range = Some((min, max));
+        self
+    }
+
+    fn with_value_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.value_range = (min, max);
+        self
+    }
+
+    fn with_fingerprint(mut self) -> Self {
+        self.fingerprint = Some(ContentFingerprint::default());
+        self
+    }
+
+    fn with_benford(mut self) -> Self {
+        self.benford = Some(BenfordTally::default());
+        self
+    }
+
+    fn with_type_evidence(mut self) -> Self {
+        self.evidence = Some(TypeEvidence::default());
+        self
+    }
+
+    fn with_value_buffer(mut self) -> Self {
+        self.values = Some(Vec::new());
+        self
+    }
+
+    /// Feeds one raw cell; an empty string is a missing value.
+    pub fn update(&mut self, value: &str) {
+        self.update_cell(value, None);
+    }
+
+    // `number` is the cell as normalize_number rewrote it, if it did: the
+    // numeric stats and the type evidence read it, everything else (missing
+    // values, --validate, the fingerprint, text stats, examples) the raw cell
+    fn update_cell(&mut self, value: &str, number: Option<&str>) {
+        if let Some(sample) = &mut self.sample {
+            sample.push((value.to_string(), number.map(str::to_string)));
+            if sample.len() >= self.sample_size {
+                self.resolve_sample();
+            }
+            return;
+        }
+
+        self.total += 1;
+        if let Some(fingerprint) = &mut self.fingerprint {
+            fingerprint.add(value);
+        }
+
+        if self.is_missing(value) {
+            self.missing += 1;
+            return;
+        }
+
+        if let Some(pattern) = &self.validator
+            && !pattern.is_match(value)
+        {
+            self.validation_failures += 1;
+            if self.validation_examples.len() < MAX_FAILURE_EXAMPLES {
+                self.validation_examples.push((self.total, value.to_string()));
+            }
+        }
+
+        if let Some(evidence) = &mut self.evidence {
+            evidence.record(number.unwrap_or(value));
+        }
+
+        match parse_bool(value) {
+            Some(true) => self.true_count += 1,
+            Some(false) => self.false_count += 1,
+            None => {}
+        }
+
+        if self.digits_only {
+            self.track_code(value);
+        }
+
+        match self.kind {
+            ColumnType::Numeric => {
+                let raw = value;
+                let value = number.unwrap_or(value);
+                let integer_failed = self.coerced == Some(CoercedType::Integer) && value.parse::<i64>().is_err();
+                if is_large_integer(value) && !integer_failed {
+                    // as a float it would be rounded, so it is counted but kept
+                    // out of the stats, and doesn't make the column float
+                    self.first_number.get_or_insert(self.total);
+                    self.large_integers += 1;
+                    self.large_integer_example.get_or_insert_with(|| (self.total, raw.to_string()));
+                    if value.parse::<i64>().is_err() {
+                        self.large_integers_past_i64 += 1;
+                    }
+                } else if let Ok(x) = value.parse::<f64>()
+                    && !integer_failed
+                {
+                    // the first non-integer demotes the column to float
+                    if self.integers_only && value.parse::<i64>().is_err() {
+                        self.integers_only = false;
+                    }
+                    self.first_number.get_or_insert(self.total);
+                    self.accumulate_number(x);
+                } else {
+                    self.numeric_parse_failures += 1;
+                    // every record feeds every column, so total is the row number
+                    if self.failure_examples.len() < MAX_FAILURE_EXAMPLES {
+                        self.failure_examples.push((self.total, value.to_string()));
+                    }
+                }
+            }
+            ColumnType::Categorical | ColumnType::DateTime | ColumnType::Boolean => {
+                if let Some((ts, has_time, has_zone)) = parse_datetime(value) {
+                    self.accumulate_date(ts, has_time, has_zone);
+                }
+
+                let length = value.chars().count();
+                self.track_text(value, length);
+                self.max_length = self.max_length.max(length);
+                self.total_length += length;
+
+                if self.kind == ColumnType::Categorical && self.text_examples.len() < MAX_FAILURE_EXAMPLES {
+                    self.text_examples.push((self.total, value.to_string()));
+                }
+                if let Some(semantic) = SemanticType::detect(value) {
+                    self.semantic_counts[semantic as usize] += 1;
+                }
+
+                self.count_value(value);
+            }
+        }
+    }
+
+    fn accumulate_number(&mut self, x: f64) {
+        // Welford update with the current value, extended to the higher
+        // moments (Terriberry). m3 and m4 read the previous m2/m3, so the
+        // order of the updates matters.
+        let n1 = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term1 = delta * delta_n * n1;
+
+        self.mean += delta_n;
+        self.m4 += term1 * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term1 * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term1;
+
+        self.min = Some(self.min.map_or(x, |m| m.min(x)));
+        self.max = Some(self.max.map_or(x, |m| m.max(x)));
+        self.number_order.update(x);
+
+        if x < 0.0 {
+            self.negatives += 1;
+            // every record feeds every column, so total is the row number
+            if self.negative_examples.len() < MAX_FAILURE_EXAMPLES {
+                self.negative_examples.push((self.total, x));
+            }
+        } else if x == 0.0 {
+            self.zeros += 1;
+        } else if x > 0.0 {
+            self.positives += 1;
+        }
+
+        if let Some(tally) = &mut self.benford {
+            tally.add(x);
+        }
+
+        let (low, high) = self.value_range;
+        if low.is_some_and(|low| x < low) || high.is_some_and(|high| x > high) {
+            self.out_of_range += 1;
+            if self.range_examples.len() < MAX_FAILURE_EXAMPLES {
+                self.range_examples.push((self.total, x));
+            }
+        }
+
+        if let Some(values) = &mut self.values {
+            values.push(x);
+        }
+    }
+
+    fn accumulate_date(&mut self, ts: i64, has_time: bool, has_zone: bool) {
+        self.date_count += 1;
+        self.date_has_zone |= has_zone;
+        self.date_days.insert(ts.div_euclid(86_400));
+        if self.date_range.is_some_and(|(min, max)| ts < min || ts > max) {
+            self.dates_out_of_range += 1;
+            if self.date_range_examples.len() < MAX_FAILURE_EXAMPLES {
+                self.date_range_examples.push((self.total, ts));
+            }
+        }
+        self.min_date = Some(self.min_date.map_or(ts, |m| m.min(ts)));
+        self.max_date = Some(self.max_date.map_or(ts, |m| m.max(ts)));
+        self.date_has_time |= has_time;
+        self.date_order.update(ts as f64);
+    }
+
+    // called before max_length takes in the new length
+    fn track_text(&mut self, value: &str, length: usize) {
+        if self.min_text.as_deref().is_none_or(|min| value < min) {
+            self.min_text = Some(value.to_string());
+        }
+        if self.max_text.as_deref().is_none_or(|max| value > max) {
+            self.max_text = Some(value.to_string());
+        }
+        if self.shortest.as_deref().is_none_or(|s| length < s.chars().count()) {
+            self.shortest = Some(value.to_string());
+        }
+        if self.longest.is_none() || length > self.max_length {
+            self.longest = Some(value.to_string());
+        }
+    }
+
+    fn track_code(&mut self, value: &str) {
+        let width = value.len();
+        if !value.bytes().all(|b| b.is_ascii_digit()) || self.code_width.is_some_and(|w| w != width) {
+            self.digits_only = false;
+            return;
+        }
+        self.code_width = Some(width);
+        self.leading_zero |= width > 1 && value.starts_with('0');
+    }
+
+    // once max_uniques is hit the tracked count is only a lower bound,
+    // unless the sketch is counting
+    fn uniques_capped(&self) -> bool {
+        self.sketch.is_none() && self.other_count > 0
+    }
+
+    // the sketch is only consulted once the exact map stopped growing
+    fn distinct_estimate(&self) -> Option<&HyperLogLog> {
+        self.sketch.as_ref().filter(|_| self.other_count > 0)
+    }
+
+    // exact, or estimated by the sketch; an estimate never exceeds the values seen
+    fn distinct_count(&self) -> usize {
+        match self.distinct_estimate() {
+            Some(sketch) => sketch.estimate().min(self.total - self.missing),
+            None => self.uniques.len(),
+        }
+    }
+
+    // a capped column has at least max_uniques distinct values, which is
+    // treated as high cardinality whatever the ratio of the tracked ones
+    fn high_cardinality(&self, non_missing: usize, threshold: f64) -> bool {
+        non_missing > 0
+            && (self.uniques_capped() || self.distinct_count() as f64 / non_missing as f64 > threshold)
+    }
+
+    /// The pattern more than 90% of a categorical column's values match, if
+    /// any.
+    pub fn semantic_type(&self) -> Option<SemanticType> {
+        let non_missing = self.total - self.missing;
+        if self.kind != ColumnType::Categorical || non_missing == 0 {
+            return None;
+        }
+        SemanticType::ALL
+            .into_iter()
+            .find(|&t| self.semantic_counts[t as usize] as f64 / non_missing as f64 > SEMANTIC_MAJORITY)
+    }
+
+    // width of the codes when the column looks like zero-padded identifiers
+    fn zero_padded_width(&self) -> Option<usize> {
+        self.code_width.filter(|_| self.digits_only && self.leading_zero)
+    }
+
+    fn count_value(&mut self, value: &str) {
+        if let Some(sketch) = &mut self.sketch {
+            sketch.insert(value);
+        }
+        if let Some(count) = self.uniques.get_mut(value) {
+            *count += 1;
+        } else if self.max_uniques.is_some_and(|cap| self.uniques.len() >= cap) {
+            self.other_count += 1;
+        } else {
+            self.uniques.insert(value.to_string(), 1);
+        }
+    }
+
+    /// Folds in the stats of the rows right after these (the next batch, or
+    /// the next file), as if both had been read in one pass; `finalize` the
+    /// result once everything is merged, not the parts. A number after text
+    /// upgrades the column to numeric as it would while reading, and text
+    /// after numbers counts as parse failures.
+    pub fn merge(&mut self, other: &ColumnStats) {
+        // the text other read before turning numeric (or all of it, if it
+        // never did) would not have parsed on this already numeric side
+        let mut failed_text = 0;
+        if self.kind == ColumnType::Numeric {
+            failed_text = match other.kind {
+                ColumnType::Numeric => other.before_promotion,
+                _ => other.total - other.missing,
+            };
+        } else if self.kind == ColumnType::Categorical && other.kind == ColumnType::Numeric && !self.type_locked {
+            self.promote_to_numeric();
+            // the text other read before its first number goes too, as in one pass
+            self.before_promotion += other.before_promotion;
+        }
+
+        // every record feeds every column, so total is the row offset of other
+        let offset = self.total;
+        self.first_number = self.first_number.or(other.first_number.map(|row| row + offset));
+        self.total += other.total;
+        self.missing += other.missing;
+        self.true_count += other.true_count;
+        self.false_count += other.false_count;
+
+        self.digits_only &= other.digits_only
+            && (self.code_width.is_none() || other.code_width.is_none() || self.code_width == other.code_width);
+        self.code_width = self.code_width.or(other.code_width);
+        self.leading_zero |= other.leading_zero;
+        for (count, other) in self.semantic_counts.iter_mut().zip(other.semantic_counts) {
+            *count += other;
+        }
+
+        if failed_text > 0 {
+            self.numeric_parse_failures += failed_text;
+            self.add_failure_examples(&other.text_examples, offset);
+        }
+        if let (Some(evidence), Some(other_evidence)) = (&mut self.evidence, &other.evidence) {
+            evidence.merge(other_evidence);
+        }
+        if let (Some(fingerprint), Some(other)) = (&mut self.fingerprint, &other.fingerprint) {
+            fingerprint.merge(other);
+        }
+        match (&mut self.json_kinds, &other.json_kinds) {
+            (Some(kinds), Some(other)) => kinds.merge(other),
+            (kinds @ None, Some(other)) => *kinds = Some(*other),
+            _ => {}
+        }
+        self.null_bytes += other.null_bytes;
+        if self.null_byte_example.is_none() {
+            self.null_byte_example = other.null_byte_example.clone();
+        }
+        if self.kind == ColumnType::Numeric && other.kind != ColumnType::Numeric {
+            return;
+        }
+
+        // Welford moments via the pairwise update (Chan et al., extended to
+        // m3 and m4 by Pébay); like accumulate_number, m4 and m3 read the old m2/m3
+        if other.count > 0 {
+            let (na, nb) = (self.count as f64, other.count as f64);
+            let n = na + nb;
+            let delta = other.mean - self.mean;
+            let delta2 = delta * delta;
+
+            self.m4 += other.m4
+                + delta2 * delta2 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n)
+                + 6.0 * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+                + 4.0 * delta * (na * other.m3 - nb * self.m3) / n;
+            self.m3 += other.m3
+                + delta2 * delta * na * nb * (na - nb) / (n * n)
+                + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+            self.m2 += other.m2 + delta2 * na * nb / n;
+            self.mean += delta * nb / n;
+            self.count += other.count;
+
+            self.integers_only &= other.integers_only;
+            self.min = self.min.into_iter().chain(other.min).reduce(f64::min);
+            self.max = self.max.into_iter().chain(other.max).reduce(f64::max);
+
+            self.negatives += other.negatives;
+            self.zeros += other.zeros;
+            self.positives += other.positives;
+            let room = MAX_FAILURE_EXAMPLES - self.negative_examples.len();
+            self.negative_examples
+                .extend(other.negative_examples.iter().take(room).map(|&(row, x)| (row + offset, x)));
+            if let (Some(tally), Some(other)) = (&mut self.benford, &other.benford) {
+                tally.merge(other);
+            }
+            self.out_of_range += other.out_of_range;
+            let room = MAX_FAILURE_EXAMPLES - self.range_examples.len();
+            self.range_examples
+                .extend(other.range_examples.iter().take(room).map(|&(row, x)| (row + offset, x)));
+        }
+        self.number_order.merge(&other.number_order);
+        if let (Some(values), Some(other)) = (&mut self.values, &other.values) {
+            values.extend_from_slice(other);
+        }
+        self.numeric_parse_failures += other.numeric_parse_failures;
+        self.add_failure_examples(&other.failure_examples, offset);
+        self.large_integers += other.large_integers;
+        self.large_integer_example = self
+            .large_integer_example
+            .take()
+            .or_else(|| other.large_integer_example.as_ref().map(|(row, value)| (row + offset, value.clone())));
+        self.large_integers_past_i64 += other.large_integers_past_i64;
+        self.validation_failures += other.validation_failures;
+        let room = MAX_FAILURE_EXAMPLES - self.validation_examples.len();
+        self.validation_examples.extend(
+            other
+                .validation_examples
+                .iter()
+                .take(room)
+                .map(|(row, value)| (row + offset, value.clone())),
+        );
+        self.outlier_count += other.outlier_count;
+
+        for (value, &count) in &other.uniques {
+            if let Some(existing) = self.uniques.get_mut(value) {
+                *existing += count;
+            } else if self.max_uniques.is_some_and(|cap| self.uniques.len() >= cap) {
+                self.other_count += count;
+            } else {
+                self.uniques.insert(value.clone(), count);
+            }
+        }
+        self.other_count += other.other_count;
+        match (&mut self.sketch, &other.sketch) {
+            (Some(sketch), Some(other)) => sketch.merge(other),
+            (sketch @ None, Some(other)) => *sketch = Some(other.clone()),
+            _ => {}
+        }
+        for value in [&other.min_text, &other.max_text, &other.shortest, &other.longest]
+            .into_iter()
+            .flatten()
+        {
+            self.track_text(value, value.chars().count());
+        }
+        self.max_length = self.max_length.max(other.max_length);
+        self.total_length += other.total_length;
+        let room = MAX_FAILURE_EXAMPLES - self.text_examples.len();
+        self.text_examples.extend(
+            other
+                .text_examples
+                .iter()
+                .take(room)
+                .map(|(row, value)| (row + offset, value.clone())),
+        );
+
+        self.date_count += other.date_count;
+        self.min_date = self.min_date.into_iter().chain(other.min_date).min();
+        self.max_date = self.max_date.into_iter().chain(other.max_date).max();
+        self.date_has_time |= other.date_has_time;
+        self.date_has_zone |= other.date_has_zone;
+        self.date_days.extend(&other.date_days);
+        self.date_order.merge(&other.date_order);
+        self.dates_out_of_range += other.dates_out_of_range;
+        let room = MAX_FAILURE_EXAMPLES - self.date_range_examples.len();
+        self.date_range_examples
+            .extend(other.date_range_examples.iter().take(room).map(|&(row, ts)| (row + offset, ts)));
+    }
+
+    fn add_failure_examples(&mut self, examples: &[(usize, String)], offset: usize) {
+        let room = MAX_FAILURE_EXAMPLES - self.failure_examples.len();
+        self.failure_examples.extend(
+            examples
+                .iter()
+                .take(room)
+                .map(|(row, value)| (row + offset, value.clone())),
+        );
+    }
+
+    // decides the type from the buffered sample and replays it; the type is
+    // then locked, so later values no longer trigger the numeric upgrade
+    fn resolve_sample(&mut self) {
+        let Some(sample) = self.sample.take() else {
+            return;
+        };
+
+        let non_empty = sample.iter().filter(|(v, _)| !self.is_missing(v)).count();
+        let numeric = sample
+            .iter()
+            .filter(|(v, number)| matches!(infer_type(number.as_deref().unwrap_or(v)), "integer" | "float"))
+            .count();
+
+        self.kind = if non_empty > 0 && numeric as f64 / non_empty as f64 >= self.infer_threshold {
+            ColumnType::Numeric
+        } else {
+            ColumnType::Categorical
+        };
+        self.type_locked = true;
+        if let Some(evidence) = &mut self.evidence {
+            evidence.sampled = Some((numeric, non_empty));
+        }
+
+        for (value, number) in &sample {
+            self.update_cell(value, number.as_deref());
+        }
+    }
+
+    fn add_missing(&mut self, n: usize) {
+        self.total += n;
+        self.missing += n;
+        if let Some(fingerprint) = &mut self.fingerprint {
+            fingerprint.add_times("", n);
+        }
+    }
+
+    fn promote_to_numeric(&mut self) {
+        self.kind = ColumnType::Numeric;
+        self.before_promotion = self.total - self.missing;
+        self.uniques.clear(); // no longer needed
+        self.other_count = 0;
+        if let Some(sketch) = &mut self.sketch {
+            *sketch = HyperLogLog::new();
+        }
+        self.max_length = 0;
+        self.total_length = 0;
+        self.min_text = None;
+        self.max_text = None;
+        self.shortest = None;
+        self.longest = None;
+        self.date_count = 0;
+        self.min_date = None;
+        self.max_date = None;
+        self.date_days = HashSet::new();
+        self.date_order = ValueOrder::default();
+        self.dates_out_of_range = 0;
+        self.date_range_examples.clear();
+    }
+
+    // the large integers a numeric column left out of its stats
+    fn large_integers(&self) -> Option<usize> {
+        (self.kind == ColumnType::Numeric && self.large_integers > 0).then_some(self.large_integers)
+    }
+
+    fn numeric_type(&self) -> Option<&'static str> {
+        if self.kind != ColumnType::Numeric || self.count + self.large_integers == 0 {
+            None
+        } else if self.coerced == Some(CoercedType::Float) {
+            Some("float")
+        } else if self.integers_only {
+            Some("integer")
+        } else {
+            Some("float")
+        }
+    }
+
+    // the --explain evidence and the rule that picked the final type, mirroring
+    // update_column, resolve_sample and finalize
+    fn type_inference(&self, skip: usize) -> Option<TypeInference> {
+        let evidence = self.evidence.as_ref()?;
+        let non_missing = self.total - self.missing;
+        let share = |n: usize, of: usize| n as f64 / of.max(1) as f64 * 100.0;
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+
+        let rule = if let Some(kind) = self.coerced {
+            format!("forced to {} by --coerce", kind.as_str())
+        } else if non_missing == 0 {
+            "no values, so categorical by default".to_string()
+        } else if self.kind == ColumnType::Boolean {
+            "every value is a boolean spelling (true/false, yes/no, t/f, 1/0)".to_string()
+        } else if self.kind == ColumnType::DateTime {
+            format!(
+                "{:.1}% of the values parsed as dates ({}% needed)",
+                share(self.date_count, non_missing),
+                DATETIME_MAJORITY * 100.0
+            )
+        } else if let Some((numeric, sampled)) = evidence.sampled {
+            let verdict = if self.kind == ColumnType::Numeric { "at least" } else { "below" };
+            format!(
+                "{} of the first {} values were numbers ({:.1}%, {} the --infer-threshold of {}%)",
+                numeric,
+                sampled,
+                share(numeric, sampled),
+                verdict,
+                self.infer_threshold * 100.0
+            )
+        } else if self.type_locked {
+            "declared by the file's schema".to_string()
+        } else if self.kind == ColumnType::Numeric && self.before_promotion > 0 {
+            let row = self.first_number.map(|row| format!(" on row {}", row + skip)).unwrap_or_default();
+            format!(
+                "upgraded to numeric by the number{} after {} text value{} (left out of the stats)",
+                row,
+                self.before_promotion,
+                plural(self.before_promotion)
+            )
+        } else if self.kind == ColumnType::Numeric && self.numeric_parse_failures == 0 {
+            "every value parsed as a number".to_string()
+        } else if self.kind == ColumnType::Numeric {
+            format!(
+                "the first value was a number; {} later value{} failed to parse as one",
+                self.numeric_parse_failures,
+                plural(self.numeric_parse_failures)
+            )
+        } else if evidence.large_integers > 0 && evidence.integers + evidence.floats == 0 {
+            format!(
+                "integers past 2^53 ({} of them) are too large for a float to hold exactly, so kept as text",
+                evidence.large_integers
+            )
+        } else {
+            let mut rule = "no value parsed as a number".to_string();
+            let booleans = self.true_count + self.false_count;
+            if booleans > 0 {
+                rule += &format!(", only {} of {} are boolean spellings", booleans, non_missing);
+            }
+            if self.date_count > 0 {
+                rule += &format!(
+                    ", only {:.1}% parsed as dates ({}% needed)",
+                    share(self.date_count, non_missing),
+                    DATETIME_MAJORITY * 100.0
+                );
+            }
+            rule
+        };
+
+        Some(TypeInference {
+            integers: evidence.integers,
+            large_integers: evidence.large_integers,
+            floats: evidence.floats,
+            booleans: evidence.booleans,
+            datetimes: evidence.datetimes,
+            strings: evidence.strings,
+            parse_failures: self.numeric_parse_failures,
+            upgraded_at: self
+                .first_number
+                .filter(|_| self.kind == ColumnType::Numeric && self.before_promotion > 0)
+                .map(|row| row + skip),
+            rule,
+        })
+    }
+
+    fn stddev(&self) -> Option<f64> {
+        if self.count > 1 {
+            Some((self.m2 / (self.count as f64 - 1.0)).sqrt())
+        } else {
+            None
+        }
+    }
+
+    // population skewness g1; None for fewer than two values or no spread
+    fn skewness(&self) -> Option<f64> {
+        if self.count < 2 || self.m2 <= 0.0 {
+            return None;
+        }
+        Some((self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5))
+    }
+
+    // excess kurtosis g2, so a normal distribution is around 0
+    fn kurtosis(&self) -> Option<f64> {
+        if self.count < 2 || self.m2 <= 0.0 {
+            return None;
+        }
+        Some(self.count as f64 * self.m4 / (self.m2 * self.m2) - 3.0)
+    }
+
+    // Distinct values that only differ by surrounding whitespace, e.g. "active"
+    // and "active ". Returns how many distinct values would disappear after
+    // trimming, plus the smallest colliding trimmed value as an example.
+    fn whitespace_collisions(&self) -> (usize, Option<&str>) {
+        let mut groups: HashMap<&str, usize> = HashMap::new();
+        for value in self.uniques.keys() {
+            *groups.entry(value.trim()).or_insert(0) += 1;
+        }
+
+        let merged = groups.values().map(|&n| n - 1).sum();
+        let example = groups
+            .iter()
+            .filter(|&(_, &n)| n > 1)
+            .map(|(&value, _)| value)
+            .min();
+        (merged, example)
+    }
+
+    // most frequent values first, ties broken alphabetically
+    fn top_values(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut values: Vec<(&str, usize)> = self
+            .uniques
+            .iter()
+            .map(|(value, count)| (value.as_str(), *count))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        values.truncate(n);
+        values
+    }
+
+    /// Settles the final type and sorts the buffered values, once all cells
+    /// are in and before `summary` or `diagnostics`.
+    pub fn finalize(&mut self) {
+        self.resolve_sample();
+
+        let non_missing = self.total - self.missing;
+        if self.coerced.is_some() {
+            // the type was given with --coerce
+        } else if matches!(self.kind, ColumnType::Categorical | ColumnType::Numeric)
+            && non_missing > 0
+            && self.true_count + self.false_count == non_missing
+        {
+            // every value is a boolean spelling, including 0/1 flag columns
+            self.kind = ColumnType::Boolean;
+        } else if self.kind == ColumnType::Categorical
+            && non_missing > 0
+            && self.date_count as f64 / non_missing as f64 >= DATETIME_MAJORITY
+        {
+            self.kind = ColumnType::DateTime;
+        }
+
+        if let Some(values) = &mut self.values {
+            values.sort_by(|a, b| a.total_cmp(b));
+        }
+
+        self.count_outliers();
+    }
+
+    // Scores every buffered value against the final mean and stddev, so the
+    // result doesn't depend on row order or on how early an outlier appears.
+    // The z-range is always computed; which values count as outliers depends
+    // on the method. Expects the values sorted, for the quartiles.
+    fn count_outliers(&mut self) {
+        if self.outlier_method == OutlierMethod::Iqr
+            && let (Some(q1), Some(q3)) = (self.quantile(0.25), self.quantile(0.75))
+        {
+            let iqr = q3 - q1;
+            let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+            self.outlier_fences = Some((low, high));
+            if let Some(values) = &self.values {
+                self.outlier_count = values.iter().filter(|&&x| x < low || x > high).count();
+            }
+        }
+
+        let (Some(values), Some(stddev)) = (&self.values, self.stddev()) else {
+            return;
+        };
+        if stddev <= 0.0 {
+            return;
+        }
+
+        for &x in values {
+            let z = (x - self.mean).abs() / stddev;
+            if self.outlier_method == OutlierMethod::ZScore && z >= self.outlier_sigma {
+                self.outlier_count += 1;
+            }
+            self.min_z = Some(self.min_z.map_or(z, |m| m.min(z)));
+            self.max_z = Some(self.max_z.map_or(z, |m| m.max(z)));
+        }
+    }
+
+    // equal-width bins over [min, max]; the last bin is closed on the right
+    fn histogram(&self, bins: usize) -> Option<Vec<HistogramBin>> {
+        let values = self.values.as_ref()?;
+        let (min, max) = (self.min?, self.max?);
+        let width = (max - min) / bins as f64;
+
+        let mut counts = vec![0; bins];
+        for &x in values {
+            let bin = if width > 0.0 {
+                (((x - min) / width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+
+        Some(
+            counts
+                .into_iter()
+                .enumerate()
+                .map(|(i, count)| HistogramBin {
+                    start: min + width * i as f64,
+                    end: if i + 1 == bins { max } else { min + width * (i + 1) as f64 },
+                    count,
+                })
+                .collect(),
+        )
+    }
+
+    // linear interpolation between closest ranks, expects finalize() first
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let values = self.values.as_ref()?;
+        if values.is_empty() {
+            return None;
+        }
+
+        let rank = q * (values.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let frac = rank - lower as f64;
+
+        Some(values[lower] + (values[upper] - values[lower]) * frac)
+    }
+}
+
+// typed entry points for columnar formats, bypassing string parsing
+#[cfg(any(feature = "parquet", feature = "arrow", feature = "xlsx"))]
+impl ColumnStats {
+    fn is_missing_number(&self, x: f64) -> bool {
+        self.na_values
+            .iter()
+            .any(|na| na.parse::<f64>().is_ok_and(|na| na == x))
+    }
+
+    fn update_number(&mut self, value: Option<f64>) {
+        self.total += 1;
+        if let Some(fingerprint) = &mut self.fingerprint {
+            fingerprint.add(&value.map(|x| x.to_string()).unwrap_or_default());
+        }
+        match value {
+            Some(x) if !self.is_missing_number(x) => self.accumulate_number(x),
+            _ => self.missing += 1,
+        }
+    }
+
+    #[cfg(any(feature = "parquet", feature = "arrow"))]
+    fn update_datetime(&mut self, value: Option<(i64, bool)>) {
+        self.total += 1;
+        if let Some(fingerprint) = &mut self.fingerprint {
+            fingerprint.add(&value.map(|(ts, has_time)| format_datetime(ts, has_time)).unwrap_or_default());
+        }
+        match value {
+            Some((ts, has_time)) => {
+                self.accumulate_date(ts, has_time, false);
+                self.count_value(&format_datetime(ts, has_time));
+            }
+            None => self.missing += 1,
+        }
+    }
+}
+
+// the dialect options shared by the sequential and the chunked reader
+fn csv_builder(opts: &InspectOptions) -> ReaderBuilder {
+    let mut builder = ReaderBuilder::new();
+    builder
+        .delimiter(opts.delimiter.unwrap_or(b','))
+        .quote(opts.quote.unwrap_or(b'"'))
+        .escape(opts.escape)
+        .quoting(!opts.no_quoting);
+    builder
+}
+
+fn read_csv(
+    input: impl Read,
+    opts: &InspectOptions,
+    progress: Option<&ProgressBar>,
+) -> Result<Report, InspectError> {
+    let mut reader = csv_builder(opts)
+        .has_headers(!opts.no_header)
+        .flexible(!opts.strict)
+        .from_reader(input);
+    let headers = csv_headers(&mut reader, opts)?;
+    let selected = select_columns(&headers, opts)?;
+
+    let mut groups = match &opts.group_by {
+        Some(spec) => Some(Groups::new(&headers, spec)?),
+        None => None,
+    };
+
+    let mut rows = RowStats::new(opts);
+    rows.fields = headers.len();
+    let mut column_stats: Vec<Option<ColumnStats>> = (0..headers.len()).map(|_| None).collect();
+
+    for result in reader.records().skip(opts.skip).take(opts.limit.unwrap_or(usize::MAX)) {
+        let record = result?;
+        csv_record(&record, &headers, &selected, &mut rows, &mut column_stats, opts);
+        if let Some(groups) = &mut groups {
+            groups.update(&record, &headers, &selected, opts)?;
+        }
+        report_rows(progress, rows.rows);
+    }
+
+    let columns = selected
+        .iter()
+        .map(|&i| (headers[i].clone(), column_stats[i].take()))
+        .collect();
+    let mut report = build_report("CSV", &rows, columns, opts);
+    report.groups = groups.map(|groups| groups.into_summary(&headers, &selected, opts));
+    Ok(report)
+}
+
+// grouping by a column with more distinct values than this is almost
+// certainly a mistake (an id, a timestamp), and would not be readable anyway
+const MAX_GROUPS: usize = 100;
+
+// --group-by: one set of column stats per value of the grouping column, fed
+// alongside the overall stats; rows where it is missing form the "" group
+#[derive(Debug)]
+struct Groups {
+    column: usize,
+    index: HashMap<String, usize>,
+    groups: Vec<(String, usize, Vec<Option<ColumnStats>>)>,
+}
+
+impl Groups {
+    fn new(headers: &[String], spec: &str) -> Result<Self, InspectError> {
+        let column = match resolve_columns(headers, spec)?[..] {
+            [column] => column,
+            _ => return Err(InspectError::Usage("--group-by takes a single column".to_string())),
+        };
+        Ok(Self {
+            column,
+            index: HashMap::new(),
+            groups: Vec::new(),
+        })
+    }
+
+    fn update(
+        &mut self,
+        record: &csv::StringRecord,
+        headers: &[String],
+        selected: &[usize],
+        opts: &InspectOptions,
+    ) -> Result<(), InspectError> {
+        let cell = |i: usize| record.get(i).unwrap_or("");
+        let key = cell(self.column);
+        let group = match self.index.get(key) {
+            Some(&group) => group,
+            None if self.groups.len() >= MAX_GROUPS => {
+                return Err(InspectError::TooManyGroups {
+                    column: headers[self.column].clone(),
+                    limit: MAX_GROUPS,
+                });
+            }
+            None => {
+                self.index.insert(key.to_string(), self.groups.len());
+                self.groups.push((key.to_string(), 0, (0..headers.len()).map(|_| None).collect()));
+                self.groups.len() - 1
+            }
+        };
+
+        let (_, rows, stats) = &mut self.groups[group];
+        *rows += 1;
+        for &i in selected.iter().filter(|&&i| i != self.column) {
+            update_column(&mut stats[i], &headers[i], cell(i), opts);
+        }
+        Ok(())
+    }
+
+    // groups in value order, each with the summaries of the selected columns
+    fn into_summary(self, headers: &[String], selected: &[usize], opts: &InspectOptions) -> GroupedSummary {
+        let mut groups: Vec<GroupSummary> = self
+            .groups
+            .into_iter()
+            .map(|(value, rows, mut stats)| GroupSummary {
+                value,
+                rows,
+                columns: selected
+                    .iter()
+                    .filter(|&&i| i != self.column)
+                    .map(|&i| {
+                        let mut stats = stats[i].take();
+                        if let Some(stats) = &mut stats {
+                            stats.finalize();
+                        }
+                        ColumnReport {
+                            name: headers[i].clone(),
+                            kind: stats.as_ref().map(|s| s.kind),
+                            numeric_type: stats.as_ref().and_then(ColumnStats::numeric_type),
+                            large_integers: stats.as_ref().and_then(ColumnStats::large_integers),
+                            large_integers_past_i64: stats.as_ref().is_some_and(|s| s.large_integers_past_i64 > 0),
+                            semantic_type: stats.as_ref().and_then(ColumnStats::semantic_type),
+                            summary: stats.as_ref().map(|s| summarize_column(s, opts)),
+                            diagnostics: None,
+                            inference: None,
+                            fingerprint: stats.as_ref().and_then(|s| s.fingerprint.as_ref()).map(ContentFingerprint::hex),
+                            benford: stats.as_ref().and_then(|s| s.benford.as_ref()).and_then(BenfordTally::test),
+                            json_kinds: stats.as_ref().and_then(|s| s.json_kinds),
+                            presence: stats.as_ref().and_then(|s| s.json_kinds).map(|k| k.presence()),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        groups.sort_by(|a, b| a.value.cmp(&b.value));
+
+        GroupedSummary {
+            column: headers[self.column].clone(),
+            groups,
+        }
+    }
+}
+
+// without a header row, headers() peeks at the first record instead
+fn csv_headers<R: Read>(reader: &mut csv::Reader<R>, opts: &InspectOptions) -> Result<Vec<String>, InspectError> {
+    Ok(if opts.no_header {
+        (0..reader.headers()?.len()).map(|i| format!("col_{}", i)).collect()
+    } else {
+        reader.headers()?.iter().map(|name| name.replace('\0', "")).collect()
+    })
+}
+
+fn csv_record(
+    record: &csv::StringRecord,
+    headers: &[String],
+    selected: &[usize],
+    rows: &mut RowStats,
+    column_stats: &mut [Option<ColumnStats>],
+    opts: &InspectOptions,
+) {
+    rows.update(row_fingerprint(record.iter()));
+    if record.len() != headers.len() {
+        rows.ragged_row(record.len());
+    }
+
+    // a short row's absent fields are missing values; extra ones are ignored
+    let cell = |i: usize| record.get(i).unwrap_or("");
+    rows.sample_row(selected.iter().map(|&i| cell(i).to_string()));
+    if let Some(correlations) = &mut rows.correlations {
+        correlations.update(selected.iter().map(|&i| parse_number(cell(i), opts)));
+    }
+
+    let mut dirty = false;
+    for &i in selected {
+        dirty |= update_column(&mut column_stats[i], &headers[i], cell(i), opts);
+    }
+    if dirty {
+        rows.dirty_row();
+    }
+}
+
+// A column of a fixed-width file: `length` characters from `start`.
+#[derive(Debug)]
+struct FixedWidthField {
+    name: String,
+    start: usize,
+    length: usize,
+}
+
+fn parse_fixed_width(spec: &str) -> Result<Vec<FixedWidthField>, InspectError> {
+    let mut fields: Vec<FixedWidthField> = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let invalid = || {
+            InspectError::Usage(format!(
+                "invalid --fixed-width field: {} (expected name:start:length)",
+                part
+            ))
+        };
+        // the name may itself contain colons, the two numbers can't
+        let mut pieces = part.rsplitn(3, ':');
+        let (Some(length), Some(start), Some(name)) = (pieces.next(), pieces.next(), pieces.next()) else {
+            return Err(invalid());
+        };
+        let (Ok(start), Ok(length)) = (start.trim().parse(), length.trim().parse()) else {
+            return Err(invalid());
+        };
+        let name = name.trim();
+        if name.is_empty() || length == 0 {
+            return Err(invalid());
+        }
+        if fields.iter().any(|f| f.name == name) {
+            return Err(InspectError::Usage(format!("duplicate --fixed-width field: {}", name)));
+        }
+        fields.push(FixedWidthField {
+            name: name.to_string(),
+            start,
+            length,
+        });
+    }
+    if fields.is_empty() {
+        return Err(InspectError::Usage("--fixed-width needs at least one field".to_string()));
+    }
+    Ok(fields)
+}
+
+// Fixed-width lines are cut into records and go through the CSV path from
+// there. There is no header row: the names come from the spec. A line that
+// ends before a field starts is short like a ragged CSV row, with that field
+// and the ones after it missing. Blank lines are skipped.
+fn read_fixed_width(
+    input: impl BufRead,
+    opts: &InspectOptions,
+    progress: Option<&ProgressBar>,
+    name: &str,
+) -> Result<Report, InspectError> {
+    let fields = parse_fixed_width(opts.fixed_width.as_deref().unwrap_or(""))?;
+    let headers: Vec<String> = fields.iter().map(|f| f.name.clone()).collect();
+    let selected = select_columns(&headers, opts)?;
+
+    let mut groups = match &opts.group_by {
+        Some(spec) => Some(Groups::new(&headers, spec)?),
+        None => None,
+    };
+
+    let mut rows = RowStats::new(opts);
+    rows.fields = headers.len();
+    let mut column_stats: Vec<Option<ColumnStats>> = (0..headers.len()).map(|_| None).collect();
+
+    let mut offsets = Vec::new();
+    let lines = input
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim_end_matches('\r').is_empty()));
+    for line in lines.skip(opts.skip).take(opts.limit.unwrap_or(usize::MAX)) {
+        let line = line.map_err(io_error(name))?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+
+        // byte offset of every character, for slicing by character position
+        offsets.clear();
+        offsets.extend(line.char_indices().map(|(i, _)| i));
+        offsets.push(line.len());
+        let chars = offsets.len() - 1;
+
+        let mut record = csv::StringRecord::new();
+        for field in &fields {
+            let start = field.start.min(chars);
+            let end = (field.start + field.length).min(chars);
+            let cell = &line[offsets[start]..offsets[end]];
+            record.push_field(if opts.no_trim { cell } else { cell.trim() });
+        }
+        // the fields past the end of the line are absent, not empty
+        record.truncate(fields.iter().rposition(|f| f.start < chars).map_or(0, |i| i + 1));
+
+        csv_record(&record, &headers, &selected, &mut rows, &mut column_stats, opts);
+        if let Some(groups) = &mut groups {
+            groups.update(&record, &headers, &selected, opts)?;
+        }
+        report_rows(progress, rows.rows);
+    }
+
+    let columns = selected
+        .iter()
+        .map(|&i| (headers[i].clone(), column_stats[i].take()))
+        .collect();
+    let mut report = build_report("Fixed-width", &rows, columns, opts);
+    report.groups = groups.map(|groups| groups.into_summary(&headers, &selected, opts));
+    Ok(report)
+}
+
+// Chunked reading needs random access, so it is limited to plain files; the
+// --skip / --limit window and the --infer-sample vote depend on reading in order.
+fn splits_into_chunks(filename: &str, format: InputFormat, opts: &InspectOptions) -> bool {
+    opts.threads > 1
+        && matches!(format, InputFormat::Csv | InputFormat::Tsv)
+        && !filename.ends_with(".gz")
+        && opts.encoding.is_none()
+        && !starts_with_utf16_bom(filename)
+        && opts.limit.is_none()
+        && opts.skip == 0
+        && opts.infer_sample.is_none()
+        && opts.group_by.is_none()
+        && opts.random_sample.is_none()
+}
+
+// below this a chunk isn't worth a thread
+const MIN_CHUNK_BYTES: u64 = 1024 * 1024;
+
+// the chunks are read as raw bytes, so transcoded input stays on one thread
+fn starts_with_utf16_bom(filename: &str) -> bool {
+    let mut bom = [0u8; 2];
+    File::open(filename)
+        .and_then(|mut file| file.read_exact(&mut bom))
+        .is_ok_and(|_| bom == [0xFF, 0xFE] || bom == [0xFE, 0xFF])
+}
+
+// A byte range of a CSV file starting on a record boundary, with the csv
+// position of its first record so errors point at the right line.
+#[derive(Debug, Clone, Copy)]
+struct CsvChunk {
+    start: u64,
+    end: u64,
+    line: u64,
+    record: u64,
+}
+
+// Cuts the file into up to `parts` ranges of about equal size, each starting
+// at the first record boundary after its cut. Records end at \n, \r\n or a
+// lone \r, as in the csv crate, which places the record after a \r\n at its
+// \n; so does this, or a chunk would read its successor's first record. Quotes are tracked so newlines inside quoted
+// fields don't count; a doubled "" toggles twice and cancels out, and a quote
+// after the --escape character doesn't toggle at all.
+fn csv_chunks(file: &File, parts: usize, opts: &InspectOptions) -> io::Result<Vec<CsvChunk>> {
+    let len = file.metadata()?.len();
+    let parts = parts.min((len / MIN_CHUNK_BYTES) as usize).max(1);
+    let cuts: Vec<u64> = (1..parts as u64).map(|k| len * k / parts as u64).collect();
+
+    let mut starts: Vec<(u64, u64, u64)> = Vec::new();
+    if opts.no_header {
+        starts.push((0, 0, 0));
+    }
+
+    let mut reader = BufReader::with_capacity(1 << 20, file);
+    let (mut offset, mut lines, mut records) = (0u64, 0u64, 0u64);
+    let quote = if opts.no_quoting { None } else { Some(opts.quote.unwrap_or(b'"')) };
+    let delimiter = opts.delimiter.unwrap_or(b',');
+    // the csv crate's rules: a quote only opens a quoted field at its start
+    // (elsewhere it is a literal), and inside one a doubled quote is an
+    // escaped quote rather than the end of the field
+    let mut field_start = true;
+    let mut in_quotes = false;
+    let mut closing_quote = false;
+    let mut escaped = false;
+    // the \n of a \r\n ends no record of its own
+    let mut after_cr = false;
+    let mut next_cut = 0;
+    'scan: while starts.is_empty() || next_cut < cuts.len() {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        let consumed = buf.len();
+        for (i, &byte) in buf.iter().enumerate() {
+            let crlf = std::mem::take(&mut after_cr) && byte == b'\n';
+            if escaped {
+                escaped = false;
+                continue;
+            }
+            if closing_quote {
+                closing_quote = false;
+                if Some(byte) == quote {
+                    continue;
+                }
+                in_quotes = false;
+            }
+            if in_quotes {
+                if Some(byte) == opts.escape {
+                    escaped = true;
+                } else if Some(byte) == quote {
+                    closing_quote = true;
+                } else if byte == b'\n' {
+                    lines += 1;
+                }
+                continue;
+            }
+            match byte {
+                _ if field_start && Some(byte) == quote => {
+                    in_quotes = true;
+                    field_start = false;
+                }
+                _ if byte == delimiter => field_start = true,
+                b'\n' if crlf => lines += 1,
+                b'\n' | b'\r' => {
+                    field_start = true;
+                    records += 1;
+                    if byte == b'\n' {
+                        lines += 1;
+                    } else {
+                        after_cr = true;
+                    }
+                    if add_chunk_start(&mut starts, &cuts, &mut next_cut, (offset + i as u64 + 1, lines, records)) {
+                        break 'scan;
+                    }
+                }
+                _ => field_start = false,
+            }
+        }
+        offset += consumed as u64;
+        reader.consume(consumed);
+    }
+
+    let mut chunks: Vec<CsvChunk> = starts
+        .iter()
+        .map(|&(start, lines, record)| CsvChunk {
+            start,
+            end: len,
+            line: lines + 1,
+            record,
+        })
+        .collect();
+    for i in 1..chunks.len() {
+        chunks[i - 1].end = chunks[i].start;
+    }
+    chunks.retain(|chunk| chunk.start < chunk.end);
+    Ok(chunks)
+}
+
+// Takes a record boundary (offset, lines, records) as a chunk start if it ends
+// the header or is the first past the next cut; true once every cut has one.
+fn add_chunk_start(starts: &mut Vec<(u64, u64, u64)>, cuts: &[u64], next_cut: &mut usize, start: (u64, u64, u64)) -> bool {
+    let boundary = start.0;
+    if !starts.is_empty() && boundary < cuts[*next_cut] {
+        return false;
+    }
+    starts.push(start);
+    while *next_cut < cuts.len() && cuts[*next_cut] <= boundary {
+        *next_cut += 1;
+    }
+    *next_cut == cuts.len()
+}
+
+// Inspects each chunk on its own thread into partial row and column stats,
+// then merges them in file order.
+fn read_csv_parallel(
+    filename: &str,
+    opts: &InspectOptions,
+    progress: Option<&ProgressBar>,
+) -> Result<Report, InspectError> {
+    let builder = || csv_builder(opts);
+
+    let file = open_file(filename)?;
+    if file.metadata().map_err(io_error(filename))?.len() == 0 {
+        return Err(InspectError::EmptyInput(filename.to_string()));
+    }
+    let mut reader = builder().has_headers(!opts.no_header).from_reader(file);
+    let headers = csv_headers(&mut reader, opts)?;
+    let selected = select_columns(&headers, opts)?;
+    let chunks = csv_chunks(&open_file(filename)?, opts.threads, opts).map_err(io_error(filename))?;
+
+    let inspect_chunk = |chunk: CsvChunk| -> Result<(RowStats, Vec<Option<ColumnStats>>), InspectError> {
+        // flexible, so the field count is checked against the header rather
+        // than against the first record of the chunk
+        let mut reader = builder().has_headers(false).flexible(true).from_reader(open_file(filename)?);
+        let mut position = csv::Position::new();
+        position.set_byte(chunk.start).set_line(chunk.line).set_record(chunk.record);
+        reader.seek_raw(io::SeekFrom::Start(chunk.start), position)?;
+
+        let mut rows = RowStats::new(opts);
+        rows.fields = headers.len();
+        let mut column_stats: Vec<Option<ColumnStats>> = (0..headers.len()).map(|_| None).collect();
+        let mut record = csv::StringRecord::new();
+        let mut reported = chunk.start;
+        while reader.read_record(&mut record)? {
+            let position = record.position().expect("records read from a reader have a position");
+            if position.byte() >= chunk.end {
+                break;
+            }
+            if opts.strict && record.len() != headers.len() {
+                return Err(InspectError::FieldCount {
+                    record: position.record(),
+                    line: position.line(),
+                    byte: position.byte(),
+                    expected: headers.len(),
+                    found: record.len(),
+                });
+            }
+            csv_record(&record, &headers, &selected, &mut rows, &mut column_stats, opts);
+            if let Some(bar) = progress
+                && rows.rows.is_multiple_of(10_000)
+            {
+                bar.inc(position.byte() - reported);
+                reported = position.byte();
+            }
+        }
+        Ok((rows, column_stats))
+    };
+
+    let partials: Vec<Result<_, InspectError>> = std::thread::scope(|scope| {
+        let workers: Vec<_> = chunks
+            .iter()
+            .map(|&chunk| scope.spawn(move || inspect_chunk(chunk)))
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("chunk worker panicked"))
+            .collect()
+    });
+
+    let mut rows = RowStats::new(opts);
+    rows.fields = headers.len();
+    let mut column_stats: Vec<Option<ColumnStats>> = (0..headers.len()).map(|_| None).collect();
+    for partial in partials {
+        let (chunk_rows, chunk_stats) = partial?;
+        rows.merge(chunk_rows);
+        for (slot, stats) in column_stats.iter_mut().zip(chunk_stats) {
+            match (slot.as_mut(), stats) {
+                (Some(merged), Some(stats)) => merged.merge(&stats),
+                (None, stats) => *slot = stats,
+                (Some(_), None) => {}
+            }
+        }
+    }
+
+    let columns = selected
+        .iter()
+        .map(|&i| (headers[i].clone(), column_stats[i].take()))
+        .collect();
+    Ok(build_report("CSV", &rows, columns, opts))
+}
+
+// Feeds one cell into a column's stats. The column type is first guessed
+// from its first value and upgraded categorical → numeric when a numeric
+// value turns up later. Returns whether the value failed to parse in a column
+// that is numeric by now (a --infer-sample vote replays its values later, so
+// those aren't known here).
+fn update_column(slot: &mut Option<ColumnStats>, name: &str, raw: &str, opts: &InspectOptions) -> bool {
+    // NUL bytes (binary junk, or UTF-16 read as UTF-8) are dropped before
+    // anything else sees the value, and reported by diagnose_column
+    let without_nul = raw.contains('\0').then(|| raw.replace('\0', ""));
+    let value = without_nul.as_deref().unwrap_or(raw);
+    // only the type and the numeric stats go by the normalized number
+    let cleaned = normalize_number(value, opts);
+    let number = cleaned.as_deref().unwrap_or(value);
+    let missing = value.is_empty() || opts.na_values.iter().any(|na| na == value);
+    let stats = slot.get_or_insert_with(|| {
+        let kind = if missing {
+            // temporarily unknown, treat as categorical for now
+            ColumnType::Categorical
+        } else {
+            match infer_type(number) {
+                "integer" | "float" => ColumnType::Numeric,
+                _ => ColumnType::Categorical,
+            }
+        };
+
+        new_column_stats(name, kind, opts)
+            .with_sampled_inference(opts.infer_sample.unwrap_or(0), opts.infer_threshold)
+    });
+
+    if stats.kind == ColumnType::Categorical
+        && !stats.type_locked
+        && stats.sample.is_none()
+        && !missing
+        && matches!(infer_type(number), "integer" | "float")
+    {
+        // Upgrade categorical → numeric
+        stats.promote_to_numeric();
+    }
+
+    if without_nul.is_some() {
+        stats.null_bytes += 1;
+        stats.null_byte_example.get_or_insert_with(|| raw.to_string());
+    }
+    let failures = stats.numeric_parse_failures;
+    let sampling = stats.sample.is_some();
+    stats.update_cell(value, cleaned.as_deref());
+    !sampling && stats.numeric_parse_failures > failures
+}
+
+// Algorithm R: the first `size` rows are kept, then row k takes the place of
+// a random kept one with probability size/k, which leaves every row seen so
+// far equally likely to be in the sample, in one pass and O(size) memory.
+#[derive(Debug)]
+struct Reservoir {
+    size: usize,
+    seed: u64,
+    rng: SplitMix64,
+    // (1-based row, cells)
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+impl Reservoir {
+    fn new(size: usize, seed: u64) -> Self {
+        Self {
+            size,
+            seed,
+            rng: SplitMix64(seed),
+            rows: Vec::with_capacity(size),
+        }
+    }
+
+    // where row `row` goes in the sample, if it gets in
+    fn slot(&mut self, row: usize) -> Option<usize> {
+        if self.rows.len() < self.size {
+            return Some(self.rows.len());
+        }
+        let pick = self.rng.below(row as u64) as usize;
+        (pick < self.size).then_some(pick)
+    }
+
+    fn put(&mut self, slot: usize, row: usize, cells: Vec<String>) {
+        if slot == self.rows.len() {
+            self.rows.push((row, cells));
+        } else {
+            self.rows[slot] = (row, cells);
+        }
+    }
+}
+
+// splitmix64: small, fast and fully determined by the seed, which is all the
+// row sampling needs
+#[derive(Debug)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0..n (Lemire's multiply-shift, bias below 2^-64 per draw)
+    fn below(&mut self, n: u64) -> u64 {
+        ((u128::from(self.next()) * u128::from(n)) >> 64) as u64
+    }
+}
+
+fn clock_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+// Row-level accumulators, kept alongside the per-column stats. Rows are
+// remembered as 64-bit fingerprints so duplicate detection stays cheap.
+#[derive(Debug, Default)]
+struct RowStats {
+    rows: usize,
+    duplicates: usize,
+    fingerprints: HashSet<u64>,
+    correlations: Option<Correlations>,
+    // the first --sample rows, as cells of the inspected columns
+    sample: Option<Vec<Vec<String>>>,
+    sample_size: usize,
+    // the last --tail rows so far, oldest first
+    tail: Option<VecDeque<Vec<String>>>,
+    tail_size: usize,
+    // --random-sample
+    reservoir: Option<Reservoir>,
+    // CSV rows whose field count differs from the header's `fields`, with
+    // (1-based row, field count) of the first few
+    fields: usize,
+    ragged: usize,
+    ragged_examples: Vec<(usize, usize)>,
+    // rows with a value that failed to parse in a numeric column, and the
+    // 1-based numbers of the first few
+    dirty: usize,
+    dirty_examples: Vec<usize>,
+}
+
+impl RowStats {
+    fn new(opts: &InspectOptions) -> Self {
+        Self {
+            correlations: opts.correlate.then(Correlations::default),
+            sample: opts.sample_rows.map(Vec::with_capacity),
+            sample_size: opts.sample_rows.unwrap_or(0),
+            tail: opts.tail_rows.map(|n| VecDeque::with_capacity(n + 1)),
+            tail_size: opts.tail_rows.unwrap_or(0),
+            reservoir: opts.random_sample.map(|size| Reservoir::new(size, opts.seed.unwrap_or_else(clock_seed))),
+            ..Self::default()
+        }
+    }
+
+    // after update, so rows is this row's number
+    fn sample_row(&mut self, cells: impl Iterator<Item = String>) {
+        self.sample_row_at(self.rows, cells);
+    }
+
+    // Cells are only formatted while the sample still has room, for --tail,
+    // or when the row is drawn into the random sample.
+    fn sample_row_at(&mut self, row: usize, cells: impl Iterator<Item = String>) {
+        let head = self.sample.as_mut().filter(|sample| sample.len() < self.sample_size);
+        let slot = self.reservoir.as_mut().and_then(|reservoir| reservoir.slot(row));
+        if head.is_none() && self.tail.is_none() && slot.is_none() {
+            return;
+        }
+
+        let cells: Vec<String> = cells.collect();
+        if let (Some(reservoir), Some(slot)) = (&mut self.reservoir, slot) {
+            reservoir.put(slot, row, cells.clone());
+        }
+        if let Some(tail) = &mut self.tail {
+            tail.push_back(cells.clone());
+            if tail.len() > self.tail_size {
+                tail.pop_front();
+            }
+        }
+        if let Some(sample) = head {
+            sample.push(cells);
+        }
+    }
+
+    // after update, so rows is this row's number
+    fn ragged_row(&mut self, fields: usize) {
+        self.ragged += 1;
+        if self.ragged_examples.len() < MAX_FAILURE_EXAMPLES {
+            self.ragged_examples.push((self.rows, fields));
+        }
+    }
+
+    fn dirty_row(&mut self) {
+        self.dirty += 1;
+        if self.dirty_examples.len() < MAX_FAILURE_EXAMPLES {
+            self.dirty_examples.push(self.rows);
+        }
+    }
+
+    fn update(&mut self, fingerprint: u64) {
+        self.rows += 1;
+        if !self.fingerprints.insert(fingerprint) {
+            self.duplicates += 1;
+        }
+    }
+
+    // `other` covers the rows right after these; a row already seen here
+    // is one more duplicate
+    fn merge(&mut self, other: RowStats) {
+        let room = MAX_FAILURE_EXAMPLES - self.ragged_examples.len();
+        self.ragged_examples
+            .extend(other.ragged_examples.iter().take(room).map(|&(row, fields)| (row + self.rows, fields)));
+        self.ragged += other.ragged;
+        let room = MAX_FAILURE_EXAMPLES - self.dirty_examples.len();
+        self.dirty_examples
+            .extend(other.dirty_examples.iter().take(room).map(|&row| row + self.rows));
+        self.dirty += other.dirty;
+        self.rows += other.rows;
+        self.duplicates += other.duplicates;
+        for fingerprint in other.fingerprints {
+            if !self.fingerprints.insert(fingerprint) {
+                self.duplicates += 1;
+            }
+        }
+        if let (Some(correlations), Some(other)) = (&mut self.correlations, &other.correlations) {
+            correlations.merge(other);
+        }
+        if let (Some(sample), Some(other)) = (&mut self.sample, other.sample) {
+            let room = self.sample_size - sample.len();
+            sample.extend(other.into_iter().take(room));
+        }
+        if let (Some(tail), Some(other)) = (&mut self.tail, other.tail) {
+            tail.extend(other);
+            let excess = tail.len().saturating_sub(self.tail_size);
+            tail.drain(..excess);
+        }
+    }
+}
+
+// Streaming co-moments for one pair of columns, the two-variable version of
+// the Welford update in ColumnStats. Only rows where both values are numbers
+// contribute.
+#[derive(Debug, Default, Clone)]
+struct CoMoment {
+    n: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl CoMoment {
+    fn update(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        let n = self.n as f64;
+        let dx = x - self.mean_x;
+        self.mean_x += dx / n;
+        let dy = y - self.mean_y;
+        self.mean_y += dy / n;
+
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    // Chan et al.'s pairwise combination, the same as ColumnStats::merge
+    fn merge(&mut self, other: &CoMoment) {
+        if other.n == 0 {
+            return;
+        }
+        let (na, nb) = (self.n as f64, other.n as f64);
+        let n = na + nb;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+
+        self.mean_x += dx * nb / n;
+        self.mean_y += dy * nb / n;
+        self.m2_x += other.m2_x + dx * dx * na * nb / n;
+        self.m2_y += other.m2_y + dy * dy * na * nb / n;
+        self.c_xy += other.c_xy + dx * dy * na * nb / n;
+        self.n += other.n;
+    }
+
+    // None when the pair shares fewer than two rows or either side is constant
+    fn pearson(&self) -> Option<f64> {
+        if self.n < 2 || self.m2_x <= 0.0 || self.m2_y <= 0.0 {
+            return None;
+        }
+        Some((self.c_xy / (self.m2_x * self.m2_y).sqrt()).clamp(-1.0, 1.0))
+    }
+}
+
+// Pairwise accumulators over every inspected column, in report order. Column
+// types are only settled at the end, so all pairs are tracked and the
+// non-numeric ones dropped when the matrix is built. pairs[j][i] holds (i, j)
+// for i < j; columns discovered later (JSON) just add rows.
+#[derive(Debug, Default)]
+struct Correlations {
+    pairs: Vec<Vec<CoMoment>>,
+}
+
+impl Correlations {
+    fn update(&mut self, values: impl Iterator<Item = Option<f64>>) {
+        let values: Vec<Option<f64>> = values.collect();
+        while self.pairs.len() < values.len() {
+            let j = self.pairs.len();
+            self.pairs.push(vec![CoMoment::default(); j]);
+        }
+
+        for (j, y) in values.iter().enumerate() {
+            let Some(y) = *y else { continue };
+            for (i, x) in values[..j].iter().enumerate() {
+                if let Some(x) = *x {
+                    self.pairs[j][i].update(x, y);
+                }
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &Correlations) {
+        while self.pairs.len() < other.pairs.len() {
+            let j = self.pairs.len();
+            self.pairs.push(vec![CoMoment::default(); j]);
+        }
+        for (row, other_row) in self.pairs.iter_mut().zip(&other.pairs) {
+            for (pair, other_pair) in row.iter_mut().zip(other_row) {
+                pair.merge(other_pair);
+            }
+        }
+    }
+
+    fn matrix(&self, columns: &[ColumnReport]) -> CorrelationMatrix {
+        let numeric: Vec<usize> = (0..columns.len())
+            .filter(|&i| columns[i].kind == Some(ColumnType::Numeric))
+            .collect();
+
+        let pair = |a: usize, b: usize| {
+            let (i, j) = if a < b { (a, b) } else { (b, a) };
+            self.pairs.get(j).and_then(|row| row.get(i)).and_then(CoMoment::pearson)
+        };
+
+        let values = numeric
+            .iter()
+            .map(|&a| {
+                numeric
+                    .iter()
+                    .map(|&b| {
+                        if a == b {
+                            // a constant column correlates with nothing, itself included
+                            let stddev = columns[a].summary.as_ref().and_then(|s| s.stddev);
+                            stddev.filter(|&sd| sd > 0.0).map(|_| 1.0)
+                        } else {
+                            pair(a, b)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        CorrelationMatrix {
+            columns: numeric.iter().map(|&i| columns[i].name.clone()).collect(),
+            values,
+        }
+    }
+}
+
+// str's Hash impl terminates each field, so ("a,b", "c") and ("a", "b,c") differ
+fn row_fingerprint<'a>(fields: impl Iterator<Item = &'a str>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for field in fields {
+        field.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn new_column_stats(name: &str, kind: ColumnType, opts: &InspectOptions) -> ColumnStats {
+    let stats = ColumnStats::new(name, kind)
+        .with_max_uniques(opts.max_uniques)
+        .with_outlier_sigma(opts.outlier_sigma)
+        .with_outlier_method(opts.outlier_method)
+        .with_na_values(&opts.na_values)
+        .with_value_range(opts.range_min, opts.range_max);
+    let (date_min, date_max) = opts.date_bounds();
+    let stats = stats.with_date_range(date_min, date_max);
+    let stats = match opts.coerce.iter().find(|(column, _)| column == name) {
+        Some(&(_, kind)) => stats.with_coerced_type(kind),
+        None => stats,
+    };
+    let stats = match opts.validate.iter().find(|(column, _)| column == name) {
+        Some((_, pattern)) => stats.with_validator(pattern.clone()),
+        None => stats,
+    };
+    let stats = if opts.approx_distinct {
+        stats.with_approx_distinct()
+    } else {
+        stats
+    };
+    let stats = if opts.explain {
+        stats.with_type_evidence()
+    } else {
+        stats
+    };
+    let stats = if opts.fingerprint {
+        stats.with_fingerprint()
+    } else {
+        stats
+    };
+    let stats = if opts.benford {
+        stats.with_benford()
+    } else {
+        stats
+    };
+    if opts.buffers_values() {
+        stats.with_value_buffer()
+    } else {
+        stats
+    }
+}
+
+fn build_report(
+    file_type: &'static str,
+    row_stats: &RowStats,
+    columns: Vec<(String, Option<ColumnStats>)>,
+    opts: &InspectOptions,
+) -> Report {
+    let rows = row_stats.rows;
+    let mut columns = columns;
+    for stats in columns.iter_mut().filter_map(|(_, stats)| stats.as_mut()) {
+        stats.finalize();
+    }
+    let quality = quality_score(row_stats, &columns, opts);
+
+    let reports: Vec<ColumnReport> = columns
+        .iter()
+        .map(|(name, stats)| match stats {
+            Some(stats) => ColumnReport {
+                name: stats.name.clone(),
+                kind: Some(stats.kind),
+                numeric_type: stats.numeric_type(),
+                large_integers: stats.large_integers(),
+                large_integers_past_i64: stats.large_integers_past_i64 > 0,
+                semantic_type: stats.semantic_type(),
+                summary: Some(summarize_column(stats, opts)),
+                diagnostics: Some(diagnose_column(stats, rows, opts)),
+                inference: stats.type_inference(opts.skip),
+                fingerprint: stats.fingerprint.as_ref().map(ContentFingerprint::hex),
+                benford: stats.benford.as_ref().and_then(BenfordTally::test),
+                json_kinds: stats.json_kinds,
+                presence: stats.json_kinds.map(|k| k.presence()),
+            },
+            None => ColumnReport {
+                name: name.clone(),
+                kind: None,
+                numeric_type: None,
+                large_integers: None,
+                large_integers_past_i64: false,
+                semantic_type: None,
+                summary: None,
+                diagnostics: None,
+                inference: None,
+                fingerprint: None,
+                benford: None,
+                json_kinds: None,
+                presence: None,
+            },
+        })
+        .collect();
+
+    // the summaries are computed, so the buffered values can go
+    let stats: Vec<Option<ColumnStats>> = columns
+        .into_iter()
+        .map(|(_, stats)| {
+            stats.map(|mut stats| {
+                stats.values = None;
+                stats
+            })
+        })
+        .collect();
+    let columns = reports;
+
+    let mut diagnostics = Vec::new();
+    if row_stats.duplicates > 0 {
+        diagnostics.push(Diagnostic::new(
+            "duplicates",
+            Severity::Warning,
+            format!(
+                "duplicate rows: {} ({:.1}%)",
+                row_stats.duplicates,
+                row_stats.duplicates as f64 / rows as f64 * 100.0
+            ),
+        ));
+    }
+
+    if row_stats.ragged > 0 {
+        let mut examples: Vec<String> = row_stats
+            .ragged_examples
+            .iter()
+            .map(|(row, fields)| {
+                format!("row {}: {} field{}", row + opts.skip, fields, if *fields == 1 { "" } else { "s" })
+            })
+            .collect();
+        if row_stats.ragged > examples.len() {
+            examples.push("...".to_string());
+        }
+        diagnostics.push(Diagnostic::new(
+            "ragged",
+            Severity::Warning,
+            format!(
+                "ragged rows: {} with a field count other than the header's {} ({})",
+                row_stats.ragged,
+                row_stats.fields,
+                examples.join(", ")
+            ),
+        ));
+    }
+    // whether the unparsable values are bunched in a few rows (which can be
+    // dropped) or spread over many (a systematic problem)
+    if row_stats.dirty > 0 {
+        let failures: usize = stats.iter().flatten().map(|s| s.numeric_parse_failures).sum();
+        let mut examples: Vec<String> =
+            row_stats.dirty_examples.iter().map(|row| (row + opts.skip).to_string()).collect();
+        if row_stats.dirty > examples.len() {
+            examples.push("...".to_string());
+        }
+        diagnostics.push(Diagnostic::new(
+            "dirty-rows",
+            Severity::Warning,
+            format!(
+                "dirty rows: {} ({:.1}%) with a value that doesn't parse in a numeric column, {} such value{} in all (row{} {})",
+                row_stats.dirty,
+                row_stats.dirty as f64 / rows as f64 * 100.0,
+                failures,
+                if failures == 1 { "" } else { "s" },
+                if row_stats.dirty == 1 { "" } else { "s" },
+                examples.join(", ")
+            ),
+        ));
+    }
+    if rows == 0 && !columns.is_empty() {
+        diagnostics.push(Diagnostic::new(
+            "no-rows",
+            Severity::Warning,
+            "no data rows: the file has a header but nothing under it".to_string(),
+        ));
+    }
+    diagnostics.retain(|d| opts.runs_check(d.check));
+
+    Report {
+        file_type,
+        rows,
+        limited: opts.limit.is_some_and(|limit| rows >= limit),
+        skipped_rows: opts.skip,
+        duplicate_rows: row_stats.duplicates,
+        ragged_rows: row_stats.ragged,
+        schema: schema_counts(&columns),
+        diagnostics,
+        quality,
+        correlations: row_stats.correlations.as_ref().map(|c| c.matrix(&columns)),
+        sample: row_stats.sample.clone().map(|rows| SampleRows {
+            columns: columns.iter().map(|c| c.name.clone()).collect(),
+            rows,
+        }),
+        tail: row_stats.tail.clone().map(|rows| SampleRows {
+            columns: columns.iter().map(|c| c.name.clone()).collect(),
+            rows: rows.into(),
+        }),
+        random_sample: row_stats.reservoir.as_ref().map(|reservoir| {
+            let mut drawn = reservoir.rows.clone();
+            drawn.sort_by_key(|&(row, _)| row);
+            let (row_numbers, rows) = drawn.into_iter().map(|(row, cells)| (row + opts.skip, cells)).unzip();
+            RandomSample {
+                seed: reservoir.seed,
+                row_numbers,
+                sample: SampleRows {
+                    columns: columns.iter().map(|c| c.name.clone()).collect(),
+                    rows,
+                },
+            }
+        }),
+        columns,
+        groups: None,
+        stats,
+    }
+}
+
+// Headline 0-100 score: each check can cost at most `weight` points, scaled by
+// how much of the dataset it affects, so the breakdown always adds up
+fn quality_score(
+    row_stats: &RowStats,
+    columns: &[(String, Option<ColumnStats>)],
+    opts: &InspectOptions,
+) -> QualityScore {
+    let rows = row_stats.rows;
+    let stats: Vec<&ColumnStats> = columns.iter().filter_map(|(_, s)| s.as_ref()).collect();
+    let share = |n: usize, of: usize| if of == 0 { 0.0 } else { n as f64 / of as f64 };
+
+    let is_empty = |s: &ColumnStats| s.total > 0 && s.missing == s.total;
+    let filled: Vec<&ColumnStats> = stats.iter().copied().filter(|s| !is_empty(s)).collect();
+    let missing: usize = stats.iter().map(|s| s.missing).sum();
+    let empty = stats.len() - filled.len();
+    let mixed = filled
+        .iter()
+        .filter(|s| match s.kind {
+            ColumnType::Numeric => s.numeric_parse_failures > 0,
+            ColumnType::DateTime => s.total - s.missing > s.date_count,
+            _ => false,
+        })
+        .count();
+    let numeric = filled.iter().filter(|s| s.kind == ColumnType::Numeric).count();
+    let outliers = filled
+        .iter()
+        .filter(|s| s.kind == ColumnType::Numeric && s.outlier_count > 0)
+        .count();
+    let categorical: Vec<&&ColumnStats> =
+        filled.iter().filter(|s| s.kind == ColumnType::Categorical).collect();
+    let identifiers = categorical
+        .iter()
+        .filter(|s| s.high_cardinality(rows - s.missing, opts.cardinality_threshold))
+        .count();
+
+    let penalties = vec![
+        QualityPenalty::new("missing values", 30.0, share(missing, rows * stats.len())),
+        QualityPenalty::new("mixed types", 20.0, share(mixed, filled.len())),
+        QualityPenalty::new("empty columns", 15.0, share(empty, stats.len())),
+        QualityPenalty::new("duplicate rows", 15.0, share(row_stats.duplicates, rows)),
+        QualityPenalty::new("outliers", 10.0, share(outliers, numeric)),
+        QualityPenalty::new("high cardinality", 10.0, share(identifiers, categorical.len())),
+    ];
+    let lost: f64 = penalties.iter().map(|p| p.points).sum();
+
+    QualityScore {
+        score: round1(100.0 - lost).max(0.0),
+        penalties,
+    }
+}
+
+fn round1(x: f64) -> f64 {
+    (x * 10.0).round() / 10.0
+}
+
+// ordered (key, value) pairs as a JSON object
+fn serialize_pairs<S: serde::Serializer>(
+    pairs: &[(&'static str, usize)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(pairs.iter().copied())
+}
+
+fn schema_counts(columns: &[ColumnReport]) -> Vec<(&'static str, usize)> {
+    let mut counts: Vec<(&'static str, usize)> = ColumnType::ALL
+        .iter()
+        .map(|&kind| (kind.as_str(), columns.iter().filter(|c| c.kind == Some(kind)).count()))
+        .collect();
+    counts.push(("unknown", columns.iter().filter(|c| c.kind.is_none()).count()));
+    counts.retain(|&(_, count)| count > 0);
+    counts
+}
+
+/// Writes the report as JSON with `opts.json`, as the text report otherwise.
+pub fn emit_report(out: &mut dyn Write, report: &Report, opts: &InspectOptions) -> io::Result<()> {
+    if opts.json {
+        write_json(out, report, opts.json_pretty)?;
+        writeln!(out)
+    } else {
+        print_report(out, report, opts)
+    }
+}
+
+/// Serializes `value` on one line, or indented when `pretty`, with no
+/// trailing newline.
+pub fn write_json<T: Serialize + ?Sized>(out: &mut dyn Write, value: &T, pretty: bool) -> io::Result<()> {
+    if pretty {
+        serde_json::to_writer_pretty(&mut *out, value)?;
+    } else {
+        serde_json::to_writer(&mut *out, value)?;
+    }
+    Ok(())
+}
+
+// Interactive exploration of one report: the file is parsed once and each
+// command re-renders part of it, so nothing is read again.
+
+const EXPLORE_HELP: &str = "commands:
+  columns               list the columns and their types
+  types                 inferred type of every column
+  summary [column]      summary of every column, or of one
+  diagnose [column]     data quality report, or one column's diagnostics
+  top <column> [n]      the n most frequent values (default 10)
+  help                  show this list
+  quit                  leave (also exit, or end of input)";
+
+/// Runs a command loop over an already built report, reading commands from
+/// `input` until `quit` or end of input.
+pub fn explore(
+    report: &Report,
+    opts: &InspectOptions,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(out, "type help for the list of commands")?;
+    let mut line = String::new();
+    loop {
+        write!(out, "> ")?;
+        out.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return writeln!(out);
+        }
+
+        let words = tokenize(&line);
+        let Some((command, args)) = words.split_first() else {
+            continue;
+        };
+        match (command.as_str(), args) {
+            ("quit" | "exit", _) => return Ok(()),
+            ("help", _) => writeln!(out, "{}", EXPLORE_HELP)?,
+            ("columns", _) => {
+                for column in &report.columns {
+                    let dtype = column.kind.map_or("unknown", ColumnType::as_str);
+                    writeln!(out, "  {} ({})", column.name, dtype)?;
+                }
+            }
+            ("types", _) => print_types(out, report)?,
+            ("summary", []) => print_summary(out, report, opts)?,
+            ("diagnose", []) => print_diagnose(out, report, opts)?,
+            ("summary" | "diagnose" | "top", [name, rest @ ..]) => {
+                let Some(i) = find_column(report, name) else {
+                    writeln!(out, "unknown column: {}", name)?;
+                    continue;
+                };
+                match command.as_str() {
+                    "summary" => print_column_summary(out, &report.columns[i])?,
+                    "diagnose" => {
+                        print_column_diagnostics(out, &report.columns[i], opts.color)?;
+                    }
+                    _ => match rest.first().map(|n| n.parse::<usize>()) {
+                        None => print_top_values(out, report, i, 10)?,
+                        Some(Ok(n)) => print_top_values(out, report, i, n)?,
+                        Some(Err(_)) => writeln!(out, "invalid count: {}", rest[0])?,
+                    },
+                }
+            }
+            ("top", []) => writeln!(out, "usage: top <column> [n]")?,
+            _ => writeln!(out, "unknown command: {} (type help)", command)?,
+        }
+    }
+}
+
+// by name, or by 0-based index like --columns
+fn find_column(report: &Report, name: &str) -> Option<usize> {
+    report
+        .columns
+        .iter()
+        .position(|c| c.name == name)
+        .or_else(|| name.parse().ok().filter(|&i| i < report.columns.len()))
+}
+
+fn print_top_values(out: &mut dyn Write, report: &Report, index: usize, n: usize) -> io::Result<()> {
+    let column = &report.columns[index];
+    let Some(stats) = &report.stats[index] else {
+        return writeln!(out, "{}: no values", column.name);
+    };
+    // numeric columns keep moments, not value counts
+    if stats.kind == ColumnType::Numeric {
+        return writeln!(out, "{}: value counts are only kept for non-numeric columns", column.name);
+    }
+
+    let count = (stats.total - stats.missing).max(1);
+    for (value, c) in stats.top_values(n) {
+        let percent = c as f64 / count as f64 * 100.0;
+        writeln!(out, "  {}: {} ({:.1}%)", escape_cell(value), c, percent)?;
+    }
+    if stats.other_count > 0 {
+        let percent = stats.other_count as f64 / count as f64 * 100.0;
+        writeln!(out, "  (others): {} ({:.1}%)", stats.other_count, percent)?;
+    }
+    Ok(())
+}
+
+// whitespace-separated words; double or single quotes keep spaces in names
+fn tokenize(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut in_word = false;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+// --columns first, then --exclude-columns on what is left
+fn select_columns(headers: &[String], opts: &InspectOptions) -> Result<Vec<usize>, InspectError> {
+    let mut selected = match &opts.columns {
+        Some(spec) => resolve_columns(headers, spec)?,
+        None => (0..headers.len()).collect(),
+    };
+    if let Some(spec) = &opts.exclude_columns {
+        let excluded = resolve_columns(headers, spec)?;
+        selected.retain(|i| !excluded.contains(i));
+    }
+    Ok(selected)
+}
+
+// Resolves a comma-separated list of header names or 0-based indices,
+// keeping the file's column order. Names take precedence over indices.
+fn resolve_columns(headers: &[String], spec: &str) -> Result<Vec<usize>, InspectError> {
+    let mut selected = Vec::new();
+
+    for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let index = headers
+            .iter()
+            .position(|h| h == name)
+            .or_else(|| name.parse::<usize>().ok().filter(|&i| i < headers.len()))
+            .ok_or_else(|| InspectError::UnknownColumn(name.to_string()))?;
+
+        if !selected.contains(&index) {
+            selected.push(index);
+        }
+    }
+
+    selected.sort_unstable();
+    Ok(selected)
+}
+
+// Structured report, rendered either as text or as JSON
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub file_type: &'static str,
+    pub rows: usize,
+    // stats only cover the first --limit rows
+    pub limited: bool,
+    // rows passed over with --skip before the inspected ones
+    pub skipped_rows: usize,
+    pub duplicate_rows: usize,
+    // CSV rows with more or fewer fields than the header
+    pub ragged_rows: usize,
+    // number of columns per inferred type, in ColumnType order
+    #[serde(serialize_with = "serialize_pairs")]
+    pub schema: Vec<(&'static str, usize)>,
+    // dataset-level findings; per-column ones live on each column
+    pub diagnostics: Vec<Diagnostic>,
+    pub quality: QualityScore,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlations: Option<CorrelationMatrix>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<SampleRows>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tail: Option<SampleRows>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_sample: Option<RandomSample>,
+    pub columns: Vec<ColumnReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<GroupedSummary>,
+    // finalized stats behind each entry of `columns`, for drilling down
+    // (e.g. value counts in --interactive)
+    #[serde(skip)]
+    stats: Vec<Option<ColumnStats>>,
+}
+
+/// With --group-by: the summaries of the other columns for each value of
+/// `column`, in value order.
+#[derive(Debug, Serialize)]
+pub struct GroupedSummary {
+    pub column: String,
+    pub groups: Vec<GroupSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupSummary {
+    // empty for the rows where the grouping column is missing
+    pub value: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnReport>,
+}
+
+// the first --sample (or last --tail) rows as raw cells; rows may be shorter
+// than columns when a JSON field only shows up later in the file
+#[derive(Debug, Serialize)]
+pub struct SampleRows {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Rows drawn uniformly at random, in file order, with their 1-based row
+/// numbers and the seed that reproduces the draw.
+#[derive(Debug, Serialize)]
+pub struct RandomSample {
+    pub seed: u64,
+    pub row_numbers: Vec<usize>,
+    #[serde(flatten)]
+    pub sample: SampleRows,
+}
+
+impl Report {
+    /// Dataset-level diagnostics first, then each column's.
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .chain(self.columns.iter().flat_map(|c| c.diagnostics.iter().flatten()))
+    }
+
+    /// The inferred schema as a draft-07 JSON Schema for one record: a
+    /// property per column with its type, `null` allowed where values were
+    /// missing, and an `enum` of the observed values for low-cardinality
+    /// categorical columns. Fields every record has are required, and no
+    /// others are allowed. JSON, YAML and TOML fields take the kinds of value
+    /// they held; other formats, the inferred column type.
+    pub fn json_schema(&self, title: &str) -> serde_json::Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+        for (column, stats) in self.columns.iter().zip(&self.stats) {
+            let Some(stats) = stats else {
+                properties.insert(column.name.clone(), serde_json::json!({}));
+                continue;
+            };
+            properties.insert(column.name.clone(), column_schema(stats));
+            if column.presence.is_none_or(|p| p >= 1.0) {
+                required.push(column.name.clone());
+            }
+        }
+
+        serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": title,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+}
+
+// at most this many distinct values, each seen more than once on average,
+// make an `enum` in --json-schema
+const SCHEMA_ENUM_MAX: usize = 20;
+
+fn column_schema(stats: &ColumnStats) -> serde_json::Value {
+    let number = if stats.numeric_type() == Some("integer") { "integer" } else { "number" };
+    let types: Vec<&str> = match &stats.json_kinds {
+        Some(kinds) => [
+            (kinds.number, number),
+            (kinds.string, "string"),
+            (kinds.boolean, "boolean"),
+            (kinds.array, "array"),
+            (kinds.object, "object"),
+            (kinds.null, "null"),
+        ]
+        .into_iter()
+        .filter(|&(count, _)| count > 0)
+        .map(|(_, name)| name)
+        .collect(),
+        None => {
+            let kind = match stats.kind {
+                ColumnType::Numeric => number,
+                ColumnType::Boolean => "boolean",
+                ColumnType::Categorical | ColumnType::DateTime => "string",
+            };
+            let mut types = vec![kind];
+            if stats.missing > 0 {
+                types.push("null");
+            }
+            types
+        }
+    };
+
+    let nullable = types.contains(&"null");
+    let text = types.iter().all(|&t| t == "string" || t == "null");
+    let mut schema = serde_json::Map::new();
+    match types[..] {
+        [] => {}
+        [only] => {
+            schema.insert("type".to_string(), only.into());
+        }
+        _ => {
+            schema.insert("type".to_string(), types.into());
+        }
+    }
+    if stats.kind == ColumnType::DateTime {
+        let format = if stats.date_has_time { "date-time" } else { "date" };
+        schema.insert("format".to_string(), format.into());
+    }
+
+    let non_missing = stats.total - stats.missing;
+    let distinct = stats.uniques.len();
+    if stats.kind == ColumnType::Categorical
+        && text
+        && !stats.uniques_capped()
+        && stats.distinct_estimate().is_none()
+        && distinct > 0
+        && distinct <= SCHEMA_ENUM_MAX
+        && distinct * 2 <= non_missing
+    {
+        let mut values: Vec<serde_json::Value> = stats.uniques.keys().map(|v| v.as_str().into()).collect();
+        if nullable {
+            values.push(serde_json::Value::Null);
+        }
+        values.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        schema.insert("enum".to_string(), values.into());
+    }
+    schema.into()
+}
+
+// Ordered by how bad a finding is, so `>=` compares against --fail-on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Error, Severity::Warning, Severity::Info];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Severity::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+}
+
+/// Names of the checks behind the diagnostics, for --only-check and
+/// --disable-check.
+pub const CHECKS: &[&str] = &[
+    "duplicates",
+    "ragged",
+    "missing",
+    "cardinality",
+    "whitespace",
+    "max-length",
+    "constant",
+    "primary-key",
+    "zero-padded",
+    "mixed-type",
+    "negatives",
+    "outliers",
+    "null-bytes",
+    "type-upgrade",
+    "no-rows",
+    "range",
+    "date-range",
+    "benford",
+    "mixed-kinds",
+    "sparse",
+    "dirty-rows",
+    "zero-inflated",
+    "validate",
+    "large-integers",
+];
+
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    // one of CHECKS
+    pub check: &'static str,
+    pub severity: Severity,
+    // the column it is about; none for findings on the rows as a whole
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(check: &'static str, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity,
+            column: None,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QualityScore {
+    pub score: f64,
+    pub penalties: Vec<QualityPenalty>,
+}
+
+// points lost on one check: `ratio` of the dataset affected times `weight`
+#[derive(Debug, Serialize)]
+pub struct QualityPenalty {
+    pub check: &'static str,
+    pub weight: f64,
+    pub ratio: f64,
+    pub points: f64,
+}
+
+impl QualityPenalty {
+    fn new(check: &'static str, weight: f64, ratio: f64) -> Self {
+        Self {
+            check,
+            weight,
+            ratio,
+            points: round1(weight * ratio),
+        }
+    }
+}
+
+// Pearson coefficients between the numeric columns, row-major; null where a
+// pair is undefined
+#[derive(Debug, Serialize)]
+pub struct CorrelationMatrix {
+    pub columns: Vec<String>,
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnReport {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<ColumnType>,
+    // "integer" or "float" for numeric columns with at least one value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numeric_type: Option<&'static str>,
+    // integers past ±2^53 a numeric column left out of its summary, and
+    // whether any of them is past the i64 range too
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_integers: Option<usize>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub large_integers_past_i64: bool,
+    // what a categorical column's values appear to be, e.g. email addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_type: Option<SemanticType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ColumnSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Vec<Diagnostic>>,
+    // with --explain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inference: Option<TypeInference>,
+    // with --fingerprint: 16 hex digits, equal for the same values in any row order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    // with --benford, for numeric columns with enough values over a wide range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub benford: Option<BenfordTest>,
+    // JSON, YAML and TOML input: the kinds of value the field held, and the
+    // share of records that have it (null or not)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_kinds: Option<JsonKinds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence: Option<f64>,
+}
+
+/// Why a column got its type: how many values parse as each type on their
+/// own (1 and 0 count as integers, not booleans), how many failed to parse as
+/// numbers in a numeric column, and the rule that decided.
+#[derive(Debug, Serialize)]
+pub struct TypeInference {
+    pub integers: usize,
+    // integers past ±2^53, counted apart: they keep a column text, and a
+    // numeric column leaves them out of its stats
+    pub large_integers: usize,
+    pub floats: usize,
+    pub booleans: usize,
+    pub datetimes: usize,
+    pub strings: usize,
+    pub parse_failures: usize,
+    // row of the number that turned a text column numeric, discarding the
+    // values before it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upgraded_at: Option<usize>,
+    pub rule: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnSummary {
+    pub count: usize,
+    pub missing: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stddev: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skewness: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kurtosis: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_z: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_z: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q1: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q3: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<Vec<HistogramBin>>,
+    // how many numbers are below, at and above zero
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zero: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub positive: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique: Option<usize>,
+    // set when --max-uniques stopped tracking, making unique a lower bound
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub unique_capped: bool,
+    // relative standard error when unique is a --approx-distinct estimate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_error: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<Vec<ValueCount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub others: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_length: Option<f64>,
+    // smallest and largest values by code point order, and the shortest and
+    // longest ones, of categorical columns
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub false_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earliest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest: Option<String>,
+    // the values carried Z or an offset and were normalized to UTC
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub zoned: bool,
+    // latest - earliest, in seconds and spelled out ("41 days 06:30:00")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<String>,
+    // calendar days with at least one value, and the days in the range without
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distinct_days: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_days: Option<usize>,
+    // "increasing", "non-decreasing" or "unordered", once there are two values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+    pub percent: f64,
+}
+
+// "≥N" when the count stopped at --max-uniques and "≈N" for an estimate;
+// sep goes before an exact count
+fn unique_label(summary: &ColumnSummary, sep: &str) -> String {
+    match summary.unique {
+        Some(unique) if summary.unique_capped => format!("≥{}", unique),
+        Some(unique) if summary.unique_error.is_some() => format!("≈{}", unique),
+        Some(unique) => format!("{}{}", sep, unique),
+        None if sep.is_empty() => "-".to_string(),
+        None => format!("{}0", sep),
+    }
+}
+
+fn summarize_column(stats: &ColumnStats, opts: &InspectOptions) -> ColumnSummary {
+    let count = stats.total - stats.missing;
+    let mut summary = ColumnSummary {
+        count,
+        missing: stats.missing,
+        min: None,
+        max: None,
+        mean: None,
+        stddev: None,
+        skewness: None,
+        kurtosis: None,
+        min_z: None,
+        max_z: None,
+        q1: None,
+        median: None,
+        q3: None,
+        histogram: None,
+        negative: None,
+        zero: None,
+        positive: None,
+        unique: None,
+        unique_capped: false,
+        unique_error: None,
+        mode: None,
+        top: None,
+        others: None,
+        max_length: None,
+        avg_length: None,
+        min_value: None,
+        max_value: None,
+        shortest: None,
+        longest: None,
+        true_count: None,
+        false_count: None,
+        true_ratio: None,
+        earliest: None,
+        latest: None,
+        zoned: false,
+        span_seconds: None,
+        span: None,
+        distinct_days: None,
+        gap_days: None,
+        order: None,
+    };
+
+    match stats.kind {
+        ColumnType::Numeric => {
+            // stats.count leaves out large integers and unparsed values
+            if stats.count > 0 {
+                summary.min = stats.min;
+                summary.max = stats.max;
+                summary.mean = Some(stats.mean);
+                summary.stddev = Some(stats.stddev().unwrap_or(0.0));
+                summary.skewness = stats.skewness();
+                summary.kurtosis = stats.kurtosis();
+                summary.min_z = stats.min_z;
+                summary.max_z = stats.max_z;
+                summary.negative = Some(stats.negatives);
+                summary.zero = Some(stats.zeros);
+                summary.positive = Some(stats.positives);
+                if stats.count > 1 {
+                    summary.order = Some(stats.number_order.as_str());
+                }
+                if let Some(bins) = opts.histogram_bins {
+                    summary.histogram = stats.histogram(bins);
+                }
+                if opts.show_percentiles {
+                    summary.q1 = stats.quantile(0.25);
+                    summary.median = stats.quantile(0.5);
+                    summary.q3 = stats.quantile(0.75);
+                }
+            }
+        }
+        ColumnType::Categorical => {
+            summary.unique = Some(stats.distinct_count());
+            summary.unique_capped = stats.uniques_capped();
+            summary.unique_error = stats.distinct_estimate().map(HyperLogLog::error);
+            summary.mode = stats.top_values(1).first().map(|(value, _)| value.to_string());
+
+            if let Some(n) = opts.top {
+                let percent = |c: usize| c as f64 / count.max(1) as f64 * 100.0;
+                summary.top = Some(
+                    stats
+                        .top_values(n)
+                        .into_iter()
+                        .map(|(value, c)| ValueCount {
+                            value: value.to_string(),
+                            count: c,
+                            percent: percent(c),
+                        })
+                        .collect(),
+                );
+            }
+            if stats.other_count > 0 {
+                summary.others = Some(stats.other_count);
+            }
+            if count > 0 {
+                summary.max_length = Some(stats.max_length);
+                summary.avg_length = Some(stats.total_length as f64 / count as f64);
+            }
+            summary.min_value = stats.min_text.clone();
+            summary.max_value = stats.max_text.clone();
+            summary.shortest = stats.shortest.clone();
+            summary.longest = stats.longest.clone();
+        }
+        ColumnType::DateTime => {
+            summary.unique = Some(stats.distinct_count());
+            summary.unique_capped = stats.uniques_capped();
+            summary.unique_error = stats.distinct_estimate().map(HyperLogLog::error);
+            let format = |ts| {
+                let mut text = format_datetime(ts, stats.date_has_time);
+                if stats.date_has_zone {
+                    text.push('Z');
+                }
+                text
+            };
+            summary.earliest = stats.min_date.map(format);
+            summary.latest = stats.max_date.map(format);
+            summary.zoned = stats.date_has_zone;
+            if let (Some(min), Some(max)) = (stats.min_date, stats.max_date) {
+                let span = max - min;
+                summary.span_seconds = Some(span);
+                summary.span = Some(format_duration(span, stats.date_has_time));
+                let days = (max.div_euclid(86_400) - min.div_euclid(86_400) + 1) as usize;
+                summary.distinct_days = Some(stats.date_days.len());
+                summary.gap_days = Some(days - stats.date_days.len());
+            }
+            if stats.date_count > 1 {
+                summary.order = Some(stats.date_order.as_str());
+            }
+        }
+        ColumnType::Boolean => {
+            summary.true_count = Some(stats.true_count);
+            summary.false_count = Some(stats.false_count);
+            if count > 0 {
+                summary.true_ratio = Some(stats.true_count as f64 / count as f64);
+            }
+        }
+    }
+
+    summary
+}
+
+fn print_report(out: &mut dyn Write, report: &Report, opts: &InspectOptions) -> io::Result<()> {
+    if !opts.quiet {
+        print_preamble(out, report)?;
+    }
+
+    if let Some(sample) = &report.sample {
+        // after --skip the sample isn't the head of the file, so name its rows
+        match (report.skipped_rows, sample.rows.len()) {
+            (0, n) | (_, n @ 0) => writeln!(out, "Sample (first {} rows):", n)?,
+            (skipped, n) => writeln!(out, "Sample (rows {}-{}):", skipped + 1, skipped + n)?,
+        }
+        print_sample(out, sample)?;
+    }
+
+    if let Some(tail) = &report.tail {
+        match tail.rows.len() {
+            0 => writeln!(out, "Tail (last 0 rows):")?,
+            n => writeln!(out, "Tail (rows {}-{} of {}):", report.rows + 1 - n, report.rows, report.rows)?,
+        }
+        print_sample(out, tail)?;
+    }
+
+    if let Some(random) = &report.random_sample {
+        writeln!(
+            out,
+            "Random sample ({} of {} rows, seed {}):",
+            random.row_numbers.len(),
+            report.rows,
+            random.seed
+        )?;
+        // the row numbers go in front, as a column of their own
+        let numbered = SampleRows {
+            columns: std::iter::once("#".to_string()).chain(random.sample.columns.iter().cloned()).collect(),
+            rows: random
+                .row_numbers
+                .iter()
+                .zip(&random.sample.rows)
+                .map(|(row, cells)| std::iter::once(row.to_string()).chain(cells.iter().cloned()).collect())
+                .collect(),
+        };
+        print_sample(out, &numbered)?;
+    }
+
+    if opts.show_types {
+        print_types(out, report)?;
+    }
+
+    if opts.explain {
+        print_inference(out, report)?;
+    }
+
+    if opts.fingerprint {
+        print_fingerprints(out, report)?;
+    }
+
+    if opts.show_summary {
+        print_summary(out, report, opts)?;
+    }
+
+    if opts.benford {
+        print_benford(out, report)?;
+    }
+
+    if let Some(grouped) = &report.groups {
+        print_groups(out, grouped, opts)?;
+    }
+
+    if opts.histogram_bins.is_some() {
+        writeln!(out, "Histograms:")?;
+
+        for column in &report.columns {
+            let Some(bins) = column.summary.as_ref().and_then(|s| s.histogram.as_ref()) else {
+                continue;
+            };
+
+            writeln!(out, "  {}", column.name)?;
+            let peak = bins.iter().map(|b| b.count).max().unwrap_or(0);
+            for bin in bins {
+                writeln!(
+                    out,
+                    "    {:>12.4} .. {:<12.4} {:<30} {}",
+                    bin.start,
+                    bin.end,
+                    histogram_bar(bin.count, peak, 30),
+                    bin.count
+                )?;
+            }
+        }
+    }
+
+    if let Some(matrix) = &report.correlations {
+        writeln!(out, "Correlations:")?;
+        if matrix.columns.len() < 2 {
+            writeln!(out, "  (fewer than two numeric columns)")?;
+        } else {
+            let width = matrix.columns.iter().map(|c| c.chars().count()).max().unwrap_or(0).max(6);
+            let header: String = matrix.columns.iter().map(|c| format!(" {:>width$}", c)).collect();
+            writeln!(out, "  {:width$}{}", "", header)?;
+            for (name, row) in matrix.columns.iter().zip(&matrix.values) {
+                let cells: String = row
+                    .iter()
+                    .map(|r| match r {
+                        Some(r) => format!(" {:>width$.3}", r),
+                        None => format!(" {:>width$}", "-"),
+                    })
+                    .collect();
+                writeln!(out, "  {:width$}{}", name, cells)?;
+            }
+        }
+    }
+
+    if opts.show_diagnose {
+        print_diagnose(out, report, opts)?;
+    }
+
+    Ok(())
+}
+
+fn print_types(out: &mut dyn Write, report: &Report) -> io::Result<()> {
+    writeln!(out, "Inferred types:")?;
+    for column in &report.columns {
+        let dtype = column
+            .numeric_type
+            .or(column.kind.map(ColumnType::as_str))
+            .unwrap_or("unknown");
+        match column.semantic_type {
+            Some(semantic) => write!(out, "  - {}: {} (appears to be {})", column.name, dtype, semantic.as_str())?,
+            None => write!(out, "  - {}: {}", column.name, dtype)?,
+        }
+        if let Some(presence) = column.presence.filter(|&p| p < 1.0) {
+            write!(out, " (in {:.1}% of records)", presence * 100.0)?;
+        }
+        match column.json_kinds.filter(JsonKinds::is_mixed) {
+            Some(kinds) => writeln!(out, " [mixed: {}]", kinds.describe())?,
+            None => writeln!(out)?,
+        }
+    }
+    Ok(())
+}
+
+fn print_benford(out: &mut dyn Write, report: &Report) -> io::Result<()> {
+    writeln!(out, "Benford's law (leading digits 1-9):")?;
+    for column in report.columns.iter().filter(|c| c.kind == Some(ColumnType::Numeric)) {
+        let Some(test) = &column.benford else {
+            writeln!(
+                out,
+                "  - {}: skipped (needs {} positive values spanning two orders of magnitude)",
+                column.name, BENFORD_MIN_VALUES
+            )?;
+            continue;
+        };
+        let shares: Vec<String> = test.observed.iter().map(|share| format!("{:.1}", share * 100.0)).collect();
+        writeln!(
+            out,
+            "  - {}: {} (chi-square={:.2}, critical={}, n={}) observed%={}",
+            column.name,
+            if test.pass { "pass" } else { "fail" },
+            test.chi_square,
+            BENFORD_CRITICAL,
+            test.values,
+            shares.join(" ")
+        )?;
+    }
+    Ok(())
+}
+
+fn print_fingerprints(out: &mut dyn Write, report: &Report) -> io::Result<()> {
+    writeln!(out, "Fingerprints:")?;
+    for column in &report.columns {
+        writeln!(out, "  - {}: {}", column.name, column.fingerprint.as_deref().unwrap_or("-"))?;
+    }
+    Ok(())
+}
+
+fn print_inference(out: &mut dyn Write, report: &Report) -> io::Result<()> {
+    writeln!(out, "Type inference:")?;
+    for column in &report.columns {
+        let Some(inference) = &column.inference else {
+            continue;
+        };
+        let dtype = column
+            .numeric_type
+            .or(column.kind.map(ColumnType::as_str))
+            .unwrap_or("unknown");
+        let large = match inference.large_integers {
+            0 => String::new(),
+            n => format!(" large-integer={}", n),
+        };
+        writeln!(
+            out,
+            "  - {} ({}): integer={}{} float={} boolean={} datetime={} string={} failed={}",
+            column.name,
+            dtype,
+            inference.integers,
+            large,
+            inference.floats,
+            inference.booleans,
+            inference.datetimes,
+            inference.strings,
+            inference.parse_failures
+        )?;
+        writeln!(out, "      {}", inference.rule)?;
+    }
+
+    let upgraded = report
+        .columns
+        .iter()
+        .filter(|c| c.inference.as_ref().is_some_and(|i| i.upgraded_at.is_some()))
+        .count();
+    if upgraded > 0 {
+        writeln!(
+            out,
+            "  {} of {} columns upgraded from text to numeric mid-stream",
+            upgraded,
+            report.columns.len()
+        )?;
+    }
+    Ok(())
+}
+
+fn print_summary(out: &mut dyn Write, report: &Report, opts: &InspectOptions) -> io::Result<()> {
+    writeln!(out, "Summary:")?;
+    print_columns_summary(out, &report.columns, opts)
+}
+
+fn print_columns_summary(out: &mut dyn Write, columns: &[ColumnReport], opts: &InspectOptions) -> io::Result<()> {
+    if opts.pretty {
+        return print_summary_table(out, columns, opts);
+    }
+    for column in columns {
+        print_column_summary(out, column)?;
+    }
+    Ok(())
+}
+
+fn print_groups(out: &mut dyn Write, grouped: &GroupedSummary, opts: &InspectOptions) -> io::Result<()> {
+    writeln!(out, "Summary by {} ({} groups):", grouped.column, grouped.groups.len())?;
+    for group in &grouped.groups {
+        let value = if group.value.is_empty() { Cow::Borrowed("(missing)") } else { escape_cell(&group.value) };
+        writeln!(out, "[{}] {} row{}", value, group.rows, if group.rows == 1 { "" } else { "s" })?;
+        print_columns_summary(out, &group.columns, opts)?;
+    }
+    Ok(())
+}
+
+fn print_diagnose(out: &mut dyn Write, report: &Report, opts: &InspectOptions) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "Data Quality Report")?;
+    writeln!(out, "--------------------")?;
+    writeln!(out)?;
+
+    writeln!(out, "Rows")?;
+    print_warnings(out, &report.diagnostics, opts.color)?;
+    writeln!(out)?;
+
+    for column in &report.columns {
+        if print_column_diagnostics(out, column, opts.color)? {
+            writeln!(out)?;
+        }
+    }
+
+    let counts: Vec<String> = Severity::ALL
+        .iter()
+        .map(|&level| {
+            let count = report.diagnostics().filter(|d| d.severity == level).count();
+            format!("{} {}", count, level.as_str())
+        })
+        .collect();
+    writeln!(out, "Diagnostics: {}", counts.join(", "))?;
+
+    writeln!(out)?;
+    writeln!(out, "Data Quality Score: {:.1} / 100", report.quality.score)?;
+    // checks that cost nothing are only listed in --json
+    for penalty in report.quality.penalties.iter().filter(|p| p.points > 0.0) {
+        writeln!(
+            out,
+            "  - {}: -{:.1} of {} ({:.1}% affected)",
+            penalty.check,
+            penalty.points,
+            penalty.weight,
+            penalty.ratio * 100.0
+        )?;
+    }
+    Ok(())
+}
+
+// false for columns without stats, which print nothing
+fn print_column_diagnostics(out: &mut dyn Write, column: &ColumnReport, color: bool) -> io::Result<bool> {
+    let (Some(kind), Some(warnings)) = (column.kind, &column.diagnostics) else {
+        return Ok(false);
+    };
+
+    writeln!(out, "{} ({:?})", column.name, kind)?;
+    print_warnings(out, warnings, color)?;
+    Ok(true)
+}
+
+// one line per column, plus the --top values of categorical columns
+fn print_column_summary(out: &mut dyn Write, column: &ColumnReport) -> io::Result<()> {
+    let (Some(kind), Some(summary)) = (column.kind, &column.summary) else {
+        return Ok(());
+    };
+
+    if summary.count == 0 && summary.missing > 0 {
+        return writeln!(out, "  - {} (empty): count=0 missing={}", column.name, summary.missing);
+    }
+
+    match kind {
+        ColumnType::Numeric => {
+            if let (Some(min), Some(max), Some(mean), Some(stddev)) =
+                (summary.min, summary.max, summary.mean, summary.stddev)
+            {
+                write!(
+                    out,
+                    "  - {} (numeric): count={} missing={} min={} max={} mean={} stddev={}",
+                    column.name,
+                    summary.count,
+                    summary.missing,
+                    min, max, mean, stddev
+                )?;
+
+                if let (Some(skewness), Some(kurtosis)) = (summary.skewness, summary.kurtosis) {
+                    write!(out, " skewness={:.3} kurtosis={:.3}", skewness, kurtosis)?;
+                }
+
+                if let (Some(min_z), Some(max_z)) = (summary.min_z, summary.max_z) {
+                    write!(out, " z=[{:.2}, {:.2}]", min_z, max_z)?;
+                }
+
+                if let (Some(q1), Some(median), Some(q3)) =
+                    (summary.q1, summary.median, summary.q3)
+                {
+                    write!(out, " q1={} median={} q3={}", q1, median, q3)?;
+                }
+                if let (Some(negative), Some(zero), Some(positive)) =
+                    (summary.negative, summary.zero, summary.positive)
+                {
+                    write!(out, " negative={} zero={} positive={}", negative, zero, positive)?;
+                }
+                if let Some(order) = summary.order {
+                    write!(out, " order={}", order)?;
+                }
+                writeln!(out)?;
+            } else {
+                // only large integers or unparsed values: nothing to aggregate
+                writeln!(
+                    out,
+                    "  - {} (numeric): count={} missing={} min=- max=- mean=- stddev=-",
+                    column.name, summary.count, summary.missing
+                )?;
+            }
+        }
+        ColumnType::Categorical => {
+            write!(
+                out,
+                "  - {} (categorical): count={} missing={} unique{}",
+                column.name,
+                summary.count,
+                summary.missing,
+                unique_label(summary, "=")
+            )?;
+            if let Some(error) = summary.unique_error {
+                write!(out, " (±{:.1}%)", error * 100.0)?;
+            }
+            if let Some(mode) = &summary.mode {
+                write!(out, " mode={}", escape_cell(mode))?;
+            }
+            if let (Some(max_length), Some(avg_length)) =
+                (summary.max_length, summary.avg_length)
+            {
+                write!(out, " max_length={} avg_length={:.1}", max_length, avg_length)?;
+            }
+            if let (Some(min), Some(max)) = (&summary.min_value, &summary.max_value) {
+                write!(out, " min={} max={}", escape_cell(min), escape_cell(max))?;
+            }
+            if let (Some(shortest), Some(longest)) = (&summary.shortest, &summary.longest) {
+                write!(out, " shortest={} longest={}", escape_cell(shortest), escape_cell(longest))?;
+            }
+            writeln!(out)?;
+
+            if let Some(top) = &summary.top {
+                for entry in top {
+                    writeln!(
+                        out,
+                        "      {}: {} ({:.1}%)",
+                        escape_cell(&entry.value),
+                        entry.count,
+                        entry.percent
+                    )?;
+                }
+                if let Some(others) = summary.others {
+                    let percent = others as f64 / summary.count.max(1) as f64 * 100.0;
+                    writeln!(out, "      (others): {} ({:.1}%)", others, percent)?;
+                }
+            }
+        }
+        ColumnType::DateTime => {
+            write!(
+                out,
+                "  - {} (datetime): count={} missing={} min={} max={}",
+                column.name,
+                summary.count,
+                summary.missing,
+                summary.earliest.as_deref().unwrap_or("-"),
+                summary.latest.as_deref().unwrap_or("-")
+            )?;
+            if let (Some(span), Some(days), Some(gaps)) = (&summary.span, summary.distinct_days, summary.gap_days) {
+                write!(out, " span={} days={} gap_days={}", span, days, gaps)?;
+            }
+            if let Some(order) = summary.order {
+                write!(out, " order={}", order)?;
+            }
+            writeln!(out)?;
+        }
+        ColumnType::Boolean => {
+            writeln!(
+                out,
+                "  - {} (boolean): count={} missing={} true={} false={} true_ratio={:.3}",
+                column.name,
+                summary.count,
+                summary.missing,
+                summary.true_count.unwrap_or(0),
+                summary.false_count.unwrap_or(0),
+                summary.true_ratio.unwrap_or(0.0)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// File type, row count, column listing and schema overview.
+pub fn print_preamble(out: &mut dyn Write, report: &Report) -> io::Result<()> {
+    writeln!(out, "File type: {}", report.file_type)?;
+    if report.skipped_rows > 0 {
+        writeln!(
+            out,
+            "Rows: {} ({}after skipping {})",
+            report.rows,
+            if report.limited { "limited, " } else { "" },
+            report.skipped_rows
+        )?;
+    } else if report.limited {
+        writeln!(out, "Rows: {} (limited)", report.rows)?;
+    } else if report.rows == 0 {
+        writeln!(out, "Rows: 0 (no data rows)")?;
+    } else {
+        writeln!(out, "Rows: {}", report.rows)?;
+    }
+    writeln!(out, "Columns:")?;
+    for column in &report.columns {
+        writeln!(out, "  - {}", column.name)?;
+    }
+
+    let schema: Vec<String> = report
+        .schema
+        .iter()
+        .map(|(dtype, count)| format!("{} {}", count, dtype))
+        .collect();
+    if !schema.is_empty() {
+        writeln!(out, "Schema: {}", schema.join(", "))?;
+    }
+    Ok(())
+}
+
+// Quoted CSV fields can hold newlines and tabs; shown raw they would break
+// the line-oriented text report, so they are printed escaped.
+fn escape_cell(value: &str) -> Cow<'_, str> {
+    if !value.contains(['\n', '\r', '\t']) {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(
+        value
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+            .replace('\t', "\\t"),
+    )
+}
+
+// cells wider than this are cut with an ellipsis to keep rows on screen
+const TABLE_CELL_WIDTH: usize = 24;
+
+fn truncate_cell(cell: &str) -> String {
+    let cell = escape_cell(cell);
+    if cell.chars().count() > TABLE_CELL_WIDTH {
+        let mut cut: String = cell.chars().take(TABLE_CELL_WIDTH - 1).collect();
+        cut.push('…');
+        cut
+    } else {
+        cell.to_string()
+    }
+}
+
+fn print_sample(out: &mut dyn Write, sample: &SampleRows) -> io::Result<()> {
+    let header: Vec<String> = sample.columns.iter().map(|c| truncate_cell(c)).collect();
+    let rows: Vec<Vec<String>> = sample
+        .rows
+        .iter()
+        .map(|row| {
+            (0..header.len())
+                .map(|i| row.get(i).map_or(String::new(), |cell| truncate_cell(cell)))
+                .collect()
+        })
+        .collect();
+
+    print_table(out, &header, &rows)
+}
+
+// One statistic per table column, one data column per row. Aggregates are
+// rounded to 3 decimals here; --plain and --json keep full precision.
+fn print_summary_table(out: &mut dyn Write, columns: &[ColumnReport], opts: &InspectOptions) -> io::Result<()> {
+    let number = |x: Option<f64>| match x {
+        Some(x) => {
+            let text = format!("{:.3}", x);
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+        None => "-".to_string(),
+    };
+    let text = |x: Option<&str>| x.map_or("-".to_string(), truncate_cell);
+
+    let mut header = vec![
+        "column", "type", "count", "missing", "missing%", "unique", "min", "max", "mean", "stddev",
+    ];
+    if opts.show_percentiles {
+        header.extend(["q1", "median", "q3"]);
+    }
+    header.extend(["mode", "order"]);
+    let header: Vec<String> = header.into_iter().map(str::to_string).collect();
+
+    let mut rows = Vec::new();
+    for column in columns {
+        let (Some(kind), Some(summary)) = (column.kind, &column.summary) else {
+            continue;
+        };
+        let total = summary.count + summary.missing;
+        let dtype = if summary.count == 0 && summary.missing > 0 {
+            "empty"
+        } else {
+            column.numeric_type.unwrap_or(kind.as_str())
+        };
+        let (min, max) = match kind {
+            ColumnType::DateTime => (text(summary.earliest.as_deref()), text(summary.latest.as_deref())),
+            ColumnType::Categorical => (text(summary.min_value.as_deref()), text(summary.max_value.as_deref())),
+            _ => (number(summary.min), number(summary.max)),
+        };
+        // for booleans, the mean of the true/false indicator is the true ratio
+        let mean = match kind {
+            ColumnType::Boolean => summary.true_ratio,
+            _ => summary.mean,
+        };
+
+        let mut row = vec![
+            truncate_cell(&column.name),
+            dtype.to_string(),
+            summary.count.to_string(),
+            summary.missing.to_string(),
+            format!("{:.1}", summary.missing as f64 / total.max(1) as f64 * 100.0),
+            unique_label(summary, ""),
+            min,
+            max,
+            number(mean),
+            number(summary.stddev),
+        ];
+        if opts.show_percentiles {
+            row.extend([number(summary.q1), number(summary.median), number(summary.q3)]);
+        }
+        row.extend([text(summary.mode.as_deref()), text(summary.order)]);
+        rows.push(row);
+    }
+    print_table(out, &header, &rows)?;
+
+    // --top lists don't fit in a cell; they follow the table
+    let tops: Vec<(&str, &Vec<ValueCount>)> = columns
+        .iter()
+        .filter_map(|c| Some((c.name.as_str(), c.summary.as_ref()?.top.as_ref()?)))
+        .collect();
+    if !tops.is_empty() {
+        writeln!(out, "Top values:")?;
+        for (name, top) in tops {
+            let values: Vec<String> = top
+                .iter()
+                .map(|entry| format!("{} ({}, {:.1}%)", escape_cell(&entry.value), entry.count, entry.percent))
+                .collect();
+            writeln!(out, "  {}: {}", name, values.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+// left-aligned columns, two spaces apart, without trailing whitespace
+fn print_table(out: &mut dyn Write, header: &[String], rows: &[Vec<String>]) -> io::Result<()> {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let print_row = |out: &mut dyn Write, cells: &[String]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell))
+            .collect();
+        writeln!(out, "  {}", line.join("  ").trim_end())
+    };
+
+    print_row(out, header)?;
+    for row in rows {
+        print_row(out, row)?;
+    }
+    Ok(())
+}
+
+// bar scaled to the tallest bin, with eighth-block characters for the remainder
+fn histogram_bar(count: usize, peak: usize, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    if peak == 0 {
+        return String::new();
+    }
+
+    let eighths = count * width * 8 / peak;
+    let mut bar = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(PARTIAL[eighths % 8]);
+    }
+    bar
+}
+
+fn print_warnings(out: &mut dyn Write, warnings: &[Diagnostic], color: bool) -> io::Result<()> {
+    if warnings.is_empty() {
+        return if color {
+            writeln!(out, "  {}", "ok".green())
+        } else {
+            writeln!(out, "  ok")
+        };
+    }
+
+    for w in warnings {
+        let line = format!("! {}: {}", w.severity.as_str(), w.message);
+        if !color {
+            writeln!(out, "  {}", line)?;
+            continue;
+        }
+        match w.severity {
+            Severity::Info => writeln!(out, "  {}", line.cyan())?,
+            Severity::Warning => writeln!(out, "  {}", line.yellow())?,
+            Severity::Error => writeln!(out, "  {}", line.red().bold())?,
+        }
+    }
+    Ok(())
+}
+
+fn diagnose_column(stats: &ColumnStats, total_rows: usize, opts: &InspectOptions) -> Vec<Diagnostic> {
+    let mut warnings = Vec::new();
+    // the stats count rows from the start of the window; the rest of the
+    // report counts them from the start of the file
+    let row = |row: usize| row + opts.skip;
+
+    if let Some(example) = &stats.null_byte_example {
+        warnings.push(Diagnostic::new(
+            "null-bytes",
+            Severity::Warning,
+            format!(
+                "null bytes: {} value{} contained NUL characters, which were removed (e.g. {:?})",
+                stats.null_bytes,
+                if stats.null_bytes == 1 { "" } else { "s" },
+                example
+            ),
+        ));
+    }
+
+    // a field that is a number in some records and a string or an array in
+    // others: the schema drifted, or one producer writes it differently
+    if let Some(kinds) = stats.json_kinds.filter(JsonKinds::is_mixed) {
+        warnings.push(Diagnostic::new(
+            "mixed-kinds",
+            Severity::Warning,
+            format!("mixed JSON value kinds: {}", kinds.describe()),
+        ));
+    }
+
+    // a field most records leave out is a different thing from one they
+    // leave empty: optional, or only written by some producers
+    if let Some(kinds) = stats.json_kinds.filter(|k| k.presence() < opts.sparse_threshold) {
+        warnings.push(Diagnostic::new(
+            "sparse",
+            Severity::Warning,
+            format!(
+                "sparse field: present in {:.1}% of records (absent from {} of {})",
+                kinds.presence() * 100.0,
+                kinds.absent,
+                stats.total
+            ),
+        ));
+    }
+
+    // a --validate rule broken: the data doesn't hold to its contract
+    if let Some(pattern) = &stats.validator
+        && stats.validation_failures > 0
+    {
+        let mut examples: Vec<String> = stats
+            .validation_examples
+            .iter()
+            .map(|(r, value)| format!("row {}: {:?}", row(*r), value))
+            .collect();
+        if stats.validation_failures > examples.len() {
+            examples.push("...".to_string());
+        }
+        let values = stats.total - stats.missing;
+        warnings.push(Diagnostic::new(
+            "validate",
+            Severity::Error,
+            format!(
+                "validation failed: {} of {} values ({:.1}%) don't match {} ({})",
+                stats.validation_failures,
+                values,
+                stats.validation_failures as f64 / values as f64 * 100.0,
+                pattern.as_str(),
+                examples.join(", ")
+            ),
+        ));
+    }
+
+    // nothing else to check on a column without a single value, typically a
+    // leftover field from a schema change
+    if stats.total > 0 && stats.missing == stats.total {
+        warnings.push(Diagnostic::new(
+            "missing",
+            Severity::Error,
+            format!("column is empty: all {} values are missing", stats.total),
+        ));
+        return column_diagnostics(warnings, stats, opts);
+    }
+
+    let missing_ratio = stats.missing as f64 / total_rows as f64;
+
+    // ZIP codes, product codes: read as numbers they lose their leading zeros
+    if let Some(width) = stats.zero_padded_width() {
+        let severity = if stats.kind == ColumnType::Numeric {
+            Severity::Warning
+        } else {
+            Severity::Info
+        };
+        warnings.push(Diagnostic::new(
+            "zero-padded",
+            severity,
+            format!(
+                "zero-padded codes: all values are {}-digit strings with leading zeros (likely an identifier, not a quantity)",
+                width
+            ),
+        ));
+    }
+
+    // missing severity: mostly-empty columns are unusable, not just incomplete
+    if missing_ratio > opts.missing_threshold {
+        let severity = if missing_ratio > 0.5 {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        warnings.push(Diagnostic::new(
+            "missing",
+            severity,
+            format!("missing values: {}%", (missing_ratio * 100.0).round() as usize),
+        ));
+    }
+
+    match stats.kind {
+        ColumnType::Categorical => {
+            if stats.semantic_type() == Some(SemanticType::LargeInteger) {
+                warnings.push(Diagnostic::new(
+                    "large-integers",
+                    Severity::Info,
+                    "large integers: past 2^53 a float can't hold every integer exactly, so the \
+                     column is kept as text (likely an identifier)",
+                ));
+            }
+
+            if let Some(limit) = opts.max_length
+                && stats.max_length > limit
+            {
+                warnings.push(Diagnostic::new(
+                    "max-length",
+                    Severity::Warning,
+                    format!(
+                        "values longer than {} characters (max length {})",
+                        limit, stats.max_length
+                    ),
+                ));
+            }
+
+            let non_missing = total_rows - stats.missing;
+            if non_missing > 0 {
+                let unique_ratio = stats.distinct_count() as f64 / non_missing as f64;
+                let (merged, example) = stats.whitespace_collisions();
+                if merged > 0 {
+                    warnings.push(Diagnostic::new(
+                        "whitespace",
+                        Severity::Warning,
+                        format!(
+                            "whitespace variants: {} distinct value{} merge after trimming (e.g. {:?})",
+                            merged,
+                            if merged == 1 { "" } else { "s" },
+                            example.unwrap_or_default()
+                        ),
+                    ));
+                }
+
+                // one value repeated on every row carries no information; a
+                // single row is trivially constant, so it needs two
+                if non_missing > 1
+                    && stats.other_count == 0
+                    && let Some(value) = stats.uniques.keys().next().filter(|_| stats.uniques.len() == 1)
+                {
+                    warnings.push(Diagnostic::new(
+                        "constant",
+                        Severity::Warning,
+                        format!("constant column: every value is {:?}", value),
+                    ));
+                }
+
+                if stats.uniques_capped() {
+                    warnings.push(Diagnostic::new(
+                        "cardinality",
+                        Severity::Info,
+                        format!(
+                            "high cardinality: ≥{} unique values, tracking capped by --max-uniques (likely identifier)",
+                            stats.uniques.len()
+                        ),
+                    ));
+                } else if stats.high_cardinality(non_missing, opts.cardinality_threshold) {
+                    warnings.push(Diagnostic::new(
+                        "cardinality",
+                        Severity::Info,
+                        format!(
+                            "high cardinality: {:.1}% unique (likely identifier)",
+                            unique_ratio * 100.0
+                        ),
+                    ));
+                }
+            }
+        }
+
+        ColumnType::Numeric => {
+            // near-constant numeric
+            if let (Some(min), Some(max)) = (stats.min, stats.max)
+                && (max - min).abs() < 1e-12
+            {
+                warnings.push(Diagnostic::new("constant", Severity::Info, "near-constant numeric column"));
+            }
+
+            // mostly zeros with a few other values: min and max differ, so it
+            // isn't constant, but the mean and stddev mostly describe the zeros
+            let zero_share = stats.zeros as f64 / stats.count.max(1) as f64;
+            if stats.zeros < stats.count && zero_share > opts.zero_threshold {
+                warnings.push(Diagnostic::new(
+                    "zero-inflated",
+                    Severity::Warning,
+                    format!(
+                        "zero-inflated: {:.1}% of the values are 0, so mean and stddev say little \
+                         (maybe a sparse or indicator field)",
+                        zero_share * 100.0
+                    ),
+                ));
+            }
+
+            // strictly increasing means every value is unique, so a complete
+            // integer column like that is almost certainly a key
+            if stats.integers_only
+                && stats.missing == 0
+                && stats.count > 1
+                && stats.count == total_rows
+                && stats.number_order.increasing
+            {
+                warnings.push(Diagnostic::new(
+                    "primary-key",
+                    Severity::Info,
+                    "likely primary key: unique, strictly increasing integers",
+                ));
+            }
+
+            // as floats they would be rounded, so they are not in the stats:
+            // these are IDs that happened to start small
+            if let Some((r, value)) = &stats.large_integer_example {
+                warnings.push(Diagnostic::new(
+                    "large-integers",
+                    Severity::Warning,
+                    format!(
+                        "large integers: {} value{} past 2^53 (row {}: {:?}) can't be held exactly \
+                         as floats and are left out of the numeric stats; if it is an identifier, \
+                         --coerce {}=categorical keeps it as text",
+                        stats.large_integers,
+                        if stats.large_integers == 1 { "" } else { "s" },
+                        row(*r),
+                        value,
+                        stats.name
+                    ),
+                ));
+            }
+
+            // mixed-type numeric
+            if stats.numeric_parse_failures > 0 {
+                let mut examples: Vec<String> = stats
+                    .failure_examples
+                    .iter()
+                    .map(|(r, value)| format!("row {}: {:?}", row(*r), value))
+                    .collect();
+                if stats.numeric_parse_failures > examples.len() {
+                    examples.push("...".to_string());
+                }
+                warnings.push(Diagnostic::new(
+                    "mixed-type",
+                    Severity::Warning,
+                    format!(
+                        "mixed numeric and non-numeric values: {} unparsed ({})",
+                        stats.numeric_parse_failures,
+                        examples.join(", ")
+                    ),
+                ));
+            }
+
+            // the text before the first number was thrown away by the upgrade,
+            // so neither the cardinality nor the parse failures account for it
+            if stats.before_promotion > 0 {
+                let upgraded_at = stats.first_number.unwrap_or(0);
+                let mut examples: Vec<String> = stats
+                    .text_examples
+                    .iter()
+                    .filter(|(r, _)| *r < upgraded_at)
+                    .map(|(r, value)| format!("row {}: {:?}", row(*r), value))
+                    .collect();
+                if stats.before_promotion > examples.len() {
+                    examples.push("...".to_string());
+                }
+                warnings.push(Diagnostic::new(
+                    "type-upgrade",
+                    Severity::Warning,
+                    format!(
+                        "upgraded to numeric on row {}: {} earlier non-numeric value{} dropped from the stats ({})",
+                        row(upgraded_at),
+                        stats.before_promotion,
+                        if stats.before_promotion == 1 { "" } else { "s" },
+                        examples.join(", ")
+                    ),
+                ));
+            }
+
+            // a handful of negatives among otherwise non-negative numbers
+            if stats.negatives > 0 && stats.negatives as f64 / stats.count as f64 <= STRAY_NEGATIVE_SHARE {
+                let mut examples: Vec<String> = stats
+                    .negative_examples
+                    .iter()
+                    .map(|(r, x)| format!("row {}: {}", row(*r), x))
+                    .collect();
+                if stats.negatives > examples.len() {
+                    examples.push("...".to_string());
+                }
+                warnings.push(Diagnostic::new(
+                    "negatives",
+                    Severity::Warning,
+                    format!(
+                        "stray negative values: {} of {} ({:.1}%) in an otherwise non-negative column ({})",
+                        stats.negatives,
+                        stats.count,
+                        stats.negatives as f64 / stats.count as f64 * 100.0,
+                        examples.join(", ")
+                    ),
+                ));
+            }
+            
+            // values outside --range-min / --range-max
+            if stats.out_of_range > 0 {
+                let mut examples: Vec<String> = stats
+                    .range_examples
+                    .iter()
+                    .map(|(r, x)| format!("row {}: {}", row(*r), x))
+                    .collect();
+                if stats.out_of_range > examples.len() {
+                    examples.push("...".to_string());
+                }
+                let bounds = match stats.value_range {
+                    (Some(low), Some(high)) => format!("outside [{}, {}]", low, high),
+                    (Some(low), None) => format!("below {}", low),
+                    (None, Some(high)) => format!("above {}", high),
+                    (None, None) => unreachable!("no bounds, nothing out of range"),
+                };
+                warnings.push(Diagnostic::new(
+                    "range",
+                    Severity::Warning,
+                    format!(
+                        "out-of-range values: {} of {} {} ({})",
+                        stats.out_of_range,
+                        stats.count,
+                        bounds,
+                        examples.join(", ")
+                    ),
+                ));
+            }
+
+            if let Some(test) = stats.benford.as_ref().and_then(BenfordTally::test)
+                && !test.pass
+            {
+                warnings.push(Diagnostic::new(
+                    "benford",
+                    Severity::Warning,
+                    format!(
+                        "leading digits deviate from Benford's law: chi-square {:.2} > {} over {} values",
+                        test.chi_square, BENFORD_CRITICAL, test.values
+                    ),
+                ));
+            }
+
+            // outliers 
+            if stats.outlier_count > 0 {
+                let rule = match stats.outlier_fences {
+                    Some((low, high)) => format!("outside [{}, {}] (IQR)", low, high),
+                    None => format!(">= {}σ (z-score)", stats.outlier_sigma),
+                };
+                warnings.push(Diagnostic::new(
+                    "outliers",
+                    Severity::Warning,
+                    format!(
+                        "extreme outliers detected: {} values {}",
+                        stats.outlier_count, rule
+                    ),
+                ));
+            }
+        }
+
+        ColumnType::DateTime => {
+            let non_missing = stats.total - stats.missing;
+            let unparsed = non_missing - stats.date_count;
+            if unparsed > 0 {
+                warnings.push(Diagnostic::new(
+                    "mixed-type",
+                    Severity::Warning,
+                    format!("mixed date and non-date values: {} unparsed", unparsed),
+                ));
+            }
+
+            // sentinels like 1900-01-01 or 2099-12-31, and typos in the year
+            if let Some((min, max)) = stats.date_range.filter(|_| stats.dates_out_of_range > 0) {
+                let mut examples: Vec<String> = stats
+                    .date_range_examples
+                    .iter()
+                    .map(|&(r, ts)| format!("row {}: {}", row(r), format_datetime(ts, stats.date_has_time)))
+                    .collect();
+                if stats.dates_out_of_range > examples.len() {
+                    examples.push("...".to_string());
+                }
+                warnings.push(Diagnostic::new(
+                    "date-range",
+                    Severity::Warning,
+                    format!(
+                        "implausible dates: {} outside {} to {} ({})",
+                        stats.dates_out_of_range,
+                        format_datetime(min, false),
+                        format_datetime(max, false),
+                        examples.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        ColumnType::Boolean => {}
+    }
+
+    column_diagnostics(warnings, stats, opts)
+}
+
+// the enabled checks' findings, each tagged with the column
+fn column_diagnostics(mut warnings: Vec<Diagnostic>, stats: &ColumnStats, opts: &InspectOptions) -> Vec<Diagnostic> {
+    warnings.retain(|d| opts.runs_check(d.check));
+    for diagnostic in &mut warnings {
+        diagnostic.column = Some(stats.name.clone());
+    }
+    warnings
+}
+
+// Columnar formats carry their own schema, so column types come from it and
+// values are fed to the stats directly instead of being parsed from strings.
+
+#[cfg(feature = "parquet")]
+fn inspect_parquet(filename: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(open_file(filename)?)?;
+    let mut columns = ArrowColumns::new(builder.schema(), opts)?;
+    let builder = builder.with_offset(opts.skip);
+    let builder = match opts.limit {
+        Some(limit) => builder.with_limit(limit),
+        None => builder,
+    };
+    for batch in builder.build()? {
+        columns.update(&batch?)?;
+    }
+
+    Ok(columns.into_report("Parquet", opts))
+}
+
+// Arrow IPC in either layout: the file format (Feather v2, starts with the
+// ARROW1 magic) or the streaming format
+#[cfg(feature = "arrow")]
+fn inspect_arrow(filename: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    use arrow_ipc::reader::{FileReader, StreamReader};
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = BufReader::new(open_file(filename)?);
+    let mut magic = [0u8; 6];
+    let is_file_format = file.read_exact(&mut magic).is_ok() && &magic == b"ARROW1";
+    file.seek(SeekFrom::Start(0)).map_err(io_error(filename))?;
+
+    let batches: Box<dyn Iterator<Item = Result<arrow_array::RecordBatch, arrow_schema::ArrowError>>>;
+    let schema = if is_file_format {
+        let reader = FileReader::try_new(file, None)?;
+        let schema = reader.schema();
+        batches = Box::new(reader);
+        schema
+    } else {
+        let reader = StreamReader::try_new(file, None)?;
+        let schema = reader.schema();
+        batches = Box::new(reader);
+        schema
+    };
+
+    let mut columns = ArrowColumns::new(&schema, opts)?;
+    let mut to_skip = opts.skip;
+    let mut remaining = opts.limit.unwrap_or(usize::MAX);
+    for batch in batches {
+        if remaining == 0 {
+            break;
+        }
+        let batch = batch?;
+        let offset = batch.num_rows().min(to_skip);
+        to_skip -= offset;
+        let rows = (batch.num_rows() - offset).min(remaining);
+        if rows > 0 {
+            columns.update(&batch.slice(offset, rows))?;
+        }
+        remaining -= rows;
+    }
+
+    Ok(columns.into_report("Arrow", opts))
+}
+
+#[cfg(any(feature = "parquet", feature = "arrow"))]
+struct ArrowColumns {
+    selected: Vec<usize>,
+    stats: Vec<ColumnStats>,
+    rows: RowStats,
+}
+
+#[cfg(any(feature = "parquet", feature = "arrow"))]
+impl ArrowColumns {
+    fn new(schema: &arrow_schema::Schema, opts: &InspectOptions) -> Result<Self, InspectError> {
+        let names: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
+        let selected = select_columns(&names, opts)?;
+
+        let stats = selected
+            .iter()
+            .map(|&i| {
+                let field = schema.field(i);
+                new_column_stats(field.name(), arrow_column_type(field.data_type()), opts)
+                    .with_locked_type()
+            })
+            .collect();
+
+        Ok(Self {
+            selected,
+            stats,
+            rows: RowStats::new(opts),
+        })
+    }
+
+    fn update(&mut self, batch: &arrow_array::RecordBatch) -> Result<(), InspectError> {
+        // no row fingerprints here: that would mean formatting every cell
+        self.rows.rows += batch.num_rows();
+
+        for (stats, &i) in self.stats.iter_mut().zip(&self.selected) {
+            feed_arrow_array(stats, batch.column(i))?;
+        }
+
+        // the head rows while the sample has room, and each batch's last rows for the tail
+        let room = self.rows.sample.as_ref().map_or(0, |s| self.rows.sample_size - s.len());
+        let tail_from = match self.rows.tail {
+            Some(_) => batch.num_rows().saturating_sub(self.rows.tail_size),
+            None => batch.num_rows(),
+        };
+        if room > 0 || tail_from < batch.num_rows() || self.rows.reservoir.is_some() {
+            use arrow_cast::display::{ArrayFormatter, FormatOptions};
+
+            let options = FormatOptions::default();
+            let formatters = self
+                .selected
+                .iter()
+                .map(|&i| ArrayFormatter::try_new(batch.column(i).as_ref(), &options))
+                .collect::<Result<Vec<_>, _>>()?;
+            // rows was already advanced past the whole batch
+            let first = self.rows.rows - batch.num_rows() + 1;
+            let every_row = self.rows.reservoir.is_some();
+            for row in (0..batch.num_rows()).filter(|&row| every_row || row < room || row >= tail_from) {
+                self.rows.sample_row_at(first + row, formatters.iter().map(|f| f.value(row).to_string()));
+            }
+        }
+
+        if let Some(correlations) = &mut self.rows.correlations {
+            use arrow_array::Array;
+            use arrow_array::cast::AsArray;
+            use arrow_array::types::Float64Type;
+            use arrow_schema::DataType;
+
+            let numeric = self
+                .selected
+                .iter()
+                .map(|&i| {
+                    let array = batch.column(i);
+                    match array.data_type() {
+                        dtype if dtype.is_numeric() => {
+                            arrow_cast::cast(array, &DataType::Float64).map(Some)
+                        }
+                        _ => Ok(None),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for row in 0..batch.num_rows() {
+                correlations.update(numeric.iter().map(|array| {
+                    let values = array.as_ref()?.as_primitive::<Float64Type>();
+                    values.is_valid(row).then(|| values.value(row))
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    fn into_report(self, file_type: &'static str, opts: &InspectOptions) -> Report {
+        let columns = self
+            .stats
+            .into_iter()
+            .map(|stats| (stats.name.clone(), Some(stats)))
+            .collect();
+        build_report(file_type, &self.rows, columns, opts)
+    }
+}
+
+#[cfg(any(feature = "parquet", feature = "arrow"))]
+fn arrow_column_type(dtype: &arrow_schema::DataType) -> ColumnType {
+    use arrow_schema::DataType;
+
+    match dtype {
+        DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => ColumnType::DateTime,
+        DataType::Boolean => ColumnType::Boolean,
+        dtype if dtype.is_numeric() => ColumnType::Numeric,
+        _ => ColumnType::Categorical,
+    }
+}
+
+#[cfg(any(feature = "parquet", feature = "arrow"))]
+fn feed_arrow_array(
+    stats: &mut ColumnStats,
+    array: &arrow_array::ArrayRef,
+) -> Result<(), arrow_schema::ArrowError> {
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Date32Type, Float64Type, Int64Type};
+    use arrow_cast::display::{ArrayFormatter, FormatOptions};
+    use arrow_schema::{DataType, TimeUnit};
+
+    match array.data_type() {
+        DataType::Date32 => {
+            for days in array.as_primitive::<Date32Type>() {
+                stats.update_datetime(days.map(|d| (i64::from(d) * 86_400, false)));
+            }
+        }
+        DataType::Date64 | DataType::Timestamp(_, _) => {
+            let (per_second, has_time) = match array.data_type() {
+                DataType::Timestamp(TimeUnit::Second, _) => (1, true),
+                DataType::Timestamp(TimeUnit::Millisecond, _) => (1_000, true),
+                DataType::Timestamp(TimeUnit::Microsecond, _) => (1_000_000, true),
+                DataType::Timestamp(TimeUnit::Nanosecond, _) => (1_000_000_000, true),
+                _ => (1_000, false), // Date64 is milliseconds at day granularity
+            };
+
+            let raw = arrow_cast::cast(array, &DataType::Int64)?;
+            for value in raw.as_primitive::<Int64Type>() {
+                stats.update_datetime(value.map(|v| (v.div_euclid(per_second), has_time)));
+            }
+        }
+        dtype if dtype.is_numeric() => {
+            if !dtype.is_integer() {
+                stats.integers_only = false;
+            }
+            let values = arrow_cast::cast(array, &DataType::Float64)?;
+            for x in values.as_primitive::<Float64Type>() {
+                stats.update_number(x);
+            }
+        }
+        _ => {
+            let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?;
+            for i in 0..array.len() {
+                if array.is_null(i) {
+                    stats.update("");
+                } else {
+                    stats.update(&formatter.value(i).to_string());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Spreadsheet cells are typed individually rather than per column, so types
+// are still inferred like CSV; numeric cells skip the string round trip once
+// their column is settled as numeric.
+
+#[cfg(feature = "xlsx")]
+fn inspect_xlsx(filename: &str, opts: &InspectOptions) -> Result<Report, InspectError> {
+    use calamine::{Reader, Xlsx};
+
+    let mut workbook: Xlsx<_> = Xlsx::new(BufReader::new(open_file(filename)?))?;
+    let sheet_names = workbook.sheet_names();
+    let sheet = match &opts.sheet {
+        Some(name) if sheet_names.contains(name) => name.clone(),
+        Some(name) => {
+            return Err(InspectError::UnknownSheet {
+                name: name.clone(),
+                available: sheet_names,
+            });
+        }
+        None => sheet_names.first().cloned().unwrap_or_default(),
+    };
+    let range = workbook.worksheet_range(&sheet)?;
+
+    let mut sheet_rows = range.rows();
+    let headers: Vec<String> = if opts.no_header {
+        (0..range.width()).map(|i| format!("col_{}", i)).collect()
+    } else {
+        sheet_rows.next().unwrap_or_default().iter().map(xlsx_cell).collect()
+    };
+
+    let selected = select_columns(&headers, opts)?;
+
+    let mut rows = RowStats::new(opts);
+    let mut column_stats: Vec<Option<ColumnStats>> = headers.iter().map(|_| None).collect();
+
+    for row in sheet_rows.skip(opts.skip).take(opts.limit.unwrap_or(usize::MAX)) {
+        let cells: Vec<String> = row.iter().map(xlsx_cell).collect();
+        rows.update(row_fingerprint(cells.iter().map(String::as_str)));
+        rows.sample_row(selected.iter().map(|&i| cells[i].clone()));
+        if let Some(correlations) = &mut rows.correlations {
+            correlations.update(selected.iter().map(|&i| parse_number(&cells[i], opts)));
+        }
+
+        let mut dirty = false;
+        for &i in &selected {
+            let slot = &mut column_stats[i];
+            match (slot.as_mut(), xlsx_number(&row[i])) {
+                (Some(stats), Some(x))
+                    if stats.kind == ColumnType::Numeric && stats.sample.is_none() =>
+                {
+                    // Excel stores most numbers as floats, so go by the value
+                    if x.fract() != 0.0 {
+                        stats.integers_only = false;
+                    }
+                    stats.update_number(Some(x));
+                }
+                _ => dirty |= update_column(slot, &headers[i], &cells[i], opts),
+            }
+        }
+        if dirty {
+            rows.dirty_row();
+        }
+    }
+
+    let columns = selected
+        .iter()
+        .map(|&i| (headers[i].clone(), column_stats[i].take()))
+        .collect();
+    Ok(build_report("XLSX", &rows, columns, opts))
+}
+
+#[cfg(feature = "xlsx")]
+fn xlsx_number(cell: &calamine::Data) -> Option<f64> {
+    match cell {
+        calamine::Data::Int(v) => Some(*v as f64),
+        calamine::Data::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+// string form of a cell; Excel dates become ISO text that parse_datetime understands
+#[cfg(feature = "xlsx")]
+fn xlsx_cell(cell: &calamine::Data) -> String {
+    match cell {
+        calamine::Data::DateTime(dt) if dt.is_datetime() => {
+            let (year, month, day, hour, minute, second, _) = dt.to_ymd_hms_milli();
+            if (hour, minute, second) == (0, 0, 0) {
+                format!("{:04}-{:02}-{:02}", year, month, day)
+            } else {
+                format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    year, month, day, hour, minute, second
+                )
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+// Top-level arrays are walked element by element, so only one record is in
+// memory at a time however large the file is.
+fn read_json(
+    input: impl Read,
+    opts: &InspectOptions,
+    progress: Option<&ProgressBar>,
+) -> Result<Report, InspectError> {
+    let mut records = JsonRecords::new(opts, progress);
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(input));
+    serde::Deserializer::deserialize_any(&mut deserializer, &mut records)?;
+    deserializer.end()?;
+
+    records.into_report("JSON")
+}
+
+// Visits the document root: an array yields its objects as records, a single
+// object is one record. Anything else is rejected once the visit returns.
+impl<'de> serde::de::Visitor<'de> for &mut JsonRecords<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an object or an array")
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while !self.is_full() {
+            match seq.next_element::<serde_json::Value>()? {
+                Some(serde_json::Value::Object(record)) => {
+                    if !self.skips() {
+                        self.update(&record);
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        // past --limit the rest still has to be parsed, but not kept
+        while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+
+        // an array of primitives has no fields: one empty record
+        if self.columns.rows.rows == 0 && self.skipped == 0 {
+            self.update(&serde_json::Map::new());
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<(), A::Error> {
+        let record: serde_json::Map<String, serde_json::Value> =
+            serde::Deserialize::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+        self.update(&record);
+        Ok(())
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, _: bool) -> Result<(), E> {
+        self.unsupported = true;
+        Ok(())
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, _: i64) -> Result<(), E> {
+        self.unsupported = true;
+        Ok(())
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, _: u64) -> Result<(), E> {
+        self.unsupported = true;
+        Ok(())
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, _: f64) -> Result<(), E> {
+        self.unsupported = true;
+        Ok(())
+    }
+
+    fn visit_str<E: serde::de::Error>(self, _: &str) -> Result<(), E> {
+        self.unsupported = true;
+        Ok(())
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<(), E> {
+        self.unsupported = true;
+        Ok(())
+    }
+}
+
+// YAML documents are deserialized straight into JSON values, so a list of
+// mappings goes through exactly the same path as a JSON array of objects
+fn read_yaml(input: impl Read, opts: &InspectOptions, name: &str) -> Result<Report, InspectError> {
+    let contents = io::read_to_string(input).map_err(io_error(name))?;
+    let yaml: serde_json::Value = serde_yaml::from_str(&contents)?;
+
+    let mut records = JsonRecords::new(opts, None);
+    match yaml {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                if records.is_full() {
+                    break;
+                }
+                if let serde_json::Value::Object(record) = value
+                    && !records.skips()
+                {
+                    records.update(&record);
+                }
+            }
+            if records.columns.rows.rows == 0 && records.skipped == 0 {
+                records.update(&serde_json::Map::new());
+            }
+        }
+        serde_json::Value::Object(record) => records.update(&record),
+        _ => return Err(InspectError::UnsupportedJson),
+    }
+
+    records.into_report("YAML")
+}
+
+// A TOML document is always a table. An array of tables (`[[server]]`) or a
+// table whose values are all tables (`[server.alpha]`, `[server.beta]`) is a
+// list of records, like a JSON array of objects; anything else is one record.
+// When only one key holds such a list, it is looked through, so a `[[server]]`
+// list is inspected even with top-level settings beside it.
+fn read_toml(input: impl Read, opts: &InspectOptions, name: &str) -> Result<Report, InspectError> {
+    let contents = io::read_to_string(input).map_err(io_error(name))?;
+    let table: toml::Table = contents.parse()?;
+
+    let mut document = toml_to_json(toml::Value::Table(table));
+    while let serde_json::Value::Object(map) = &mut document
+        && (map.len() == 1 || !is_table_of_tables(map))
+        && map.values().filter(|value| holds_records(value)).count() == 1
+        && let Some(value) = map.values_mut().find(|value| holds_records(value))
+    {
+        document = value.take();
+    }
+
+    let mut records = JsonRecords::new(opts, None);
+    let values = match document {
+        serde_json::Value::Array(values) => values,
+        serde_json::Value::Object(map) if is_table_of_tables(&map) => {
+            map.into_iter().map(|(_, value)| value).collect()
+        }
+        document => vec![document],
+    };
+    for value in values {
+        if records.is_full() {
+            break;
+        }
+        if let serde_json::Value::Object(record) = value
+            && !records.skips()
+        {
+            records.update(&record);
+        }
+    }
+
+    records.into_report("TOML")
+}
+
+// an array of tables or a table of tables
+fn holds_records(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Array(values) => !values.is_empty() && values.iter().all(|v| v.is_object()),
+        serde_json::Value::Object(map) => is_table_of_tables(map),
+        _ => false,
+    }
+}
+
+fn is_table_of_tables(map: &serde_json::Map<String, serde_json::Value>) -> bool {
+    !map.is_empty() && map.values().all(|v| v.is_object())
+}
+
+// dates and times become strings, so they are typed like their JSON spelling
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => serde_json::Value::String(s),
+        toml::Value::Integer(n) => serde_json::Value::from(n),
+        toml::Value::Float(n) => serde_json::Number::from_f64(n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        toml::Value::Boolean(b) => serde_json::Value::Bool(b),
+        toml::Value::Datetime(d) => serde_json::Value::String(d.to_string()),
+        toml::Value::Array(values) => serde_json::Value::Array(values.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => {
+            serde_json::Value::Object(table.into_iter().map(|(k, v)| (k, toml_to_json(v))).collect())
+        }
+    }
+}
+
+// one JSON object per line; blank lines are skipped
+fn read_jsonl(
+    input: impl Read,
+    opts: &InspectOptions,
+    progress: Option<&ProgressBar>,
+    name: &str,
+) -> Result<Report, InspectError> {
+    let mut records = JsonRecords::new(opts, progress);
+
+    for (i, line) in BufReader::new(input).lines().enumerate() {
+        if records.is_full() {
+            break;
+        }
+        let line = line.map_err(io_error(name))?;
+        if line.trim().is_empty() || records.skips() {
+            continue;
+        }
+
+        match serde_json::from_str(&line) {
+            Ok(serde_json::Value::Object(obj)) => records.update(&obj),
+            Ok(_) => {
+                return Err(InspectError::JsonLine {
+                    line: i + 1,
+                    message: "expected a JSON object".to_string(),
+                });
+            }
+            Err(err) => {
+                return Err(InspectError::JsonLine {
+                    line: i + 1,
+                    message: err.to_string(),
+                });
+            }
+        }
+    }
+
+    records.into_report("JSONL")
+}
+
+// Flattens records into columns (in order of first appearance) and feeds
+// them through the same stats pipeline as CSV, one record at a time. Fields
+// absent from a record count as missing values, as do JSON nulls.
+struct JsonRecords<'a> {
+    opts: &'a InspectOptions,
+    progress: Option<&'a ProgressBar>,
+    columns: JsonColumns,
+    // records passed over so far for --skip
+    skipped: usize,
+    // the document root was neither an object nor an array
+    unsupported: bool,
+}
+
+#[derive(Default)]
+struct JsonColumns {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    stats: Vec<Option<ColumnStats>>,
+    rows: RowStats,
+}
+
+impl<'a> JsonRecords<'a> {
+    fn new(opts: &'a InspectOptions, progress: Option<&'a ProgressBar>) -> Self {
+        Self {
+            opts,
+            progress,
+            columns: JsonColumns {
+                rows: RowStats::new(opts),
+                ..JsonColumns::default()
+            },
+            skipped: 0,
+            unsupported: false,
+        }
+    }
+
+    // whether this record is still before the --skip window, counting it if so
+    fn skips(&mut self) -> bool {
+        if self.skipped < self.opts.skip {
+            self.skipped += 1;
+            return true;
+        }
+        false
+    }
+
+    fn is_full(&self) -> bool {
+        self.opts.limit.is_some_and(|limit| self.columns.rows.rows >= limit)
+    }
+
+    fn update(&mut self, record: &serde_json::Map<String, serde_json::Value>) {
+        let opts = self.opts;
+        let flat;
+        let record = if record.values().any(|v| v.is_object() || v.is_array()) {
+            flat = flatten_record(record, opts.max_depth);
+            &flat
+        } else {
+            record
+        };
+        let JsonColumns { names, index, stats, rows } = &mut self.columns;
+        let row = rows.rows;
+        rows.update(json_fingerprint(record));
+        report_rows(self.progress, rows.rows);
+
+        for key in record.keys() {
+            if !index.contains_key(key) {
+                index.insert(key.clone(), names.len());
+                names.push(key.clone());
+                stats.push(None);
+            }
+        }
+
+        // columns seen so far; fields first seen later are blank in earlier rows
+        rows.sample_row(names.iter().map(|name| record.get(name).map(json_cell).unwrap_or_default()));
+
+        if let Some(correlations) = &mut rows.correlations {
+            correlations.update(names.iter().map(|name| match record.get(name) {
+                Some(serde_json::Value::Number(n)) => n.as_f64(),
+                Some(serde_json::Value::String(s)) => parse_number(s, opts),
+                _ => None,
+            }));
+        }
+
+        let mut dirty = false;
+        for (i, name) in names.iter().enumerate() {
+            let value = record.get(name).map(json_cell).unwrap_or_default();
+            let is_new = stats[i].is_none();
+
+            dirty |= update_column(&mut stats[i], name, &value, opts);
+
+            if let Some(stats) = &mut stats[i] {
+                let kinds = stats.json_kinds.get_or_insert_with(JsonKinds::default);
+                kinds.record(record.get(name));
+                // the field first showed up in this record, so it was absent before
+                if is_new && row > 0 {
+                    kinds.absent += row;
+                    stats.add_missing(row);
+                }
+            }
+        }
+        if dirty {
+            rows.dirty_row();
+        }
+    }
+
+    fn into_report(self, file_type: &'static str) -> Result<Report, InspectError> {
+        if self.unsupported {
+            return Err(InspectError::UnsupportedJson);
+        }
+
+        let JsonColumns { names, stats, rows, .. } = self.columns;
+        let columns = names.into_iter().zip(stats).collect();
+        Ok(build_report(file_type, &rows, columns, self.opts))
+    }
+}
+
+fn json_fingerprint(record: &serde_json::Map<String, serde_json::Value>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for (key, value) in record {
+        key.hash(&mut hasher);
+        json_cell(value).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+// Nested objects are spread into dotted paths (`{"user": {"age": 30}}` gives
+// `user.age`) down to `max_depth` levels, past which an object stays one
+// field holding its JSON text, as does an empty one. An array keeps its JSON
+// text and gets a `.length` field beside it, so its size has numeric stats.
+fn flatten_record(
+    record: &serde_json::Map<String, serde_json::Value>,
+    max_depth: Option<usize>,
+) -> serde_json::Map<String, serde_json::Value> {
+    fn flatten_into(
+        flat: &mut serde_json::Map<String, serde_json::Value>,
+        path: String,
+        value: &serde_json::Value,
+        depth: usize,
+        max_depth: Option<usize>,
+    ) {
+        match value {
+            serde_json::Value::Object(fields)
+                if !fields.is_empty() && max_depth.is_none_or(|max| depth < max) =>
+            {
+                for (key, value) in fields {
+                    flatten_into(flat, format!("{}.{}", path, key), value, depth + 1, max_depth);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                flat.insert(format!("{}.length", path), items.len().into());
+                flat.insert(path, value.clone());
+            }
+            _ => {
+                flat.insert(path, value.clone());
+            }
+        }
+    }
+
+    let mut flat = serde_json::Map::new();
+    for (key, value) in record {
+        flatten_into(&mut flat, key.clone(), value, 0, max_depth);
+    }
+    flat
+}
+
+// string form of a JSON value as a column cell; nested values keep their JSON text
+fn json_cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// cell as a number for correlations, honoring --clean-numbers
+fn parse_number(value: &str, opts: &InspectOptions) -> Option<f64> {
+    match normalize_number(value, opts) {
+        Some(cleaned) => cleaned.parse().ok(),
+        None => value.parse().ok(),
+    }
+}
+
+// The value as plain numeric text, when --clean-numbers or a comma
+// --decimal-separator has something to rewrite in it.
+fn normalize_number(value: &str, opts: &InspectOptions) -> Option<String> {
+    if opts.clean_numbers {
+        clean_number(value, opts)
+    } else if opts.decimal_separator != '.' || opts.thousands_separators.is_some() {
+        let (sign, rest) = split_sign(value);
+        let number = format!("{}{}", sign, ungroup_number(rest, opts)?);
+        (number != value).then_some(number)
+    } else {
+        None
+    }
+}
+
+/// The symbols --clean-numbers strips unless `currency_symbols` says otherwise.
+pub const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥'];
+
+fn split_sign(value: &str) -> (&str, &str) {
+    match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value.strip_prefix('+').unwrap_or(value)),
+    }
+}
+
+// Digits with an optional fraction after the decimal separator and, in the
+// integer part, thousands separators in groups of three, so lists like
+// "1,2,3" are left alone; one number sticks to one separator. Returned with
+// the separators dropped and a `.` for the decimal point.
+fn ungroup_number(text: &str, opts: &InspectOptions) -> Option<String> {
+    let decimal = opts.decimal_separator;
+    let thousands: &[char] = match &opts.thousands_separators {
+        Some(separators) => separators,
+        None if decimal == ',' => &['.'],
+        None => &[','],
+    };
+    let (int, frac) = match text.split_once(decimal) {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (text, None),
+    };
+    let separator = int.chars().find(|c| thousands.contains(c));
+    let mut groups = int.split(separator.unwrap_or(decimal));
+    let first = groups.next().unwrap_or_default();
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(first)
+        || (separator.is_some() && first.len() > 3)
+        || !groups.all(|g| g.len() == 3 && digits(g))
+        || frac.is_some_and(|f| !digits(f))
+    {
+        return None;
+    }
+
+    let int = match separator {
+        Some(separator) => int.replace(separator, ""),
+        None => int.to_string(),
+    };
+    Some(match frac {
+        Some(frac) => format!("{}.{}", int, frac),
+        None => int,
+    })
+}
+
+// Spreadsheet-style numbers ("1,234.56", "$1,200", "-€5", "45%") as plain
+// numeric text, with percentages divided by 100. None when there is nothing to
+// strip or the value is not a number once stripped.
+fn clean_number(value: &str, opts: &InspectOptions) -> Option<String> {
+    let currency = opts.currency_symbols.as_slice();
+    let (rest, percent) = match value.strip_suffix('%') {
+        Some(rest) => (rest.trim_end(), true),
+        None => (value, false),
+    };
+    let (sign, rest) = split_sign(rest.trim_start_matches(currency));
+    let rest = rest.trim_start_matches(currency).trim_end_matches(currency).trim();
+
+    let number = format!("{}{}", sign, ungroup_number(rest, opts)?);
+    if percent {
+        number.parse::<f64>().ok().map(|x| (x / 100.0).to_string())
+    } else {
+        (number != value).then_some(number)
+    }
+}
+
+/// Epoch seconds (UTC) of a date or datetime written in any of the forms
+/// datetime columns are detected in, e.g. for `date_min`.
+pub fn parse_timestamp(value: &str) -> Option<i64> {
+    parse_datetime(value).map(|(ts, _, _)| ts)
+}
+
+/// Type of a single raw value: "integer", "float", "boolean", "datetime" or "string".
+pub fn infer_type(value: &str) -> &'static str {
+    if is_large_integer(value) {
+        "large integer"
+    } else if value.parse::<i64>().is_ok() {
+        "integer"
+    } else if value.parse::<f64>().is_ok() {
+        "float"
+    } else if parse_bool(value).is_some() {
+        "boolean"
+    } else if parse_datetime(value).is_some() {
+        "datetime"
+    } else {
+        "string"
+    }
+}
+
+// Integers above this are not all representable as f64, so stats over them
+// would be off and two distinct IDs can compare equal once parsed.
+const MAX_EXACT_INTEGER: u64 = 1 << 53;
+
+// integer text past ±2^53, such as 19-digit Snowflake IDs; zero padding
+// doesn't count towards the size
+fn is_large_integer(value: &str) -> bool {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    if digits.len() < 16 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let significant = digits.trim_start_matches('0');
+    !significant.is_empty() && significant.parse::<u64>().map_or(true, |n| n > MAX_EXACT_INTEGER)
+}
+
+// common boolean spellings, case-insensitive
+fn parse_bool(value: &str) -> Option<bool> {
+    const TRUE: [&str; 4] = ["true", "yes", "t", "1"];
+    const FALSE: [&str; 4] = ["false", "no", "f", "0"];
+
+    if TRUE.iter().any(|t| value.eq_ignore_ascii_case(t)) {
+        Some(true)
+    } else if FALSE.iter().any(|f| value.eq_ignore_ascii_case(f)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// Recognizes YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS (optionally with fractional
+// seconds and a trailing Z or UTC offset, or a space instead of T) and
+// MM/DD/YYYY. Returns epoch seconds, normalized to UTC when an offset is
+// given, whether a time component was present, and whether it had a zone.
+fn parse_datetime(value: &str) -> Option<(i64, bool, bool)> {
+    if let Some((month, rest)) = value.split_once('/') {
+        let (day, year) = rest.split_once('/')?;
+        if !(1..=2).contains(&month.len()) || !(1..=2).contains(&day.len()) || year.len() != 4 {
+            return None;
+        }
+        let days = civil_to_days(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)?;
+        return Some((days * 86_400, false, false));
+    }
+
+    let bytes = value.as_bytes();
+    if bytes.len() < 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+
+    let digits = |range: std::ops::Range<usize>| -> Option<i64> {
+        let part = value.get(range)?;
+        if part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+
+    let days = civil_to_days(digits(0..4)?, digits(5..7)? as u32, digits(8..10)? as u32)?;
+    if bytes.len() == 10 {
+        return Some((days * 86_400, false, false));
+    }
+
+    if !matches!(bytes[10], b'T' | b' ') || bytes.len() < 19 || bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+
+    let (hour, minute, second) = (digits(11..13)?, digits(14..16)?, digits(17..19)?);
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &value[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let end = fraction.find(|c: char| !c.is_ascii_digit()).unwrap_or(fraction.len());
+        if end == 0 {
+            return None;
+        }
+        rest = &fraction[end..];
+    }
+    let offset = match rest {
+        "" => None,
+        "Z" | "z" => Some(0),
+        _ => Some(parse_utc_offset(rest)?),
+    };
+
+    let local = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some((local - offset.unwrap_or(0), true, offset.is_some()))
+}
+
+// "+02:00", "-0530" or "+09", in seconds east of UTC
+fn parse_utc_offset(value: &str) -> Option<i64> {
+    let sign = match value.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = value[1..].replacen(':', "", 1);
+    if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits.get(2..).filter(|m| !m.is_empty()).map_or(Ok(0), str::parse).ok()?;
+    if hours > 18 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3_600 + minutes * 60))
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm)
+fn civil_to_days(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+fn days_to_civil(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// "41 days", or "41 days 06:30:00" when the values have times; a negative
+// span gets a leading minus
+fn format_duration(seconds: i64, with_time: bool) -> String {
+    if seconds < 0 {
+        return format!("-{}", format_duration(-seconds, with_time));
+    }
+    let days = seconds / 86_400;
+    let unit = if days == 1 { "day" } else { "days" };
+    if !with_time {
+        return format!("{} {}", days, unit);
+    }
+    let rest = seconds % 86_400;
+    format!(
+        "{} {} {:02}:{:02}:{:02}",
+        days,
+        unit,
+        rest / 3_600,
+        rest % 3_600 / 60,
+        rest % 60
+    )
+}
+
+fn format_datetime(ts: i64, with_time: bool) -> String {
+    let (year, month, day) = days_to_civil(ts.div_euclid(86_400));
+    if !with_time {
+        return format!("{:04}-{:02}-{:02}", year, month, day);
+    }
+
+    let secs = ts.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // a file under the temp dir, unique to this test process
+    fn fixture(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("datainspect-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    // equal JSON, with numbers compared up to floating-point rounding
+    fn assert_close(a: &serde_json::Value, b: &serde_json::Value, path: &str) {
+        use serde_json::Value;
+        match (a, b) {
+            (Value::Number(x), Value::Number(y)) => {
+                let (x, y) = (x.as_f64().unwrap(), y.as_f64().unwrap());
+                assert!((x - y).abs() <= 1e-9 * x.abs().max(y.abs()).max(1.0), "{}: {} != {}", path, x, y);
+            }
+            (Value::Array(xs), Value::Array(ys)) => {
+                assert_eq!(xs.len(), ys.len(), "{}: lengths differ", path);
+                for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+                    assert_close(x, y, &format!("{}[{}]", path, i));
+                }
+            }
+            (Value::Object(xs), Value::Object(ys)) => {
+                assert_eq!(xs.keys().collect::<Vec<_>>(), ys.keys().collect::<Vec<_>>(), "{}: keys differ", path);
+                for (key, x) in xs {
+                    assert_close(x, &ys[key], &format!("{}.{}", path, key));
+                }
+            }
+            _ => assert_eq!(a, b, "{}", path),
+        }
+    }
+
+    // the report and the --sample rows of inline CSV
+    fn read_csv_text(csv: &str, opts: InspectOptions) -> (Report, Vec<Vec<String>>) {
+        let opts = InspectOptions {
+            sample_rows: Some(10),
+            ..opts
+        };
+        let report = inspect_reader(csv.as_bytes(), InputFormat::Csv, &opts).unwrap();
+        let rows = report.sample.as_ref().unwrap().rows.clone();
+        (report, rows)
+    }
+
+    #[test]
+    fn quoted_fields_keep_newlines_delimiters_and_doubled_quotes() {
+        let csv = "id,text\n1,\"line one\nline two\"\n2,\"a, b\"\n3,\"say \"\"hi\"\"\"\n";
+        let (report, rows) = read_csv_text(csv, InspectOptions::default());
+        assert_eq!(report.rows, 3);
+        assert_eq!(report.ragged_rows, 0);
+        assert_eq!(
+            rows,
+            [["1", "line one\nline two"], ["2", "a, b"], ["3", "say \"hi\""]]
+        );
+    }
+
+    #[test]
+    fn backslash_escapes_only_with_the_escape_option() {
+        let csv = "id,text\n1,\"a \\\"quoted\\\" word\"\n";
+        let escaped = InspectOptions {
+            escape: Some(b'\\'),
+            ..InspectOptions::default()
+        };
+        let (report, rows) = read_csv_text(csv, escaped);
+        assert_eq!(report.rows, 1);
+        assert_eq!(rows, [["1", "a \"quoted\" word"]]);
+
+        // without it the backslash is a plain byte: the quote after it closes the
+        // quoted part, and the rest of the field is read as it is
+        let (report, rows) = read_csv_text(csv, InspectOptions::default());
+        assert_eq!(report.rows, 1);
+        assert_eq!(rows, [["1", "a \\quoted\\\" word\""]]);
+    }
+
+    #[test]
+    fn datetimes_with_mixed_offsets_normalize_to_utc() {
+        let utc = parse_datetime("2024-03-10T10:00:00Z").unwrap();
+        assert_eq!(utc, (1_710_064_800, true, true));
+        for value in [
+            "2024-03-10T12:00:00+02:00",
+            "2024-03-10T05:30:00-04:30",
+            "2024-03-10T15:30:00+0530",
+            "2024-03-10T19:00:00+09",
+            "2024-03-10T10:00:00.250z",
+        ] {
+            assert_eq!(parse_datetime(value), Some(utc), "{}", value);
+        }
+        // no offset: read as UTC, but not marked as zoned
+        assert_eq!(parse_datetime("2024-03-10T10:00:00"), Some((utc.0, true, false)));
+        for value in ["2024-03-10T10:00:00+2", "2024-03-10T10:00:00+19:00", "2024-03-10T10:00:00+05:60"] {
+            assert_eq!(parse_datetime(value), None, "{}", value);
+        }
+
+        let stats = column(&["2024-03-10T12:00:00+02:00", "2024-03-10T05:30:00-04:30"], &InspectOptions::default());
+        assert_eq!(stats.kind, ColumnType::DateTime);
+        assert_eq!((stats.min_date, stats.max_date), (Some(utc.0), Some(utc.0)));
+        assert!(stats.date_has_zone);
+    }
+
+    #[test]
+    fn leap_days_are_valid_only_in_leap_years() {
+        for (value, valid) in [
+            ("2024-02-29", true),
+            ("2000-02-29", true),
+            ("2023-02-29", false),
+            ("1900-02-29", false),
+            ("02/29/2024", true),
+            ("02/29/2023", false),
+        ] {
+            assert_eq!(parse_datetime(value).is_some(), valid, "{}", value);
+        }
+        let leap_day = civil_to_days(2024, 2, 29).unwrap();
+        assert_eq!(days_to_civil(leap_day), (2024, 2, 29));
+        assert_eq!(civil_to_days(2024, 3, 1).unwrap() - civil_to_days(2024, 2, 28).unwrap(), 2);
+        assert_eq!(format_datetime(leap_day * 86_400 + 3_661, true), "2024-02-29T01:01:01");
+    }
+
+    #[test]
+    fn durations_format_in_days_and_time() {
+        assert_eq!(format_duration(41 * 86_400, false), "41 days");
+        assert_eq!(format_duration(86_400 + 3_600 + 61, true), "1 day 01:01:01");
+        assert_eq!(format_duration(0, true), "0 days 00:00:00");
+        // a span across a leap day
+        let span = civil_to_days(2024, 3, 1).unwrap() - civil_to_days(2024, 2, 1).unwrap();
+        assert_eq!(format_duration(span * 86_400, false), "29 days");
+        // a negative span
+        assert_eq!(format_duration(-(86_400 + 3_600), true), "-1 day 01:00:00");
+        assert_eq!(format_duration(-2 * 86_400, false), "-2 days");
+    }
+
+    // feeds values through the CSV path, one per row
+    fn feed(values: &[&str], opts: &InspectOptions) -> ColumnStats {
+        let mut slot = None;
+        for value in values {
+            update_column(&mut slot, "n", value, opts);
+        }
+        slot.unwrap()
+    }
+
+    fn column(values: &[&str], opts: &InspectOptions) -> ColumnStats {
+        let mut stats = feed(values, opts);
+        stats.finalize();
+        stats
+    }
+
+    fn assert_near(a: f64, b: f64, what: &str) {
+        assert!((a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0), "{}: {} != {}", what, a, b);
+    }
+
+    // population skewness g1 and excess kurtosis g2 from central moments
+    // computed in a second pass over the values
+    fn two_pass_shape(values: &[f64]) -> (f64, f64) {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let moment = |k: i32| values.iter().map(|x| (x - mean).powi(k)).sum::<f64>();
+        let (m2, m3, m4) = (moment(2), moment(3), moment(4));
+        (n.sqrt() * m3 / m2.powf(1.5), n * m4 / (m2 * m2) - 3.0)
+    }
+
+    #[test]
+    fn streaming_skewness_and_kurtosis_match_two_pass() {
+        let opts = InspectOptions::default();
+        let samples: [&[f64]; 4] = [
+            &[1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 4.0, 10.0, 25.0, -3.5],
+            &[0.001, 1e6, 3.0, 42.0, 7.5, 7.5],
+            &[1.0, 2.0, 4.0],
+            &[1.0, 3.0],
+        ];
+        for values in samples {
+            let text: Vec<String> = values.iter().map(f64::to_string).collect();
+            let stats = column(&text.iter().map(String::as_str).collect::<Vec<_>>(), &opts);
+            let (skewness, kurtosis) = two_pass_shape(values);
+            assert_near(stats.skewness().unwrap(), skewness, "skewness");
+            assert_near(stats.kurtosis().unwrap(), kurtosis, "kurtosis");
+        }
+
+        // no spread, or a single value: the shape is undefined
+        for values in [&["4", "4", "4", "4", "4"][..], &["4"][..]] {
+            let stats = column(values, &opts);
+            assert_eq!(stats.skewness(), None);
+            assert_eq!(stats.kurtosis(), None);
+        }
+    }
+
+    #[test]
+    fn merged_halves_equal_one_pass() {
+        let opts = InspectOptions::default();
+        let numbers: Vec<String> = (0..40)
+            .map(|i| if i % 9 == 4 { String::new() } else { ((i * 37 % 23) as f64 * 1.5 - 7.0).to_string() })
+            .collect();
+        let numbers: Vec<&str> = numbers.iter().map(String::as_str).collect();
+        let words = ["b", "a", "", "c", "a", "b", "b", "", "d", "a", "e", "c"];
+
+        for values in [&numbers[..], &words[..]] {
+            let whole = feed(values, &opts);
+            for split in [1, 2, values.len() / 3, values.len() / 2, values.len() - 1] {
+                let mut merged = feed(&values[..split], &opts);
+                merged.merge(&feed(&values[split..], &opts));
+                let what = |field: &str| format!("{} split at {}", field, split);
+
+                assert_eq!(merged.kind, whole.kind, "{}", what("kind"));
+                assert_eq!(merged.total, whole.total, "{}", what("total"));
+                assert_eq!(merged.missing, whole.missing, "{}", what("missing"));
+                assert_eq!(merged.count, whole.count, "{}", what("count"));
+                assert_near(merged.mean, whole.mean, &what("mean"));
+                assert_near(merged.m2, whole.m2, &what("m2"));
+                assert_near(merged.m3, whole.m3, &what("m3"));
+                assert_near(merged.m4, whole.m4, &what("m4"));
+                assert_eq!((merged.min, merged.max), (whole.min, whole.max), "{}", what("min/max"));
+                assert_eq!(merged.uniques, whole.uniques, "{}", what("uniques"));
+                assert_eq!(merged.distinct_count(), whole.distinct_count(), "{}", what("distinct"));
+            }
+        }
+    }
+
+    #[test]
+    fn large_integers_stay_out_of_a_numeric_columns_stats() {
+        let stats = column(&["5", "12345678901234567890", "7"], &InspectOptions::default());
+        assert_eq!(stats.kind, ColumnType::Numeric);
+        assert_eq!(stats.numeric_type(), Some("integer"));
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.mean, 6.0);
+        assert_eq!((stats.min, stats.max), (Some(5.0), Some(7.0)));
+        assert_eq!(stats.large_integers, 1);
+        assert_eq!(stats.large_integer_example, Some((2, "12345678901234567890".to_string())));
+    }
+
+    #[test]
+    fn a_coerced_column_of_only_large_integers_fails_coercion_and_still_prints() {
+        let opts = InspectOptions {
+            coerce: vec![("id".to_string(), CoercedType::Numeric)],
+            show_summary: true,
+            quiet: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("id\n9007199254740993\n9007199254740995\n", opts.clone());
+        assert!(matches!(
+            check_coercions(&report, &opts),
+            Err(InspectError::CoercionFailed { failures: 2, values: 2, .. })
+        ));
+
+        let mut out = Vec::new();
+        print_report(&mut out, &report, &opts).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("id (numeric): count=2 missing=0 min=- max=- mean=- stddev=-"), "{}", out);
+    }
+
+    #[test]
+    fn cleaned_numbers_are_validated_and_fingerprinted_as_written() {
+        let csv = "rate\n45%\n50%\nn/a%\n";
+        let clean = InspectOptions {
+            clean_numbers: true,
+            validate: vec![("rate".to_string(), Regex::new("^[0-9]+%$").unwrap())],
+            na_values: vec!["n/a%".to_string()],
+            fingerprint: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text(csv, clean.clone());
+        let column = &report.columns[0];
+        assert_eq!(column.kind, Some(ColumnType::Numeric));
+        let summary = column.summary.as_ref().unwrap();
+        assert_eq!((summary.count, summary.missing, summary.mean), (2, 1, Some(0.475)));
+        assert!(!column.diagnostics.as_ref().unwrap().iter().any(|d| d.check == "validate"));
+
+        // the same cells, fingerprinted without cleaning
+        let (raw, _) = read_csv_text(csv, InspectOptions { clean_numbers: false, ..clean });
+        assert_eq!(column.fingerprint, raw.columns[0].fingerprint);
+    }
+
+    #[test]
+    fn reports_flag_large_integers_and_zoned_datetimes() {
+        let csv = "small,wide,huge,local,zoned\n\
+                   1,1,1,2024-01-01 10:00,2024-01-01T10:00:00+02:00\n\
+                   2,9007199254740993,12345678901234567890,2024-01-02 10:00,2024-01-02T10:00:00Z\n";
+        let (report, _) = read_csv_text(csv, InspectOptions::default());
+        let flags: Vec<_> = report.columns.iter().map(|c| (c.large_integers, c.large_integers_past_i64)).collect();
+        assert_eq!(flags[..3], [(None, false), (Some(1), false), (Some(1), true)]);
+        assert_eq!(report.columns[2].numeric_type, Some("integer"));
+
+        let zoned = |i: usize| report.columns[i].summary.as_ref().unwrap().zoned;
+        assert!(!zoned(3));
+        assert!(zoned(4));
+    }
+
+    #[test]
+    fn configured_thousands_separators_and_currency_symbols_parse_as_numbers() {
+        let opts = InspectOptions {
+            clean_numbers: true,
+            thousands_separators: Some(vec!['\'', ' ']),
+            currency_symbols: vec!['₹'],
+            ..InspectOptions::default()
+        };
+        let stats = column(&["1'234", "₹12 345", "7"], &opts);
+        assert_eq!(stats.kind, ColumnType::Numeric);
+        assert_eq!((stats.min, stats.max), (Some(7.0), Some(12345.0)));
+
+        // the defaults no longer apply once they are replaced
+        let stats = column(&["1,234", "$5"], &opts);
+        assert_ne!(stats.kind, ColumnType::Numeric);
+        let stats = column(&["1'234", "₹5"], &InspectOptions { clean_numbers: true, ..InspectOptions::default() });
+        assert_ne!(stats.kind, ColumnType::Numeric);
+    }
+
+    #[test]
+    fn sample_after_skip_names_its_rows() {
+        let csv = std::iter::once("n".to_string())
+            .chain((0..1000).map(|i| i.to_string()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let header = |skip| {
+            let opts = InspectOptions { skip, quiet: true, ..InspectOptions::default() };
+            let (report, _) = read_csv_text(&csv, opts.clone());
+            let mut out = Vec::new();
+            print_report(&mut out, &report, &opts).unwrap();
+            String::from_utf8(out).unwrap().lines().next().unwrap().to_string()
+        };
+        assert_eq!(header(0), "Sample (first 10 rows):");
+        assert_eq!(header(990), "Sample (rows 991-1000):");
+    }
+
+    #[test]
+    fn column_examples_count_rows_from_the_start_of_the_file() {
+        let opts = InspectOptions { skip: 2, ..InspectOptions::default() };
+        let (report, _) = read_csv_text("x\n1\n2\n3\n-4\nfoo\n", opts.clone());
+        let dirty = report.diagnostics.iter().find(|d| d.check == "dirty-rows").unwrap();
+        assert!(dirty.message.ends_with("(row 5)"), "{}", dirty.message);
+        let column = report.columns[0].diagnostics.as_ref().unwrap();
+        let mixed = column.iter().find(|d| d.check == "mixed-type").unwrap();
+        assert!(mixed.message.contains("(row 5: \"foo\")"), "{}", mixed.message);
+
+        // the row of the number that upgraded a text column
+        let opts = InspectOptions { explain: true, ..opts };
+        let (report, _) = read_csv_text("x\na\nb\nc\nd\n5\n6\n", opts);
+        assert_eq!(report.columns[0].inference.as_ref().unwrap().upgraded_at, Some(5));
+    }
+
+    #[test]
+    fn dry_run_is_complete_only_when_the_file_ran_out() {
+        let path = fixture("dry-run.csv", "a\n1\n2\n3\n4\n5\n");
+        let estimate = |limit| {
+            let opts = InspectOptions { limit, ..InspectOptions::default() };
+            let estimate = estimate_memory(path.to_str().unwrap(), &opts).unwrap();
+            (estimate.complete, estimate.sampled_rows, estimate.projected_rows)
+        };
+        assert_eq!(estimate(None), (true, 5, 5));
+        assert_eq!(estimate(Some(10)), (true, 5, 5));
+        assert_eq!(estimate(Some(2)), (false, 2, 2));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parallel_read_matches_sequential_with_quoted_newlines_and_bare_quotes() {
+        // records end at \n, \r\n or a lone \r; quoted fields keep their \n
+        for terminator in ["\n", "\r\n", "\r"] {
+            let mut csv = format!("id,desc,qty{}", terminator);
+            for i in 0..300_000 {
+                if i == 1000 {
+                    // a quote inside an unquoted field is a literal, not an opening quote
+                    csv.push_str(&format!("10,a 5\" pipe,3{}", terminator));
+                } else if i % 7 == 0 {
+                    csv.push_str(&format!("{},\"line one\nline \"\"two\"\", {}\",{}{}", i, i, i % 13, terminator));
+                } else {
+                    csv.push_str(&format!("{},item {},{}{}", i, i * 3, i % 13, terminator));
+                }
+            }
+            let path = fixture("parallel.csv", &csv);
+            let filename = path.to_str().unwrap();
+
+            let sequential = inspect_file(filename, &InspectOptions::default()).unwrap();
+            let parallel_opts = InspectOptions {
+                threads: 4,
+                ..InspectOptions::default()
+            };
+            assert!(splits_into_chunks(filename, InputFormat::Csv, &parallel_opts));
+            assert!(csv_chunks(&File::open(&path).unwrap(), 4, &parallel_opts).unwrap().len() > 1);
+            let parallel = inspect_file(filename, &parallel_opts).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(sequential.rows, 300_000, "{:?}", terminator);
+            assert_eq!(sequential.columns[1].kind, Some(ColumnType::Categorical));
+            assert_close(
+                &serde_json::to_value(&sequential).unwrap(),
+                &serde_json::to_value(&parallel).unwrap(),
+                "report",
+            );
+        }
+
+        // a file under one chunk still needs the end of its header found
+        let path = fixture("parallel-cr.csv", "a,b\r1,2\r3,4\r");
+        let parallel_opts = InspectOptions {
+            threads: 4,
+            ..InspectOptions::default()
+        };
+        let report = inspect_file(path.to_str().unwrap(), &parallel_opts).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report.rows, 2);
+    }
+
+    #[test]
+    fn strict_rejects_the_first_ragged_row() {
+        let csv = "a,b\n1,2\n3\n4,5\n";
+        let (report, _) = read_csv_text(csv, InspectOptions::default());
+        assert_eq!((report.rows, report.ragged_rows), (3, 1));
+
+        let strict = InspectOptions { strict: true, ..InspectOptions::default() };
+        let error = inspect_reader(csv.as_bytes(), InputFormat::Csv, &strict).unwrap_err();
+        assert!(error.to_string().contains("found record with 1 fields"), "{}", error);
+    }
+
+    #[test]
+    fn fixed_width_lines_are_cut_by_character_position() {
+        let text = "00000001Zoë       12.50\n\n00000002Bob          \n00000003\n";
+        let opts = InspectOptions {
+            fixed_width: Some("id:0:8,name:8:10,balance:18:5".to_string()),
+            sample_rows: Some(10),
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(text.as_bytes(), InputFormat::FixedWidth, &opts).unwrap();
+        assert_eq!(report.file_type, "Fixed-width");
+        // the blank line is skipped; the last line ends before the name starts
+        assert_eq!((report.rows, report.ragged_rows), (3, 1));
+        let rows = &report.sample.as_ref().unwrap().rows;
+        assert_eq!(rows[0], ["00000001", "Zoë", "12.50"]);
+        assert_eq!(rows[1], ["00000002", "Bob", ""]);
+        assert_eq!(report.columns[2].kind, Some(ColumnType::Numeric));
+
+        for spec in ["id:0", "id:x:8", "id:0:0", "id:0:8,id:8:2", ""] {
+            let opts = InspectOptions { fixed_width: Some(spec.to_string()), ..InspectOptions::default() };
+            let result = inspect_reader(text.as_bytes(), InputFormat::FixedWidth, &opts);
+            assert!(matches!(result, Err(InspectError::Usage(_))), "{:?}", spec);
+        }
+    }
+
+    #[test]
+    fn count_only_agrees_with_a_full_read_inside_the_window() {
+        let path = fixture("count.csv", "a,b\n1,\"two\nlines\"\n2,x\n3,y\n4,z\n5,w\n");
+        let filename = path.to_str().unwrap();
+        for (skip, limit) in [(0, None), (2, None), (1, Some(2)), (4, Some(3)), (9, None)] {
+            let opts = InspectOptions { skip, limit, ..InspectOptions::default() };
+            let count = count_rows(filename, &opts).unwrap();
+            let report = inspect_file(filename, &opts).unwrap();
+            assert_eq!((count.rows, count.columns), (report.rows, 2), "skip {} limit {:?}", skip, limit);
+        }
+        let count = count_rows(filename, &InspectOptions::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // the quoted newline doesn't start a row
+        assert_eq!(count.rows, 5);
+    }
+
+    #[test]
+    fn quartiles_interpolate_between_the_sorted_values() {
+        let opts = InspectOptions { show_percentiles: true, ..InspectOptions::default() };
+        let (report, _) = read_csv_text("x,y\n4,9\n1,\n3,1\n2,5\n", opts);
+        let quartiles = |i: usize| {
+            let summary = report.columns[i].summary.as_ref().unwrap();
+            (summary.q1, summary.median, summary.q3)
+        };
+        assert_eq!(quartiles(0), (Some(1.75), Some(2.5), Some(3.25)));
+        // missing values are left out
+        assert_eq!(quartiles(1), (Some(3.0), Some(5.0), Some(7.0)));
+
+        let (report, _) = read_csv_text("x\n4\n1\n", InspectOptions::default());
+        assert_eq!(report.columns[0].summary.as_ref().unwrap().median, None);
+    }
+
+    #[test]
+    fn json_report_is_one_line_or_pretty_with_the_same_content() {
+        let opts = InspectOptions { json: true, ..InspectOptions::default() };
+        let report = inspect_reader("id,name\n1,a\n2,\n".as_bytes(), InputFormat::Csv, &opts).unwrap();
+        let mut out = Vec::new();
+        emit_report(&mut out, &report, &opts).unwrap();
+        let compact = String::from_utf8(out).unwrap();
+        assert_eq!(compact.lines().count(), 1);
+
+        let json: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        assert_eq!(json["file_type"], "CSV");
+        assert_eq!(json["rows"], 2);
+        assert_eq!(json["columns"][0]["name"], "id");
+        assert_eq!(json["columns"][0]["type"], "numeric");
+        assert_eq!(json["columns"][1]["summary"]["missing"], 1);
+
+        let pretty = InspectOptions { json_pretty: true, ..opts };
+        let mut out = Vec::new();
+        emit_report(&mut out, &report, &pretty).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.lines().count() > 1);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&text).unwrap(), json);
+    }
+
+    #[test]
+    fn delimiters_split_tsv_and_semicolon_files() {
+        let opts = InspectOptions { delimiter: Some(b';'), ..InspectOptions::default() };
+        let (report, rows) = read_csv_text("a;b\n1,5;x\n", opts);
+        assert_eq!(report.columns.len(), 2);
+        assert_eq!(rows, [["1,5", "x"]]);
+
+        let opts = InspectOptions { sample_rows: Some(1), ..InspectOptions::default() };
+        let report = inspect_reader("a\tb\n1\tx y\n".as_bytes(), InputFormat::Tsv, &opts).unwrap();
+        assert_eq!(report.sample.unwrap().rows, [["1", "x y"]]);
+    }
+
+    #[test]
+    fn unreadable_input_is_an_error_not_a_panic() {
+        let missing = std::env::temp_dir().join("datainspect-no-such-file.csv");
+        let error = inspect_file(missing.to_str().unwrap(), &InspectOptions::default()).unwrap_err();
+        assert!(matches!(error, InspectError::Io { .. }));
+        assert!(error.to_string().contains("datainspect-no-such-file.csv"), "{}", error);
+        assert_eq!(error.exit_code(), 1);
+
+        let invalid_utf8: &[u8] = b"a,b\n1,\xff\xfe\n";
+        let error = inspect_reader(invalid_utf8, InputFormat::Csv, &InspectOptions::default()).unwrap_err();
+        assert_eq!(error.exit_code(), 1);
+        assert!(error.to_string().starts_with("invalid UTF-8 in record 1 (line 2, field 2)"), "{}", error);
+    }
+
+    #[test]
+    fn mostly_dates_make_a_datetime_column() {
+        let mut csv = String::from("day,stamp,mixed\n");
+        for d in 1..=9 {
+            csv += &format!("2024-03-0{},2024-03-0{} 12:30:00,2024-03-0{}\n", d, d, d);
+        }
+        csv += "03/15/2024,2024-03-10T08:00:00,soon\n";
+        csv += "2024-03-11,2024-03-11T09:00:00,later\n";
+        let (report, _) = read_csv_text(&csv, InspectOptions::default());
+        let summary = |i: usize| report.columns[i].summary.as_ref().unwrap();
+
+        assert_eq!(report.columns[0].kind, Some(ColumnType::DateTime));
+        assert_eq!(summary(0).earliest.as_deref(), Some("2024-03-01"));
+        assert_eq!(summary(0).latest.as_deref(), Some("2024-03-15"));
+        assert_eq!(report.columns[1].kind, Some(ColumnType::DateTime));
+        assert_eq!(summary(1).latest.as_deref(), Some("2024-03-11T09:00:00"));
+        // 9 of 11 are dates, under the 90% it takes
+        assert_eq!(report.columns[2].kind, Some(ColumnType::Categorical));
+
+        for invalid in ["2024-02-30", "2024-13-01", "2024-03-01 25:00:00", "1/2/24"] {
+            assert_eq!(parse_datetime(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn mode_and_top_values_break_ties_alphabetically() {
+        let opts = InspectOptions { top: Some(2), ..InspectOptions::default() };
+        let (report, _) = read_csv_text("letter\nc\nb\na\nb\na\nc\nd\nb\n", opts);
+        let summary = report.columns[0].summary.as_ref().unwrap();
+        assert_eq!(summary.mode.as_deref(), Some("b"));
+        let top: Vec<(&str, usize)> =
+            summary.top.as_ref().unwrap().iter().map(|v| (v.value.as_str(), v.count)).collect();
+        // a and c both come twice
+        assert_eq!(top, [("b", 3), ("a", 2)]);
+        assert_eq!(summary.top.as_ref().unwrap()[0].percent, 3.0 / 8.0 * 100.0);
+    }
+
+    #[test]
+    fn columns_select_by_name_or_index_in_file_order() {
+        let csv = "a,b,2,d\n1,2,3,4\n";
+        let names = |opts: InspectOptions| -> Vec<String> {
+            let (report, _) = read_csv_text(csv, opts);
+            report.columns.iter().map(|c| c.name.clone()).collect()
+        };
+        let columns = |spec: &str| InspectOptions { columns: Some(spec.to_string()), ..InspectOptions::default() };
+        assert_eq!(names(columns("d, a")), ["a", "d"]);
+        assert_eq!(names(columns("1,b")), ["b"]);
+        // a header named like an index wins over the index
+        assert_eq!(names(columns("2")), ["2"]);
+
+        let error = inspect_reader(csv.as_bytes(), InputFormat::Csv, &columns("a,e")).unwrap_err();
+        assert!(matches!(error, InspectError::UnknownColumn(name) if name == "e"));
+    }
+
+    #[test]
+    fn json_lines_are_records_with_a_line_number_on_errors() {
+        let text = "{\"id\": 1, \"name\": \"a\"}\n\n{\"id\": 2, \"score\": 0.5}\n";
+        let report = inspect_reader(text.as_bytes(), InputFormat::JsonLines, &InspectOptions::default()).unwrap();
+        assert_eq!((report.file_type, report.rows), ("JSONL", 2));
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "score"]);
+        // fields absent from a record are missing there
+        assert_eq!(report.columns[1].summary.as_ref().unwrap().missing, 1);
+        assert_eq!(report.columns[2].summary.as_ref().unwrap().missing, 1);
+
+        let error = inspect_reader("{}\n[1]\n".as_bytes(), InputFormat::JsonLines, &InspectOptions::default());
+        assert!(matches!(error, Err(InspectError::JsonLine { line: 2, .. })), "{:?}", error);
+        let error = inspect_reader("{}\n{\"a\":\n".as_bytes(), InputFormat::JsonLines, &InspectOptions::default());
+        assert!(matches!(error, Err(InspectError::JsonLine { line: 2, .. })), "{:?}", error);
+    }
+
+    #[test]
+    fn json_records_get_the_csv_summary_and_diagnostics() {
+        let text = r#"[{"price": 1.5, "tag": "a"}, {"price": 2.5, "tag": "b"}, {"price": null, "tag": "a"}]"#;
+        let opts = InspectOptions {
+            show_summary: true,
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(text.as_bytes(), InputFormat::Json, &opts).unwrap();
+        assert_eq!((report.file_type, report.rows), ("JSON", 3));
+        let price = &report.columns[0];
+        assert_eq!((price.name.as_str(), price.kind), ("price", Some(ColumnType::Numeric)));
+        let summary = price.summary.as_ref().unwrap();
+        assert_eq!((summary.count, summary.missing), (2, 1));
+        assert_eq!((summary.min, summary.max, summary.mean), (Some(1.5), Some(2.5), Some(2.0)));
+        let diagnostics = price.diagnostics.as_ref().unwrap();
+        assert!(diagnostics.iter().any(|d| d.check == "missing"), "{:?}", diagnostics);
+        assert_eq!(report.columns[1].summary.as_ref().unwrap().mode.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn sampled_inference_outvotes_a_stray_first_value() {
+        let values = ["n/a", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10"];
+        // without a sample the column upgrades on the first number, dropping
+        // what came before it
+        let stats = column(&values, &InspectOptions::default());
+        assert_eq!((stats.kind, stats.before_promotion), (ColumnType::Numeric, 1));
+
+        let sampled = InspectOptions {
+            infer_sample: Some(20),
+            ..InspectOptions::default()
+        };
+        let stats = column(&values, &sampled);
+        assert_eq!(stats.kind, ColumnType::Numeric);
+        assert_eq!((stats.count, stats.numeric_parse_failures, stats.before_promotion), (10, 1, 0));
+
+        // below the threshold the column stays categorical
+        let stats = column(&["a", "b", "1", "2"], &sampled);
+        assert_eq!(stats.kind, ColumnType::Categorical);
+    }
+
+    #[test]
+    fn whole_duplicate_rows_are_counted_and_reported() {
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("id,name\n1,a\n2,b\n1,a\n1,a\n1,b\n", opts);
+        assert_eq!(report.duplicate_rows, 2);
+        let duplicates = report.diagnostics.iter().find(|d| d.check == "duplicates").unwrap();
+        assert_eq!(duplicates.message, "duplicate rows: 2 (40.0%)");
+
+        let (report, _) = read_csv_text("id,name\n1,a\n1,b\n", InspectOptions::default());
+        assert_eq!(report.duplicate_rows, 0);
+    }
+
+    #[test]
+    fn the_outlier_threshold_follows_outlier_sigma() {
+        let mut values = vec!["0"; 19];
+        values.push("10");
+        // the outlier pass runs over the values buffered for --diagnose
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        // z of the 10 is about 4.3
+        assert_eq!(column(&values, &opts).outlier_count, 0);
+        let strict = InspectOptions {
+            outlier_sigma: 3.0,
+            ..opts
+        };
+        assert_eq!(column(&values, &strict).outlier_count, 1);
+    }
+
+    #[test]
+    fn outliers_are_found_wherever_they_appear() {
+        let opts = InspectOptions {
+            show_diagnose: true,
+            outlier_sigma: 3.0,
+            ..InspectOptions::default()
+        };
+        let mut values = vec!["0"; 19];
+        values.push("10");
+        let last = column(&values, &opts);
+        values.rotate_right(1);
+        assert_eq!(values[0], "10");
+        let first = column(&values, &opts);
+        assert_eq!((first.outlier_count, last.outlier_count), (1, 1));
+        assert_eq!((first.min_z, first.max_z), (last.min_z, last.max_z));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_columns_take_their_types_from_the_schema() {
+        use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+        use parquet::arrow::ArrowWriter;
+        use std::sync::Arc;
+
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(4)])) as ArrayRef),
+            // digits in a string column stay text
+            ("code", Arc::new(StringArray::from(vec!["07", "08", "09", "10"])) as ArrayRef),
+        ])
+        .unwrap();
+        let path = fixture("typed.parquet", "");
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let opts = InspectOptions {
+            show_summary: true,
+            skip: 1,
+            ..InspectOptions::default()
+        };
+        let report = inspect_file(path.to_str().unwrap(), &opts).unwrap();
+        assert_eq!((report.file_type, report.rows), ("Parquet", 3));
+        let kinds: Vec<_> = report.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, [Some(ColumnType::Numeric), Some(ColumnType::Categorical)]);
+        let id = report.columns[0].summary.as_ref().unwrap();
+        assert_eq!((id.count, id.missing, id.min, id.max), (2, 1, Some(2.0), Some(4.0)));
+    }
+
+    #[test]
+    fn limit_stops_after_n_rows_and_marks_the_report() {
+        let opts = InspectOptions {
+            limit: Some(2),
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, rows) = read_csv_text("n\n1\n2\n3\n4\n", opts);
+        assert_eq!((report.rows, report.limited), (2, true));
+        assert_eq!(rows, [["1"], ["2"]]);
+        assert_eq!(report.columns[0].summary.as_ref().unwrap().max, Some(2.0));
+
+        // a limit past the end is not a sample
+        let opts = InspectOptions {
+            limit: Some(10),
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("n\n1\n2\n", opts);
+        assert_eq!((report.rows, report.limited), (2, false));
+    }
+
+    #[test]
+    fn gzip_input_is_read_by_its_inner_extension() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let gzip = |text: &str| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(text.as_bytes()).unwrap();
+            encoder.finish().unwrap()
+        };
+        let csv = fixture("data.csv.gz", "");
+        std::fs::write(&csv, gzip("a,b\n1,x\n2,y\n")).unwrap();
+        let report = inspect_file(csv.to_str().unwrap(), &InspectOptions::default()).unwrap();
+        assert_eq!((report.file_type, report.rows, report.columns.len()), ("CSV", 2, 2));
+
+        let json = fixture("data.json.gz", "");
+        std::fs::write(&json, gzip(r#"[{"a": 1}, {"a": 2}, {"a": 3}]"#)).unwrap();
+        let report = inspect_file(json.to_str().unwrap(), &InspectOptions::default()).unwrap();
+        assert_eq!((report.file_type, report.rows), ("JSON", 3));
+    }
+
+    #[test]
+    fn histograms_split_the_range_into_equal_bins() {
+        let opts = InspectOptions {
+            histogram_bins: Some(4),
+            ..InspectOptions::default()
+        };
+        let stats = column(&["0", "1", "2", "3", "4", "5", "6", "7", "7"], &opts);
+        let bins = stats.histogram(4).unwrap();
+        let bins: Vec<_> = bins.iter().map(|b| (b.start, b.end, b.count)).collect();
+        // the maximum falls in the last bin
+        assert_eq!(bins, [(0.0, 1.75, 2), (1.75, 3.5, 2), (3.5, 5.25, 2), (5.25, 7.0, 3)]);
+
+        assert_eq!(histogram_bar(3, 3, 4), "████");
+        assert_eq!(histogram_bar(1, 2, 3), "█▌");
+        assert_eq!(histogram_bar(0, 2, 3), "");
+    }
+
+    #[test]
+    fn the_schema_line_tallies_the_column_types() {
+        let csv = "a,b,name,day\n1,2.5,x,2024-01-01\n2,3.5,y,2024-01-02\n";
+        let (report, _) = read_csv_text(csv, InspectOptions::default());
+        assert_eq!(report.schema, [("numeric", 2), ("categorical", 1), ("datetime", 1)]);
+
+        let mut out = Vec::new();
+        print_report(&mut out, &report, &InspectOptions::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Schema: 2 numeric, 1 categorical, 1 datetime\n"), "{}", out);
+    }
+
+    #[test]
+    fn without_a_header_the_first_record_is_data() {
+        let opts = InspectOptions {
+            no_header: true,
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, rows) = read_csv_text("1,x\n2,y\n", opts);
+        assert_eq!(report.rows, 2);
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["col_0", "col_1"]);
+        assert_eq!(rows[0], ["1", "x"]);
+        assert_eq!(report.columns[0].summary.as_ref().unwrap().min, Some(1.0));
+    }
+
+    #[test]
+    fn boolean_columns_count_true_and_false() {
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("flag,bit\ntrue,1\nFalse,0\nyes,1\n,1\n", opts);
+        let flag = &report.columns[0];
+        assert_eq!(flag.kind, Some(ColumnType::Boolean));
+        let summary = flag.summary.as_ref().unwrap();
+        assert_eq!((summary.true_count, summary.false_count, summary.missing), (Some(2), Some(1), 1));
+        assert_eq!(summary.true_ratio.map(|r| (r * 1000.0).round()), Some(667.0));
+        // 0/1 flags too
+        assert_eq!(report.columns[1].kind, Some(ColumnType::Boolean));
+
+        assert_eq!(column(&["true", "maybe"], &InspectOptions::default()).kind, ColumnType::Categorical);
+    }
+
+    #[test]
+    fn correlations_pair_up_the_numeric_columns() {
+        let opts = InspectOptions {
+            correlate: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("x,name,up,down,flat\n1,a,2,9,5\n2,b,4,6,5\n3,c,6,3,5\n4,d,8,1,5\n", opts);
+        let matrix = report.correlations.unwrap();
+        assert_eq!(matrix.columns, ["x", "up", "down", "flat"]);
+        assert_near(matrix.values[0][1].unwrap(), 1.0, "x~up");
+        assert!(matrix.values[0][2].unwrap() < -0.98, "{:?}", matrix.values[0][2]);
+        assert_eq!(matrix.values[1][0], matrix.values[0][1]);
+        // no spread, no correlation
+        assert_eq!(matrix.values[0][3], None);
+    }
+
+    #[test]
+    fn diagnostics_carry_severities_for_fail_on() {
+        let opts = InspectOptions {
+            fail_on: Some(Severity::Warning),
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("id,n\n1,1\n2,x\n3,3\n4,4\n5,5\n6,6\n7,7\n8,8\n9,9\n10,10\n", opts);
+        let severity = |check: &str| report.diagnostics().find(|d| d.check == check).map(|d| d.severity);
+        assert_eq!(severity("primary-key"), Some(Severity::Info));
+        assert_eq!(severity("mixed-type"), Some(Severity::Warning));
+        assert_eq!(report.diagnostics().filter(|d| d.severity >= Severity::Error).count(), 0);
+
+        assert!(Severity::Error > Severity::Warning && Severity::Warning > Severity::Info);
+        assert_eq!(Severity::parse("warning"), Some(Severity::Warning));
+        assert_eq!(Severity::parse("fatal"), None);
+        let error = InspectError::ChecksFailed { count: 2, level: Severity::Warning };
+        assert_eq!(error.to_string(), "2 diagnostics at or above warning level");
+        assert_eq!(error.exit_code(), 1);
+    }
+
+    #[test]
+    fn missing_and_cardinality_thresholds_are_configurable() {
+        let names: Vec<String> = (0..19).map(|i| format!("v{}", i)).collect();
+        let mut values: Vec<&str> = names.iter().map(String::as_str).collect();
+        values.push("");
+        let checks = |opts: &InspectOptions| -> Vec<&'static str> {
+            let stats = column(&values, opts);
+            stats.diagnostics(values.len(), opts).iter().map(|d| d.check).collect()
+        };
+
+        // 5% missing is not past the default 0.05, every value is unique
+        let defaults = InspectOptions::default();
+        assert_eq!(checks(&defaults), ["cardinality"]);
+        let tuned = InspectOptions {
+            missing_threshold: 0.01,
+            cardinality_threshold: 1.0,
+            ..defaults
+        };
+        assert_eq!(checks(&tuned), ["missing"]);
+    }
+
+    // a one-sheet workbook with inline-string and number cells
+    #[cfg(feature = "xlsx")]
+    fn xlsx_fixture(name: &str, sheet: &str, rows: &[&[&str]]) -> PathBuf {
+        use zip::write::{SimpleFileOptions, ZipWriter};
+
+        let cell = |value: &str| match value.parse::<f64>() {
+            Ok(_) => format!("<c><v>{}</v></c>", value),
+            Err(_) => format!("<c t=\"inlineStr\"><is><t>{}</t></is></c>", value),
+        };
+        let rows: String = rows
+            .iter()
+            .map(|row| format!("<row>{}</row>", row.iter().map(|v| cell(v)).collect::<String>()))
+            .collect();
+        let main = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+        let relationships = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+        let parts = [
+            (
+                "[Content_Types].xml",
+                "<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+                 <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+                 <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+                 <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+                 <Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+                 </Types>"
+                    .to_string(),
+            ),
+            (
+                "_rels/.rels",
+                format!(
+                    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+                     <Relationship Id=\"rId1\" Type=\"{}/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>",
+                    relationships
+                ),
+            ),
+            (
+                "xl/workbook.xml",
+                format!(
+                    "<workbook xmlns=\"{}\" xmlns:r=\"{}\"><sheets><sheet name=\"{}\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>",
+                    main, relationships, sheet
+                ),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                format!(
+                    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+                     <Relationship Id=\"rId1\" Type=\"{}/worksheet\" Target=\"worksheets/sheet1.xml\"/></Relationships>",
+                    relationships
+                ),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                format!("<worksheet xmlns=\"{}\"><sheetData>{}</sheetData></worksheet>", main, rows),
+            ),
+        ];
+
+        let path = fixture(name, "");
+        let mut zip = ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for (part, xml) in parts {
+            zip.start_file(part, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    }
+
+    #[cfg(feature = "xlsx")]
+    #[test]
+    fn xlsx_sheets_are_inspected_like_csv() {
+        let path = xlsx_fixture("book.xlsx", "Data", &[&["id", "name"], &["1", "a"], &["2", "b"], &["3", "a"]]);
+        let path = path.to_str().unwrap();
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let report = inspect_file(path, &opts).unwrap();
+        assert_eq!((report.file_type, report.rows), ("XLSX", 3));
+        let kinds: Vec<_> = report.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, [Some(ColumnType::Numeric), Some(ColumnType::Categorical)]);
+        assert_eq!(report.columns[0].summary.as_ref().unwrap().max, Some(3.0));
+
+        let data = InspectOptions {
+            sheet: Some("Data".to_string()),
+            ..InspectOptions::default()
+        };
+        assert_eq!(inspect_file(path, &data).unwrap().rows, 3);
+        let missing = InspectOptions {
+            sheet: Some("Sheet2".to_string()),
+            ..InspectOptions::default()
+        };
+        assert!(matches!(inspect_file(path, &missing), Err(InspectError::UnknownSheet { .. })));
+    }
+
+    #[test]
+    fn quiet_leaves_out_the_preamble() {
+        let print = |opts: &InspectOptions| {
+            let report = inspect_reader("n\n1\n2\n".as_bytes(), InputFormat::Csv, opts).unwrap();
+            let mut out = Vec::new();
+            print_report(&mut out, &report, opts).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let loud = print(&opts);
+        assert!(loud.starts_with("File type: CSV\nRows: 2\nColumns:\n  - n\n"), "{}", loud);
+
+        let quiet = print(&InspectOptions { quiet: true, ..opts });
+        assert!(!quiet.contains("File type:") && !quiet.contains("Columns:"), "{}", quiet);
+        assert!(quiet.contains("n (numeric): count=2"), "{}", quiet);
+        assert!(loud.ends_with(&quiet), "{}", quiet);
+    }
+
+    #[test]
+    fn values_that_differ_only_by_surrounding_whitespace_are_flagged() {
+        let opts = InspectOptions::default();
+        let values = ["active", "active ", " active", "closed", "closed", "open"];
+        let stats = column(&values, &opts);
+        assert_eq!(stats.whitespace_collisions().0, 2);
+        let diagnostics = stats.diagnostics(values.len(), &opts);
+        let whitespace = diagnostics.iter().find(|d| d.check == "whitespace").unwrap();
+        assert!(whitespace.message.starts_with("whitespace variants: 2 distinct values merge"), "{}", whitespace.message);
+
+        let stats = column(&["active", "closed", "open"], &opts);
+        assert_eq!(stats.whitespace_collisions().0, 0);
+    }
+
+    #[test]
+    fn diagnostics_are_colored_by_severity_only_when_asked() {
+        let print = |warnings: &[Diagnostic], color: bool| {
+            let mut out = Vec::new();
+            print_warnings(&mut out, warnings, color).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let warnings = [
+            Diagnostic::new("missing", Severity::Error, "missing values: 60.0%"),
+            Diagnostic::new("constant", Severity::Warning, "constant column"),
+        ];
+        assert_eq!(print(&warnings, false), "  ! error: missing values: 60.0%\n  ! warning: constant column\n");
+        assert_eq!(print(&[], false), "  ok\n");
+
+        let colored = print(&warnings, true);
+        assert!(colored.contains("\x1b[1m\x1b[31m! error: missing values: 60.0%"), "{:?}", colored);
+        assert!(colored.contains("\x1b[33m! warning: constant column\x1b[39m"), "{:?}", colored);
+        assert_eq!(print(&[], true), "  \x1b[32mok\x1b[39m\n");
+    }
+
+    #[test]
+    fn numeric_columns_are_integer_or_float() {
+        let opts = InspectOptions::default();
+        assert_eq!(column(&["1", "-2", "30"], &opts).numeric_type(), Some("integer"));
+        assert_eq!(column(&["1", "2.5", "3"], &opts).numeric_type(), Some("float"));
+        assert_eq!(column(&["a", "b"], &opts).numeric_type(), None);
+
+        let (report, _) = read_csv_text("i,f,s\n1,1.5,x\n2,2,y\n", InspectOptions::default());
+        let mut out = Vec::new();
+        print_types(&mut out, &report).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("  - i: integer\n  - f: float\n  - s: categorical\n"), "{}", out);
+    }
+
+    #[test]
+    fn values_past_max_length_are_flagged() {
+        let values = ["short", "héllo wörld", "mid"];
+        let stats = column(&values, &InspectOptions::default());
+        // in characters, not bytes
+        assert_eq!(stats.max_length, 11);
+        let checks = |limit: usize| {
+            let opts = InspectOptions {
+                max_length: Some(limit),
+                ..InspectOptions::default()
+            };
+            stats.diagnostics(values.len(), &opts).into_iter().find(|d| d.check == "max-length")
+        };
+        assert_eq!(checks(11).map(|d| d.message), None);
+        assert_eq!(
+            checks(10).map(|d| d.message).as_deref(),
+            Some("values longer than 10 characters (max length 11)")
+        );
+    }
+
+    #[test]
+    fn progress_counts_bytes_read_and_rows_in_steps() {
+        let path = fixture("progress.csv", "n\n1\n2\n3\n");
+        let path = path.to_str().unwrap();
+        // only large files get a bar, and never with --no-progress
+        assert!(start_progress(path, &InspectOptions::default()).is_none());
+        let quiet = InspectOptions {
+            no_progress: true,
+            ..InspectOptions::default()
+        };
+        assert!(start_progress(path, &quiet).is_none());
+
+        let bar = ProgressBar::hidden();
+        let mut text = String::new();
+        open_input(path, Some(&bar)).unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(bar.position(), text.len() as u64);
+
+        report_rows(Some(&bar), 9_999);
+        assert_eq!(bar.message(), "");
+        report_rows(Some(&bar), 10_000);
+        assert_eq!(bar.message(), "10000 rows");
+    }
+
+    #[test]
+    fn yaml_lists_of_mappings_are_records() {
+        let text = "- id: 10\n  name: a\n- id: 20\n  name: b\n  tags: [x, y]\n";
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(text.as_bytes(), InputFormat::Yaml, &opts).unwrap();
+        assert_eq!((report.file_type, report.rows), ("YAML", 2));
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "tags", "tags.length"]);
+        assert_eq!(report.columns[0].kind, Some(ColumnType::Numeric));
+        assert_eq!(report.columns[0].summary.as_ref().unwrap().mean, Some(15.0));
+        assert_eq!(InputFormat::from_extension("yml"), Some(InputFormat::Yaml));
+    }
+
+    #[test]
+    fn parse_failures_name_their_rows_up_to_a_cap() {
+        let mut values = vec!["5", "x", "7", "y"];
+        values.extend(["?"; MAX_FAILURE_EXAMPLES]);
+        let opts = InspectOptions::default();
+        let stats = column(&values, &opts);
+        assert_eq!(stats.numeric_parse_failures, MAX_FAILURE_EXAMPLES + 2);
+        assert_eq!(stats.failure_examples.len(), MAX_FAILURE_EXAMPLES);
+        assert_eq!(stats.failure_examples[..2], [(2, "x".to_string()), (4, "y".to_string())]);
+
+        let diagnostics = stats.diagnostics(values.len(), &opts);
+        let mixed = diagnostics.iter().find(|d| d.check == "mixed-type").unwrap();
+        let head = "mixed numeric and non-numeric values: 12 unparsed (row 2: \"x\", row 4: \"y\", row 5: \"?\"";
+        assert!(mixed.message.starts_with(head), "{}", mixed.message);
+        assert!(mixed.message.ends_with(", ...)"), "{}", mixed.message);
+    }
+
+    #[test]
+    fn the_sample_prints_the_first_rows_aligned() {
+        let opts = InspectOptions {
+            sample_rows: Some(2),
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader("id,name\n1,alice\n22,bo\n333,cy\n".as_bytes(), InputFormat::Csv, &opts).unwrap();
+        let sample = report.sample.as_ref().unwrap();
+        assert_eq!(sample.columns, ["id", "name"]);
+        assert_eq!(sample.rows, [["1", "alice"], ["22", "bo"]]);
+
+        let mut out = Vec::new();
+        print_sample(&mut out, sample).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "  id  name\n  1   alice\n  22  bo\n");
+    }
+
+    #[test]
+    fn iqr_outliers_fall_outside_the_tukey_fences() {
+        let values = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "100"];
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        // the tail pulls the mean and stddev along, so z stays near 3
+        assert_eq!(column(&values, &opts).outlier_count, 0);
+
+        let iqr = InspectOptions {
+            outlier_method: OutlierMethod::Iqr,
+            ..opts
+        };
+        let stats = column(&values, &iqr);
+        assert_eq!(stats.outlier_count, 1);
+        // Q1 = 3.25, Q3 = 7.75
+        assert_eq!(stats.outlier_fences, Some((-3.5, 14.5)));
+    }
+
+    #[test]
+    fn exclude_columns_drops_them_from_the_report() {
+        let csv = "id,note,score,memo\n1,a,2,x\n2,b,3,y\n";
+        let opts = InspectOptions {
+            exclude_columns: Some("note,3".to_string()),
+            ..InspectOptions::default()
+        };
+        let (report, rows) = read_csv_text(csv, opts);
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "score"]);
+        assert_eq!(rows[0], ["1", "2"]);
+
+        // applied after --columns
+        let opts = InspectOptions {
+            columns: Some("id,note".to_string()),
+            exclude_columns: Some("id".to_string()),
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text(csv, opts);
+        assert_eq!(report.columns.len(), 1);
+        assert_eq!(report.columns[0].name, "note");
+
+        let opts = InspectOptions {
+            exclude_columns: Some("nope".to_string()),
+            ..InspectOptions::default()
+        };
+        let error = inspect_reader(csv.as_bytes(), InputFormat::Csv, &opts);
+        assert!(matches!(error, Err(InspectError::UnknownColumn(ref name)) if name == "nope"), "{:?}", error);
+    }
+
+    #[test]
+    fn json_arrays_stream_record_by_record() {
+        // hands the parser one byte per read
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let Some((&byte, rest)) = self.0.split_first() else {
+                    return Ok(0);
+                };
+                buf[0] = byte;
+                self.0 = rest;
+                Ok(1)
+            }
+        }
+
+        let text = r#"[{"n": 1}, 7, {"n": 2}, {"n": 3}, {"n": 4}]"#;
+        let report = inspect_reader(Trickle(text.as_bytes()), InputFormat::Json, &InspectOptions::default()).unwrap();
+        // elements that aren't objects are not records
+        assert_eq!(report.rows, 4);
+
+        // past --limit the array is still read to the end, and checked
+        let opts = InspectOptions {
+            skip: 1,
+            limit: Some(2),
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(text.as_bytes(), InputFormat::Json, &opts).unwrap();
+        assert_eq!((report.rows, report.skipped_rows), (2, 1));
+        let truncated = &text[..text.len() - 1];
+        assert!(matches!(inspect_reader(truncated.as_bytes(), InputFormat::Json, &opts), Err(InspectError::Json(_))));
+
+        let error = inspect_reader("42".as_bytes(), InputFormat::Json, &InspectOptions::default());
+        assert!(matches!(error, Err(InspectError::UnsupportedJson)), "{:?}", error);
+    }
+
+    #[test]
+    fn an_all_missing_column_gets_one_error_of_its_own() {
+        let opts = InspectOptions {
+            na_values: vec!["NA".to_string()],
+            ..InspectOptions::default()
+        };
+        let values = ["", "NA", ""];
+        let stats = column(&values, &opts);
+        let diagnostics = stats.diagnostics(values.len(), &opts);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!((diagnostics[0].check, diagnostics[0].severity), ("missing", Severity::Error));
+        assert_eq!(diagnostics[0].message, "column is empty: all 3 values are missing");
+
+        // one value is enough to get the usual missing-values finding instead
+        let values = ["", "NA", "x"];
+        let stats = column(&values, &opts);
+        let messages: Vec<String> = stats.diagnostics(values.len(), &opts).into_iter().map(|d| d.message).collect();
+        assert!(messages.contains(&"missing values: 67%".to_string()), "{:?}", messages);
+    }
+
+    #[test]
+    fn na_values_are_missing_and_stay_out_of_the_stats() {
+        let opts = InspectOptions {
+            na_values: vec!["NA".to_string(), "-999".to_string()],
+            ..InspectOptions::default()
+        };
+        let stats = column(&["1", "NA", "3", "-999", ""], &opts);
+        assert_eq!(stats.kind, ColumnType::Numeric);
+        assert_eq!((stats.missing, stats.count, stats.numeric_parse_failures), (3, 2, 0));
+        assert_eq!((stats.min, stats.max), (Some(1.0), Some(3.0)));
+
+        // without them -999 is a number and NA a parse failure
+        let stats = column(&["1", "NA", "3", "-999", ""], &InspectOptions::default());
+        assert_eq!((stats.missing, stats.count, stats.numeric_parse_failures), (1, 3, 1));
+        assert_eq!(stats.min, Some(-999.0));
+    }
+
+    #[test]
+    fn the_quality_score_loses_points_by_weighted_share() {
+        let (report, _) = read_csv_text("n,tag\n1,x\n2,x\n3,y\n4,y\n5,x\n", InspectOptions::default());
+        assert_eq!(report.quality.score, 100.0);
+
+        // one of ten cells missing and one of five rows a duplicate
+        let (report, _) = read_csv_text("n,tag\n1,x\n2,x\n3,\n4,y\n4,y\n", InspectOptions::default());
+        let points: Vec<(&str, f64)> = report
+            .quality
+            .penalties
+            .iter()
+            .filter(|p| p.points > 0.0)
+            .map(|p| (p.check, p.points))
+            .collect();
+        assert_eq!(points, [("missing values", 3.0), ("duplicate rows", 3.0)]);
+        assert_eq!(report.quality.score, 94.0);
+    }
+
+    #[test]
+    fn column_stats_work_through_the_public_api() {
+        let mut stats = ColumnStats::new("price", ColumnType::Numeric);
+        for value in ["1.5", "", "2.5", "x"] {
+            stats.update(value);
+        }
+        stats.finalize();
+        assert_eq!((stats.name(), stats.kind()), ("price", ColumnType::Numeric));
+        let summary = stats.summary(&InspectOptions::default());
+        assert_eq!((summary.count, summary.missing, summary.mean), (3, 1, Some(2.0)));
+        assert!(stats.diagnostics(4, &InspectOptions::default()).iter().any(|d| d.check == "mixed-type"));
+
+        let kinds: Vec<&str> = ["7", "7.5", "yes", "2024-01-31", "abc", "12345678901234567890"]
+            .into_iter()
+            .map(infer_type)
+            .collect();
+        assert_eq!(kinds, ["integer", "float", "boolean", "datetime", "string", "large integer"]);
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn arrow_ipc_reads_in_the_file_and_stream_layouts() {
+        use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch};
+        use arrow_ipc::writer::{FileWriter, StreamWriter};
+        use std::sync::Arc;
+
+        let batch = RecordBatch::try_from_iter([
+            ("x", Arc::new(Float64Array::from(vec![1.5, 2.5, 3.5])) as ArrayRef),
+            ("ok", Arc::new(BooleanArray::from(vec![true, false, true])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let file = fixture("data.arrow", "");
+        let mut writer = FileWriter::try_new(std::fs::File::create(&file).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        let stream = fixture("stream.arrow", "");
+        let mut writer = StreamWriter::try_new(std::fs::File::create(&stream).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        // --skip and --limit reach across batches
+        let opts = InspectOptions {
+            show_summary: true,
+            skip: 2,
+            limit: Some(3),
+            ..InspectOptions::default()
+        };
+        let report = inspect_file(file.to_str().unwrap(), &opts).unwrap();
+        assert_eq!((report.file_type, report.rows), ("Arrow", 3));
+        let kinds: Vec<_> = report.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, [Some(ColumnType::Numeric), Some(ColumnType::Boolean)]);
+        let x = report.columns[0].summary.as_ref().unwrap();
+        assert_eq!((x.min, x.max), (Some(1.5), Some(3.5)));
+
+        let report = inspect_file(stream.to_str().unwrap(), &InspectOptions::default()).unwrap();
+        assert_eq!(report.rows, 3);
+    }
+
+    #[test]
+    fn column_order_and_likely_primary_keys() {
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let csv = "id,t,day,n\n10,1,2024-01-01,5\n11,1,2024-01-02,3\n12,2,2024-01-02,4\n";
+        let (report, _) = read_csv_text(csv, opts.clone());
+        let orders: Vec<_> = report.columns.iter().map(|c| c.summary.as_ref().unwrap().order).collect();
+        assert_eq!(orders, [Some("increasing"), Some("non-decreasing"), Some("non-decreasing"), Some("unordered")]);
+
+        let keys: Vec<&str> = report
+            .diagnostics()
+            .filter(|d| d.check == "primary-key")
+            .map(|d| d.column.as_deref().unwrap())
+            .collect();
+        assert_eq!(keys, ["id"]);
+
+        // halves merged in file order keep the order they make together
+        let mut merged = feed(&["1", "2"], &opts);
+        merged.merge(&feed(&["2", "3"], &opts));
+        assert_eq!(merged.number_order.as_str(), "non-decreasing");
+    }
+
+    #[test]
+    fn url_formats_come_from_the_path_alone() {
+        assert_eq!(file_extension("https://example.com/data/export.csv?token=a.b#top"), "csv");
+        assert_eq!(file_extension("http://example.com/daily.json.gz"), "json");
+        assert_eq!(file_extension("https://example.com/"), "");
+        assert_eq!(file_extension("https://example.com"), "");
+        assert!(is_url("https://example.com/a.csv") && !is_url("data/https.csv"));
+    }
+
+    // answers each request on a local port with a 200 and the body, or a 404
+    // for paths starting /missing
+    #[cfg(feature = "http")]
+    fn serve(body: &'static str) -> String {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = String::new();
+                let mut reader = BufReader::new(&stream);
+                reader.read_line(&mut request).unwrap();
+                while reader.read_line(&mut String::new()).unwrap() > 2 {}
+                let (status, body) = if request.contains(" /missing") {
+                    ("404 Not Found", "")
+                } else {
+                    ("200 OK", body)
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn urls_are_fetched_and_read_like_files() {
+        let server = serve("a,b\n1,x\n2,y\n");
+        let report = inspect_file(&format!("{}/export.csv?day=1", server), &InspectOptions::default()).unwrap();
+        assert_eq!((report.file_type, report.rows), ("CSV", 2));
+
+        let error = inspect_file(&format!("{}/missing.csv", server), &InspectOptions::default());
+        assert!(matches!(error, Err(InspectError::HttpStatus { ref status, .. }) if status.starts_with("404")), "{:?}", error);
+        let error = inspect_file(&format!("{}/export", server), &InspectOptions::default());
+        assert!(matches!(error, Err(InspectError::Usage(_))), "{:?}", error);
+    }
+
+    #[test]
+    fn the_pretty_summary_is_one_aligned_row_per_column() {
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("n,tag\n2,xy\n4,z\n,z\n", opts.clone());
+        let mut out = Vec::new();
+        print_summary_table(&mut out, &report.columns, &opts).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let expected = [
+            "  column  type         count  missing  missing%  unique  min  max  mean  stddev  mode  order",
+            "  n       integer      2      1        33.3      -       2    4    3     1.414   -     increasing",
+            "  tag     categorical  3      0        0.0       2       xy   z    -     -       z     -",
+        ];
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn zero_padded_codes_are_flagged_as_identifiers() {
+        let opts = InspectOptions::default();
+        let check = |values: &[&str]| {
+            let stats = column(values, &opts);
+            let finding = stats.diagnostics(values.len(), &opts).into_iter().find(|d| d.check == "zero-padded");
+            (stats.zero_padded_width(), finding.map(|d| d.severity))
+        };
+        // parsed as numbers, they would lose the zeros
+        assert_eq!(check(&["007", "012", "345"]), (Some(3), Some(Severity::Warning)));
+        assert_eq!(check(&["007", "A12"]), (None, None));
+        // mixed widths, or no leading zero at all, are just numbers
+        assert_eq!(check(&["007", "12"]), (None, None));
+        assert_eq!(check(&["123", "456"]), (None, None));
+    }
+
+    #[test]
+    fn the_explore_loop_answers_commands_from_one_report() {
+        let (report, _) = read_csv_text("n,\"home city\"\n1,Oslo\n2,Oslo\n3,Rome\n", InspectOptions::default());
+        let session = |commands: &str| {
+            let mut out = Vec::new();
+            explore(&report, &InspectOptions::default(), &mut commands.as_bytes(), &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let out = session("columns\ntop 'home city' 1\ntop n\nfrobnicate\nquit\ncolumns\n");
+        let expected = "type help for the list of commands\n\
+                        > \x20 n (numeric)\n  home city (categorical)\n\
+                        > \x20 Oslo: 2 (66.7%)\n\
+                        > n: value counts are only kept for non-numeric columns\n\
+                        > unknown command: frobnicate (type help)\n\
+                        > ";
+        assert_eq!(out, expected);
+        // end of input leaves too
+        assert!(session("top 1 x\n").ends_with("> invalid count: x\n> \n"));
+        assert_eq!(tokenize("top \"a b\" 3"), ["top", "a b", "3"]);
+    }
+
+    #[test]
+    fn max_uniques_caps_tracking_and_counts_the_rest() {
+        let opts = InspectOptions {
+            max_uniques: Some(2),
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let values = ["a", "b", "a", "c", "d", "b"];
+        let stats = column(&values, &opts);
+        assert_eq!(stats.uniques.len(), 2);
+        assert_eq!((stats.uniques["a"], stats.uniques["b"], stats.other_count), (2, 2, 2));
+
+        let summary = stats.summary(&opts);
+        assert_eq!((summary.unique, summary.unique_capped), (Some(2), true));
+        assert_eq!(unique_label(&summary, ""), "≥2");
+        let diagnostics = stats.diagnostics(values.len(), &opts);
+        let cardinality = diagnostics.iter().find(|d| d.check == "cardinality").unwrap();
+        assert!(cardinality.message.contains("capped by --max-uniques"), "{}", cardinality.message);
+
+        let stats = column(&values, &InspectOptions::default());
+        assert_eq!((stats.uniques.len(), stats.other_count), (4, 0));
+    }
+
+    #[test]
+    fn approx_distinct_estimates_within_a_few_standard_errors() {
+        let opts = InspectOptions {
+            approx_distinct: true,
+            ..InspectOptions::default()
+        };
+        // below APPROX_TRACKED_VALUES the count is still exact
+        let summary = column(&["a", "b", "a"], &opts).summary(&opts);
+        assert_eq!((summary.unique, summary.unique_error), (Some(2), None));
+
+        let names: Vec<String> = (0..60_000).map(|i| format!("user-{}", i % 30_000)).collect();
+        let values: Vec<&str> = names.iter().map(String::as_str).collect();
+        let stats = column(&values, &opts);
+        let estimate = stats.distinct_count();
+        assert!(estimate.abs_diff(30_000) < 1_000, "{}", estimate);
+
+        let summary = stats.summary(&opts);
+        let error = summary.unique_error.unwrap();
+        assert!(error > 0.005 && error < 0.01, "{}", error);
+        assert!(unique_label(&summary, "").starts_with('≈'));
+
+        // halves merge into the same sketch
+        let mut merged = feed(&values[..25_000], &opts);
+        merged.merge(&feed(&values[25_000..], &opts));
+        assert_eq!(merged.distinct_count(), estimate);
+    }
+
+    #[test]
+    fn input_is_transcoded_from_encoding_or_a_bom() {
+        let latin1: &[u8] = b"name\ncaf\xe9\nna\xefve\n";
+        let opts = InspectOptions {
+            encoding: Some("windows-1252".to_string()),
+            sample_rows: Some(5),
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(latin1, InputFormat::Csv, &opts).unwrap();
+        assert_eq!(report.sample.unwrap().rows, [["café"], ["naïve"]]);
+        // without it, invalid UTF-8 is an error rather than mangled text
+        assert!(inspect_reader(latin1, InputFormat::Csv, &InspectOptions::default()).is_err());
+
+        // a UTF-16 BOM is enough on its own
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend("name\nrésumé\n".encode_utf16().flat_map(u16::to_le_bytes));
+        let opts = InspectOptions {
+            sample_rows: Some(5),
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(&utf16[..], InputFormat::Csv, &opts).unwrap();
+        assert_eq!(report.columns[0].name, "name");
+        assert_eq!(report.sample.unwrap().rows, [["résumé"]]);
+
+        let unknown = InspectOptions {
+            encoding: Some("klingon".to_string()),
+            ..InspectOptions::default()
+        };
+        let error = inspect_reader(latin1, InputFormat::Csv, &unknown);
+        assert!(matches!(error, Err(InspectError::Usage(ref m)) if m == "unknown encoding: klingon"), "{:?}", error);
+    }
+
+    #[test]
+    fn categorical_summaries_show_the_extreme_values() {
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("word\npear\nfig\nBanana\nyam\nzucchini\nkiwi\n", opts);
+        let summary = report.columns[0].summary.as_ref().unwrap();
+        // by code point, so upper case sorts first
+        assert_eq!((summary.min_value.as_deref(), summary.max_value.as_deref()), (Some("Banana"), Some("zucchini")));
+        // the first one seen wins a tie in length
+        assert_eq!((summary.shortest.as_deref(), summary.longest.as_deref()), (Some("fig"), Some("zucchini")));
+    }
+
+    #[test]
+    fn toml_arrays_and_tables_of_tables_are_records() {
+        let read = |text: &str| inspect_reader(text.as_bytes(), InputFormat::Toml, &InspectOptions::default()).unwrap();
+
+        // the one list of records is looked through, past the settings beside it
+        let report = read("title = \"fleet\"\n[[server]]\nname = \"a\"\nport = 80\n[[server]]\nname = \"b\"\nport = 443\n");
+        assert_eq!((report.file_type, report.rows), ("TOML", 2));
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["name", "port"]);
+
+        let report = read("[server.alpha]\nport = 80\n[server.beta]\nport = 443\nip = \"10.0.0.1\"\n");
+        assert_eq!(report.rows, 2);
+        assert_eq!(report.columns[1].presence, Some(0.5));
+
+        // anything else is one record
+        let report = read("name = \"x\"\nport = 80\n");
+        assert_eq!((report.rows, report.columns.len()), (1, 2));
+    }
+
+    #[test]
+    fn explain_counts_how_each_value_parsed() {
+        let opts = InspectOptions {
+            explain: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("n\nabc\n1\n2.5\nyes\n2024-01-01\n3\n", opts);
+        let inference = report.columns[0].inference.as_ref().unwrap();
+        assert_eq!(
+            (inference.integers, inference.floats, inference.booleans, inference.datetimes, inference.strings),
+            (2, 1, 1, 1, 1)
+        );
+        assert_eq!(inference.upgraded_at, Some(2));
+        assert_eq!(
+            inference.rule,
+            "upgraded to numeric by the number on row 2 after 1 text value (left out of the stats)"
+        );
+        // what came after the upgrade and didn't parse
+        assert_eq!(inference.parse_failures, 2);
+    }
+
+    #[test]
+    fn sign_counts_and_stray_negatives() {
+        let opts = InspectOptions::default();
+        let mut values = vec!["0", "-4", "0"];
+        values.extend(["12"; 30]);
+        let stats = column(&values, &opts);
+        let summary = stats.summary(&opts);
+        assert_eq!((summary.negative, summary.zero, summary.positive), (Some(1), Some(2), Some(30)));
+
+        let diagnostics = stats.diagnostics(values.len(), &opts);
+        let negatives = diagnostics.iter().find(|d| d.check == "negatives").unwrap();
+        assert_eq!(
+            negatives.message,
+            "stray negative values: 1 of 33 (3.0%) in an otherwise non-negative column (row 2: -4)"
+        );
+
+        // plenty of negatives are just part of the data
+        let stats = column(&["-1", "2", "-3", "4"], &opts);
+        assert!(stats.diagnostics(4, &opts).iter().all(|d| d.check != "negatives"));
+    }
+
+    #[test]
+    fn tail_keeps_the_last_rows_in_a_ring() {
+        let opts = InspectOptions {
+            tail_rows: Some(2),
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader("n\n1\n2\n3\n4\n5\n".as_bytes(), InputFormat::Csv, &opts).unwrap();
+        assert_eq!(report.tail.as_ref().unwrap().rows, [["4"], ["5"]]);
+        let mut out = Vec::new();
+        print_report(&mut out, &report, &InspectOptions { quiet: true, ..opts.clone() }).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("Tail (rows 4-5 of 5):\n  n\n  4\n  5\n"));
+
+        // fewer rows than asked for
+        let report = inspect_reader("n\n1\n".as_bytes(), InputFormat::Csv, &opts).unwrap();
+        assert_eq!(report.tail.unwrap().rows, [["1"]]);
+    }
+
+    #[test]
+    fn checks_can_be_selected_or_disabled_by_name() {
+        let csv = "id,tag\n1,a\n1,a\n2,\n3,\n";
+        let checks = |opts: InspectOptions| {
+            let (report, _) = read_csv_text(csv, opts);
+            let mut checks: Vec<&'static str> = report.diagnostics().map(|d| d.check).collect();
+            checks.sort_unstable();
+            checks.dedup();
+            checks
+        };
+        assert_eq!(checks(InspectOptions::default()), ["constant", "duplicates", "missing"]);
+        let only = InspectOptions {
+            only_checks: vec!["missing".to_string()],
+            ..InspectOptions::default()
+        };
+        assert_eq!(checks(only), ["missing"]);
+        let disabled = InspectOptions {
+            disabled_checks: vec!["missing".to_string()],
+            ..InspectOptions::default()
+        };
+        assert_eq!(checks(disabled), ["constant", "duplicates"]);
+    }
+
+    #[test]
+    fn a_bom_and_nul_bytes_are_stripped_from_headers_and_cells() {
+        let csv = "\u{feff}id,na\0me\n1,x\0y\n2,z\n";
+        let opts = InspectOptions {
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text(csv, opts);
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name"]);
+        assert_eq!(report.columns[1].summary.as_ref().unwrap().min_value.as_deref(), Some("xy"));
+
+        let opts = InspectOptions {
+            columns: Some("id".to_string()),
+            ..InspectOptions::default()
+        };
+        assert_eq!(read_csv_text(csv, opts).0.columns.len(), 1);
+
+        let message = report.diagnostics().find(|d| d.check == "null-bytes").map(|d| d.message.as_str());
+        assert_eq!(
+            message,
+            Some("null bytes: 1 value contained NUL characters, which were removed (e.g. \"x\\0y\")")
+        );
+    }
+
+    #[test]
+    fn group_by_summarizes_each_group_in_value_order() {
+        let opts = InspectOptions {
+            group_by: Some("region".to_string()),
+            ..InspectOptions::default()
+        };
+        let csv = "region,price\nwest,10\neast,1\nwest,20\n,5\neast,3\n";
+        let (report, _) = read_csv_text(csv, opts.clone());
+        let grouped = report.groups.unwrap();
+        assert_eq!(grouped.column, "region");
+        let means: Vec<(&str, usize, Option<f64>)> = grouped
+            .groups
+            .iter()
+            .map(|g| (g.value.as_str(), g.rows, g.columns[0].summary.as_ref().unwrap().mean))
+            .collect();
+        // a missing key is a group of its own
+        assert_eq!(means, [("", 1, Some(5.0)), ("east", 2, Some(2.0)), ("west", 2, Some(15.0))]);
+
+        let ids: String = (0..=MAX_GROUPS).map(|i| format!("{},1\n", i)).collect();
+        let error = inspect_reader(format!("region,price\n{}", ids).as_bytes(), InputFormat::Csv, &opts);
+        assert!(matches!(error, Err(InspectError::TooManyGroups { limit: MAX_GROUPS, .. })), "{:?}", error);
+        let error = inspect_reader("a\n1\n".as_bytes(), InputFormat::Json, &opts);
+        assert!(matches!(error, Err(InspectError::Usage(_))), "{:?}", error);
+    }
+
+    #[test]
+    fn fingerprints_ignore_row_order_but_not_content() {
+        let opts = InspectOptions {
+            fingerprint: true,
+            ..InspectOptions::default()
+        };
+        let fingerprint = |csv: &str| {
+            let (report, _) = read_csv_text(csv, opts.clone());
+            report.columns.iter().map(|c| c.fingerprint.clone().unwrap()).collect::<Vec<_>>()
+        };
+        let original = fingerprint("a,b\n1,x\n2,y\n3,z\n");
+        assert_eq!(fingerprint("a,b\n3,z\n1,x\n2,y\n"), original);
+        let changed = fingerprint("a,b\n1,x\n2,y\n3,zz\n");
+        assert_eq!(changed[0], original[0]);
+        assert_ne!(changed[1], original[1]);
+        // a repeated value is a change too
+        assert_ne!(fingerprint("a,b\n1,x\n2,y\n3,z\n3,z\n")[0], original[0]);
+    }
+
+    #[test]
+    fn upgrades_to_numeric_are_counted_and_reported() {
+        let opts = InspectOptions {
+            explain: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("a,b,c\nn/a,x,1\n-,y,2\n5,z,3\n6,w,4\n", opts);
+        let upgrade = report.diagnostics().find(|d| d.check == "type-upgrade").unwrap();
+        assert_eq!(upgrade.column.as_deref(), Some("a"));
+        assert_eq!(
+            upgrade.message,
+            "upgraded to numeric on row 3: 2 earlier non-numeric values dropped from the stats (row 1: \"n/a\", row 2: \"-\")"
+        );
+
+        let mut out = Vec::new();
+        print_inference(&mut out, &report).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.ends_with("  1 of 3 columns upgraded from text to numeric mid-stream\n"), "{}", out);
+    }
+
+    #[test]
+    fn empty_and_header_only_input() {
+        let path = fixture("empty.csv", "");
+        let error = inspect_file(path.to_str().unwrap(), &InspectOptions::default()).unwrap_err();
+        assert!(matches!(error, InspectError::EmptyInput(_)), "{:?}", error);
+        assert!(error.to_string().ends_with("empty.csv: empty file, nothing to inspect"), "{}", error);
+        assert_eq!(error.exit_code(), 2);
+
+        let (report, _) = read_csv_text("id,name\n", InspectOptions::default());
+        assert_eq!((report.rows, report.columns.len()), (0, 2));
+        let no_rows = report.diagnostics.iter().find(|d| d.check == "no-rows").unwrap();
+        assert_eq!(no_rows.severity, Severity::Warning);
+        let mut out = Vec::new();
+        print_preamble(&mut out, &report).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("File type: CSV\nRows: 0 (no data rows)\n"));
+    }
+
+    #[test]
+    fn structured_identifiers_are_recognized_by_majority() {
+        let opts = InspectOptions::default();
+        let semantic = |values: &[&str]| column(values, &opts).semantic_type();
+        // more than 90% of the values have to match: one stray in ten is too many
+        let mut emails = vec!["unknown"];
+        emails.extend(["ada@example.com"; 9]);
+        assert_eq!(semantic(&emails), None);
+        emails.push("bob@example.org");
+        assert_eq!(semantic(&emails), Some(SemanticType::Email));
+
+        assert_eq!(semantic(&["https://example.com/a", "http://example.org"]), Some(SemanticType::Url));
+        assert_eq!(semantic(&["0e8f7a3c-5b9d-4c1e-8f2a-1b3c5d7e9f01"]), Some(SemanticType::Uuid));
+        assert_eq!(semantic(&["10.0.0.1", "::1", "2001:db8::8a2e:370:7334"]), Some(SemanticType::Ip));
+        assert_eq!(semantic(&["12345678901234567890"]), Some(SemanticType::LargeInteger));
+        assert_eq!(semantic(&["plain", "words"]), None);
+    }
+
+    #[test]
+    fn implausible_dates_fall_outside_the_date_range() {
+        let values = ["2024-03-01", "1900-01-01", "2024-03-02", "2099-12-31"];
+        let message = |opts: &InspectOptions| {
+            let stats = column(&values, opts);
+            let diagnostics = stats.diagnostics(values.len(), opts);
+            diagnostics.into_iter().find(|d| d.check == "date-range").map(|d| d.message)
+        };
+        // by default: from 1970 to a year from now
+        let found = message(&InspectOptions::default()).unwrap();
+        assert!(found.starts_with("implausible dates: 2 outside 1970-01-01 to "), "{}", found);
+        assert!(found.ends_with("(row 2: 1900-01-01, row 4: 2099-12-31)"), "{}", found);
+
+        let wide = InspectOptions {
+            date_min: parse_timestamp("1900-01-01"),
+            date_max: parse_timestamp("2100-01-01"),
+            ..InspectOptions::default()
+        };
+        assert_eq!(message(&wide), None);
+    }
+
+    #[test]
+    fn benford_passes_growth_series_and_fails_uniform_amounts() {
+        // a geometric series spreads its leading digits the way Benford expects;
+        // the flat column leads with each digit equally often, over 1 to 900
+        let mut csv = String::from("growth,flat,narrow\n");
+        for k in 0..300 {
+            let growth = 1.05f64.powi(k);
+            let flat = (k % 9 + 1) * 10i32.pow((k / 9 % 3) as u32);
+            let narrow = 100 + k % 50;
+            csv.push_str(&format!("{:.4},{},{}\n", growth, flat, narrow));
+        }
+        let opts = InspectOptions {
+            benford: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text(&csv, opts);
+
+        let growth = report.columns[0].benford.as_ref().unwrap();
+        assert_eq!(growth.values, 300);
+        assert!(growth.pass, "chi-square {}", growth.chi_square);
+        assert_near(growth.observed.iter().sum(), 1.0, "shares");
+        let flat = report.columns[1].benford.as_ref().unwrap();
+        assert!(!flat.pass, "chi-square {}", flat.chi_square);
+        // under two orders of magnitude: not a Benford candidate
+        assert!(report.columns[2].benford.is_none());
+    }
+
+    #[test]
+    fn json_fields_report_every_kind_of_value_they_held() {
+        let text = concat!(
+            "{\"id\": 1, \"code\": 10}\n",
+            "{\"id\": 2, \"code\": \"A7\"}\n",
+            "{\"id\": 3, \"code\": null}\n",
+            "{\"id\": 4, \"code\": 12}\n",
+            "{\"id\": 5}\n",
+        );
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let report = inspect_reader(text.as_bytes(), InputFormat::JsonLines, &opts).unwrap();
+        let column = |name: &str| report.columns.iter().find(|c| c.name == name).unwrap();
+        let id = column("id").json_kinds.unwrap();
+        assert_eq!((id.number, id.absent), (5, 0));
+        assert!(!column("id").diagnostics.as_ref().unwrap().iter().any(|d| d.check == "mixed-kinds"));
+
+        let code = column("code");
+        let kinds = code.json_kinds.unwrap();
+        assert_eq!((kinds.number, kinds.string, kinds.null, kinds.absent), (2, 1, 1, 1));
+        assert_near(kinds.presence(), 0.8, "presence");
+        let mixed = code.diagnostics.as_ref().unwrap().iter().find(|d| d.check == "mixed-kinds").unwrap();
+        assert_eq!(mixed.message, "mixed JSON value kinds: number 40.0%, string 20.0%, null 20.0%, absent 20.0%");
+    }
+
+    #[test]
+    fn random_sample_is_reproducible_from_its_seed_and_draws_past_the_head() {
+        let mut csv = String::from("n\n");
+        for n in 1..=1000 {
+            csv.push_str(&format!("{}\n", n));
+        }
+        let draw = |seed| {
+            let opts = InspectOptions {
+                random_sample: Some(5),
+                seed: Some(seed),
+                ..InspectOptions::default()
+            };
+            let (report, _) = read_csv_text(&csv, opts);
+            report.random_sample.unwrap()
+        };
+        let first = draw(7);
+        assert_eq!(first.seed, 7);
+        assert_eq!(first.row_numbers.len(), 5);
+        assert!(first.row_numbers.windows(2).all(|w| w[0] < w[1]), "{:?}", first.row_numbers);
+        // one column, so each row's only cell is its own row number
+        for (row, cells) in first.row_numbers.iter().zip(&first.sample.rows) {
+            assert_eq!(cells, &[row.to_string()]);
+        }
+        assert!(first.row_numbers.iter().any(|&row| row > 5), "{:?}", first.row_numbers);
+        assert_eq!(draw(7).row_numbers, first.row_numbers);
+        assert_ne!(draw(8).row_numbers, first.row_numbers);
+
+        // fewer rows than asked for: all of them
+        let opts = InspectOptions {
+            random_sample: Some(5),
+            seed: Some(7),
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("n\n1\n2\n", opts);
+        assert_eq!(report.random_sample.unwrap().row_numbers, [1, 2]);
+    }
+
+    #[test]
+    fn nested_json_objects_flatten_into_dotted_columns_down_to_max_depth() {
+        let text = concat!(
+            "[{\"user\": {\"name\": \"ann\", \"age\": 30, \"home\": {\"city\": \"Oslo\"}}, \"tags\": [\"a\", \"b\"]},",
+            " {\"user\": {\"name\": \"bo\", \"age\": 41, \"home\": {\"city\": \"Rome\"}}, \"tags\": []}]",
+        );
+        let names = |max_depth| {
+            let opts = InspectOptions {
+                max_depth,
+                ..InspectOptions::default()
+            };
+            let report = inspect_reader(text.as_bytes(), InputFormat::Json, &opts).unwrap();
+            let mut names: Vec<String> = report.columns.iter().map(|c| c.name.clone()).collect();
+            names.sort();
+            (names, report)
+        };
+
+        let (all, report) = names(None);
+        assert_eq!(all, ["tags", "tags.length", "user.age", "user.home.city", "user.name"]);
+        let age = report.columns.iter().find(|c| c.name == "user.age").unwrap();
+        assert_eq!(age.kind, Some(ColumnType::Numeric));
+        let length = report.columns.iter().find(|c| c.name == "tags.length").unwrap();
+        assert_eq!(length.kind, Some(ColumnType::Numeric));
+
+        // one level: `user.home` keeps its JSON text
+        assert_eq!(names(Some(1)).0, ["tags", "tags.length", "user.age", "user.home", "user.name"]);
+        assert_eq!(names(Some(0)).0, ["tags", "tags.length", "user"]);
+    }
+
+    #[test]
+    fn coerced_columns_take_the_given_type_over_inference() {
+        let coerce = |column: &str, kind| InspectOptions {
+            coerce: vec![(column.to_string(), kind)],
+            ..InspectOptions::default()
+        };
+        let mut csv = String::from("zip,amount,flag\n");
+        for i in 0..10 {
+            let amount = if i == 3 { "oops".to_string() } else { format!("{}.5", i) };
+            csv.push_str(&format!("{}0301,{},{}\n", i + 1, amount, if i % 2 == 0 { "yes" } else { "no" }));
+        }
+        let csv = csv.as_str();
+        let (report, _) = read_csv_text(csv, InspectOptions::default());
+        assert_eq!(report.columns[0].kind, Some(ColumnType::Numeric));
+
+        let opts = coerce("zip", CoercedType::Categorical);
+        let (report, _) = read_csv_text(csv, opts.clone());
+        assert_eq!(report.columns[0].kind, Some(ColumnType::Categorical));
+        assert!(check_coercions(&report, &opts).is_ok());
+
+        let opts = coerce("amount", CoercedType::Float);
+        let (report, _) = read_csv_text(csv, opts.clone());
+        assert_eq!((report.columns[1].kind, report.columns[1].numeric_type), (Some(ColumnType::Numeric), Some("float")));
+        // nine of ten parse: at the 90% default, so the bad value is only a parse failure
+        assert_eq!(report.stats[1].as_ref().unwrap().numeric_parse_failures, 1);
+        assert!(check_coercions(&report, &opts).is_ok());
+
+        let opts = coerce("flag", CoercedType::Numeric);
+        let (report, _) = read_csv_text(csv, opts.clone());
+        assert!(matches!(
+            check_coercions(&report, &opts),
+            Err(InspectError::CoercionFailed { failures: 10, values: 10, .. })
+        ));
+
+        let opts = coerce("nope", CoercedType::Boolean);
+        let (report, _) = read_csv_text(csv, opts.clone());
+        assert!(matches!(check_coercions(&report, &opts), Err(InspectError::UnknownColumn(c)) if c == "nope"));
+    }
+
+    #[test]
+    fn column_diagnostics_are_structured_and_tagged_with_their_column() {
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let stats = column(&["7", "", "", "", "7", "7"], &opts);
+        let diagnostics = diagnose_column(&stats, 6, &opts);
+        let checks: Vec<(&str, Severity)> = diagnostics.iter().map(|d| (d.check, d.severity)).collect();
+        assert_eq!(checks, [("missing", Severity::Warning), ("constant", Severity::Info)]);
+        assert!(diagnostics.iter().all(|d| d.column.as_deref() == Some("n") && CHECKS.contains(&d.check)));
+        assert_eq!(diagnostics[0].message, "missing values: 50%");
+
+        // the console text is rendered from the same values
+        let mut out = Vec::new();
+        print_warnings(&mut out, &diagnostics, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, "  ! warning: missing values: 50%\n  ! info: near-constant numeric column\n");
+    }
+
+    #[test]
+    fn a_categorical_column_with_one_value_is_constant() {
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let constant = |values: &[&str]| {
+            let stats = column(values, &opts);
+            let diagnostics = diagnose_column(&stats, values.len(), &opts);
+            diagnostics.into_iter().find(|d| d.check == "constant").map(|d| (d.severity, d.message))
+        };
+        assert_eq!(
+            constant(&["US", "US", "", "US"]),
+            Some((Severity::Warning, "constant column: every value is \"US\"".to_string()))
+        );
+        assert_eq!(constant(&["US", "FR", "US"]), None);
+        // one value is too little to call constant
+        assert_eq!(constant(&["US", "", ""]), None);
+    }
+
+    #[test]
+    fn fields_absent_from_most_records_are_sparse_not_missing() {
+        let mut text = String::new();
+        for id in 0..20 {
+            match id {
+                5 => text.push_str("{\"id\": 5, \"promo\": \"SPRING\", \"note\": null}\n"),
+                _ => text.push_str(&format!("{{\"id\": {}, \"note\": null}}\n", id)),
+            }
+        }
+        let sparse = |threshold| {
+            let opts = InspectOptions {
+                show_diagnose: true,
+                sparse_threshold: threshold,
+                ..InspectOptions::default()
+            };
+            let report = inspect_reader(text.as_bytes(), InputFormat::JsonLines, &opts).unwrap();
+            report
+                .columns
+                .iter()
+                .map(|c| {
+                    let sparse = c.diagnostics.as_ref().unwrap().iter().find(|d| d.check == "sparse");
+                    (c.name.clone(), c.presence, sparse.map(|d| d.message.clone()))
+                })
+                .collect::<Vec<_>>()
+        };
+        let columns = sparse(0.1);
+        let promo = columns.iter().find(|(name, ..)| name == "promo").unwrap();
+        assert_eq!(promo.1, Some(0.05));
+        assert_eq!(promo.2.as_deref(), Some("sparse field: present in 5.0% of records (absent from 19 of 20)"));
+        // null on every record is still present on every record
+        let note = columns.iter().find(|(name, ..)| name == "note").unwrap();
+        assert_eq!((note.1, note.2.as_deref()), (Some(1.0), None));
+
+        assert!(sparse(0.01).iter().all(|(_, _, sparse)| sparse.is_none()));
+    }
+
+    #[test]
+    fn dirty_rows_count_records_with_any_unparsable_numeric_value() {
+        let dirty = |csv: &str| {
+            let (report, _) = read_csv_text(csv, InspectOptions::default());
+            report.diagnostics.iter().find(|d| d.check == "dirty-rows").map(|d| d.message.clone())
+        };
+        // both bad values in one row: droppable
+        let bunched = "a,b,tag\n1,2,x\n3,4,y\nn/a,?,z\n5,6,w\n7,8,v\n";
+        assert_eq!(
+            dirty(bunched).as_deref(),
+            Some("dirty rows: 1 (20.0%) with a value that doesn't parse in a numeric column, 2 such values in all (row 3)")
+        );
+        let spread = "a,b,tag\n1,2,x\nn/a,4,y\n3,?,z\n5,6,w\n7,8,v\n";
+        assert_eq!(
+            dirty(spread).as_deref(),
+            Some("dirty rows: 2 (40.0%) with a value that doesn't parse in a numeric column, 2 such values in all (rows 2, 3)")
+        );
+        // text in a text column doesn't make a row dirty
+        assert_eq!(dirty("a,tag\n1,x\n2,y\n"), None);
+    }
+
+    #[test]
+    fn a_comma_decimal_separator_reads_european_numbers() {
+        let opts = InspectOptions {
+            delimiter: Some(b';'),
+            decimal_separator: ',',
+            show_summary: true,
+            ..InspectOptions::default()
+        };
+        let (report, _) = read_csv_text("price;qty\n1.234,56;3\n-0,5;1.000\n12;7\n", opts.clone());
+        assert_eq!(report.columns[0].kind, Some(ColumnType::Numeric));
+        let stats = report.stats[0].as_ref().unwrap();
+        assert_eq!((stats.min, stats.max), (Some(-0.5), Some(1234.56)));
+        let qty = report.stats[1].as_ref().unwrap();
+        assert_eq!((qty.min, qty.max), (Some(3.0), Some(1000.0)));
+
+        // a comma is no longer a thousands separator, and lists stay text
+        let stats = column(&["2", "1,234.56"], &opts);
+        assert_eq!(stats.numeric_parse_failures, 1);
+        assert_eq!(normalize_number("1.2.3", &opts), None);
+        // the default leaves European numbers as text
+        assert_eq!(column(&["1.234,56", "0,5"], &InspectOptions::default()).kind, ColumnType::Categorical);
+    }
+
+    #[test]
+    fn the_json_schema_describes_types_nullability_and_small_enums() {
+        let csv = "id,price,status,name,day\n\
+                   1,9.5,open,ann,2024-01-02\n\
+                   2,,closed,bo,2024-01-03\n\
+                   3,4.25,open,cy,2024-01-04\n\
+                   4,1.0,,di,2024-01-05\n\
+                   5,2.0,closed,ed,2024-01-06\n";
+        let (report, _) = read_csv_text(csv, InspectOptions::default());
+        assert_eq!(
+            report.json_schema("orders.csv"),
+            serde_json::json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "title": "orders.csv",
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer"},
+                    "price": {"type": ["number", "null"]},
+                    "status": {"type": ["string", "null"], "enum": [null, "closed", "open"]},
+                    "name": {"type": "string"},
+                    "day": {"type": "string", "format": "date"},
+                },
+                "required": ["id", "price", "status", "name", "day"],
+                "additionalProperties": false,
+            })
+        );
+    }
+
+    #[test]
+    fn mostly_zero_numeric_columns_are_zero_inflated() {
+        let opts = InspectOptions {
+            show_diagnose: true,
+            ..InspectOptions::default()
+        };
+        let inflated = |values: &[&str], opts: &InspectOptions| {
+            let stats = column(values, opts);
+            assert_eq!(stats.kind, ColumnType::Numeric);
+            let diagnostics = diagnose_column(&stats, values.len(), opts);
+            diagnostics.into_iter().find(|d| d.check == "zero-inflated").map(|d| d.message)
+        };
+        let mut values = vec!["3.5"];
+        values.extend(["0"; 19]);
+        assert_eq!(
+            inflated(&values, &opts).as_deref(),
+            Some("zero-inflated: 95.0% of the values are 0, so mean and stddev say little (maybe a sparse or indicator field)")
+        );
+        // missing values don't count toward the share
+        values.extend([""; 20]);
+        assert!(inflated(&values, &opts).is_some());
+        assert_eq!(inflated(&values, &InspectOptions { zero_threshold: 0.96, ..opts.clone() }), None);
+        // all zeros is constant rather than zero-inflated
+        assert_eq!(inflated(&["0.0", "0", "0"], &opts), None);
+    }
+
+    #[test]
+    fn validate_rules_report_offending_values_as_errors() {
+        let opts = InspectOptions {
+            validate: vec![("sku".to_string(), Regex::new(r"^[A-Z]{3}-\d{4}$").unwrap())],
+            ..InspectOptions::default()
+        };
+        let csv = "sku,qty\nABC-1234,1\nabc-1234,2\n,3\nXYZ-0001,4\nXYZ-01,5\n";
+        let (report, _) = read_csv_text(csv, opts.clone());
+        let sku = report.columns[0].diagnostics.as_ref().unwrap();
+        let failed = sku.iter().find(|d| d.check == "validate").unwrap();
+        assert_eq!(failed.severity, Severity::Error);
+        // the empty cell is missing, not a failure
+        assert_eq!(
+            failed.message,
+            r#"validation failed: 2 of 4 values (50.0%) don't match ^[A-Z]{3}-\d{4}$ (row 2: "abc-1234", row 5: "XYZ-01")"#
+        );
+        assert!(!report.columns[1].diagnostics.as_ref().unwrap().iter().any(|d| d.check == "validate"));
+
+        let path = fixture("validate.csv", csv);
+        let opts = InspectOptions {
+            validate: vec![("code".to_string(), Regex::new("^x$").unwrap())],
+            ..InspectOptions::default()
+        };
+        let error = inspect_file(path.to_str().unwrap(), &opts);
+        assert!(matches!(error, Err(InspectError::UnknownColumn(c)) if c == "code"));
+    }
+}
@@ -1,418 +1,2168 @@
 use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Write};
 use std::path::Path;
-use std::collections::HashSet;
-use csv::Reader;
+use std::time::{Duration, Instant};
 
-fn print_help() {
-    println!(
-        "datainspect - CLI Data Inspection tool
+use indicatif::HumanBytes;
+use regex::Regex;
+use serde::Serialize;
+
+use datainspect::{
+    CHECKS, CURRENCY_SYMBOLS, CoercedType, ColumnType, Diagnostic, InputFormat, InspectError,
+    InspectOptions, MemoryEstimate, OutlierMethod, Report, RowCount, Severity, count_rows,
+    emit_report, estimate_memory, explore, inspect_file, parse_timestamp, print_preamble,
+    write_json,
+};
+
+// also the source of the flag descriptions in the completion scripts
+const HELP: &str = "datainspect - CLI Data Inspection tool
 
 USAGE: 
-    datainspect <file> [options]
+    datainspect <file>... [options]
+    datainspect completions <bash|zsh|fish>
 
 OPTIONS:
-  --summary        Show per-column statistical summary
-  --types          Show inferred column types
-  --help           Show this help message
+  --summary              Show per-column statistical summary
+  --percentiles          Include median and quartiles in the summary
+  --pretty               Print the summary as an aligned table (default on a terminal)
+  --plain                Print the summary as one line per column (default when piped)
+  --top <n>              List the n most frequent values of categorical columns
+  --group-by <column>    Also summarize the other columns for each value of this one (CSV/TSV,
+                         at most 100 groups)
+  --max-uniques <n>      Cap the distinct values tracked per column (rest count as others)
+  --approx-distinct      Estimate categorical cardinality in constant memory (HyperLogLog)
+  --histogram            Show a text histogram for each numeric column
+  --bins <n>             Number of histogram bins (default 10, implies --histogram)
+  --correlate            Show pairwise Pearson correlations between numeric columns
+  --sample <n>           Print the first n rows as an aligned table
+  --tail <n>             Print the last n rows as an aligned table (also on a pipe)
+  --random-sample <n>    Print n rows drawn uniformly from the whole file (reservoir sampling)
+  --seed <n>             Seed for --random-sample, to draw the same rows again
+  --types                Show inferred column types
+  --explain              Show how each column's type was inferred: what its values parse as,
+                         how many failed, and the rule that decided
+  --diagnose             Show the data quality report
+  --max-length <n>       Flag categorical columns with values longer than n characters
+  --date-min <date>      Flag datetime values before this date (default 1970-01-01)
+  --date-max <date>      Flag datetime values after this date (default a year from today)
+  --range-min <x>        Flag numeric values below x
+  --range-max <x>        Flag numeric values above x
+  --only-check <names>   Only run these diagnostic checks (comma-separated, repeatable)
+  --disable-check <names>
+                         Skip these diagnostic checks: duplicates, ragged, missing, cardinality,
+                         whitespace, max-length, constant, primary-key, zero-padded, mixed-type,
+                         type-upgrade, negatives, outliers, null-bytes, no-rows, range,
+                         date-range, benford, mixed-kinds, sparse, dirty-rows,
+                         zero-inflated, validate, large-integers
+  --fail-on <level>      Exit nonzero if a diagnostic at or above info, warning or error fires
+  --junit <path>         Also write the diagnostics as a JUnit XML report, one test case per column
+  --interactive          Load one file, then explore it with commands (summary, top, ...)
+  --compare-schema       Compare column names, order and types across the given files
+  --dry-run              Sample the first rows of CSV, TSV or JSON Lines input and estimate the
+                         memory a full run would take, instead of inspecting it
+  --count-only           Print just the row and column counts; CSV and TSV records are split
+                         (quotes respected) without computing any stats
+  --overview             One line per file: format, rows, columns, quality score and
+                         diagnostic counts (ok when there are none)
+  --fingerprint          Print an order-independent hash of each column's values
+  --benford              Test the leading digits of numeric columns against Benford's law
+                         (chi-square, for 100+ positive values spanning 100x or more)
+  --diff <baseline>      Compare per-column statistics of the file against a baseline file
+  --diff-tolerance <f>   Relative change (missing share: absolute) flagged by --diff (default 0.05)
+  --sql <table>          Print a CREATE TABLE statement for the file instead of the report
+  --json-schema          Print a draft-07 JSON Schema for the file's records instead of the report:
+                         column types, null where values were missing, enums of few values
+  --json                 Emit the full report as a single JSON object (indented with --pretty)
+  --json-layout <how>    With several files: lines (default; one object per line, each with its
+                         file name) or array (a single JSON array)
+  --output <path>        Write the report to this file instead of stdout
+  --config <path>        Read default options from this TOML file (default: ./datainspect.toml)
+  --color <when>         Colorize diagnostics: auto (default), always or never
+  --timing               Print elapsed time, rows processed and rows/s and bytes/s throughput
+                         to stderr after the report
+  --no-progress          Never show the progress bar for large files
+  --quiet                Skip the file/row/column preamble and print only the requested sections
+  --infer-sample <n>     Decide each column's type from its first n values instead of the first one
+  --infer-threshold <f>  Share of sampled values that must be numeric for a numeric column (default 0.9)
+  --coerce <col>=<type>  Force a column's type instead of inferring it: numeric, integer, float,
+                         categorical, boolean or datetime (comma-separated, repeatable); fails
+                         when less than --infer-threshold of a numeric one's values parse
+  --validate <col>=<re>  Check that every value of a column matches a regex, e.g.
+                         sku='^[A-Z]{3}-\\d{4}$' (repeatable); failures are errors
+  --outlier-sigma <f>    z-score at or above which values count as outliers (default 5)
+  --outlier-method <m>   Outlier rule: zscore (default) or iqr (Tukey fences, robust to skew)
+  --missing-threshold <f>
+                         Share of missing values above which a column is flagged (default 0.05)
+  --cardinality-threshold <f>
+                         Share of unique values above which a categorical column is flagged
+                         as an identifier (default 0.95)
+  --sparse-threshold <f> Share of JSON/YAML/TOML records below which a field that only some of
+                         them have is flagged as sparse (default 0.1)
+  --zero-threshold <f>   Share of zeros above which a numeric column is flagged as
+                         zero-inflated (default 0.9)
+  --limit <n>            Only inspect the first n rows
+  --skip <n>             Pass over the first n rows; with --limit, inspect a window of rows
+  --threads <n>          Inspect a plain CSV/TSV file in n chunks in parallel (default 1)
+  --columns <list>       Only inspect these columns (comma-separated names or 0-based indices)
+  --exclude-columns <list>
+                         Skip these columns (applied after --columns)
+  --clean-numbers        Accept numbers written like 1,234.56, $1,200 or 45% (as 0.45)
+  --decimal-separator <c>
+                         Decimal separator of numbers: . (default) or , for 1.234,56, which
+                         also makes . the thousands separator (pair it with --delimiter ';')
+  --currency-symbols <s> Symbols --clean-numbers strips around numbers, as one string
+                         (default $€£¥)
+  --thousands-separators <s>
+                         Characters accepted between digit groups, as one string, e.g. \"'\"
+                         for 1'234 (default , or . with --decimal-separator ,)
+  --na-values <list>     Extra comma-separated values that count as missing (e.g. NA,-999)
+  --no-header            CSV has no header row; columns are named col_0, col_1, ...
+  --strict               Fail on CSV rows whose field count differs from the header's
+                         (by default they are a diagnostic, with absent fields as missing)
+  --delimiter <c>        Field delimiter for CSV input (use \\t for tab)
+  --quote <c>            Quote character for CSV input (default \")
+  --escape <c>           Character escaping quotes inside quoted CSV fields, e.g. \\ (default:
+                         quotes are escaped by doubling them)
+  --no-quoting           Treat quotes in CSV input as ordinary characters
+  --format <fmt>         Read input as csv, tsv, json, jsonl, yaml or toml regardless of its extension
+  --fixed-width <spec>   Read input as fixed-width text: name:start:length fields (0-based
+                         character offsets), comma-separated, e.g. name:0:10,age:10:3
+  --no-trim              Keep the padding around --fixed-width fields
+  --encoding <name>      Character encoding of the input, e.g. windows-1252 or shift_jis
+                         (default UTF-8, or what a byte order mark says)
+  --max-depth <n>        Flatten nested JSON/YAML/TOML objects into dotted columns (user.name)
+                         down to n levels (default: all; 0 keeps objects as JSON text)
+  --sheet <name>         Worksheet to inspect in .xlsx input (default: the first one)
+  -h, --help             Show this help message
+  -V, --version          Show version information
 
 SUPPORTED FILES:
   .csv
+  .tsv
   .json
+  .jsonl / .ndjson
+  .yaml / .yml
+  .toml
+  fixed-width text (with --fixed-width, any extension)
+  any of the above with a .gz suffix (except .parquet)
+  .parquet         (requires the `parquet` cargo feature)
+  .arrow / .feather  (requires the `arrow` cargo feature)
+  .xlsx            (requires the `xlsx` cargo feature)
 
 EXAMPLES:
   datainspect data.csv --summary
   datainspect data.csv --types
-  datainspect data.json --types"
-    );
+  datainspect data.csv --delimiter ';' --summary
+  datainspect data.json --types
+  datainspect exports/*.csv --compare-schema
+  source <(datainspect completions bash)";
+
+fn print_help() {
+    println!("{}", HELP);
 }
 
 fn main() {
-    // skip program name
-    let args: Vec<String> = env::args().skip(1).collect();
+    match run() {
+        Ok(()) => {}
+        // the reader went away (e.g. `| head`); nothing left to report to
+        Err(InspectError::Output { source, .. }) if source.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(err.exit_code());
+        }
+    }
+}
 
-    if args.iter().any(|a| a == "--help") {
+fn run() -> Result<(), InspectError> {
+    // skip program name; --flag=value is the same as --flag value
+    let mut args: Vec<String> = env::args()
+        .skip(1)
+        .flat_map(|arg| match arg.split_once('=') {
+            Some((flag, value)) if VALUE_FLAGS.contains(&flag) => vec![flag.to_string(), value.to_string()],
+            _ => vec![arg],
+        })
+        .collect();
+
+    if args.iter().any(|a| a == "--help" || a == "-h") {
         print_help();
-        return;
+        return Ok(());
+    }
+
+    if args.iter().any(|a| a == "--version" || a == "-V") {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    if args.first().is_some_and(|a| a == "completions") {
+        let script = match args.get(1).map(String::as_str) {
+            Some("bash") => bash_completions(),
+            Some("zsh") => zsh_completions(),
+            Some("fish") => fish_completions(),
+            Some(other) => {
+                return Err(InspectError::Usage(format!(
+                    "completions: unknown shell {} (expected bash, zsh or fish)",
+                    other
+                )));
+            }
+            None => return Err(InspectError::Usage("completions needs a shell: bash, zsh or fish".to_string())),
+        };
+        print!("{}", script);
+        return Ok(());
     }
+    check_args(&args)?;
+
+    // defaults from the config file go after the command line, so flag_value
+    // sees the explicit flags first
+    let defaults = config_args(&args)?;
+    args.extend(defaults);
 
     // flag
-    let show_types = args.iter().any(|a| a == "--types");
-    let show_summary = args.iter().any(|a| a == "--summary");
-    let show_diagnose = args.iter().any(|a| a == "--diagnose");
+    let show_percentiles = args.iter().any(|a| a == "--percentiles");
+    let top: Option<usize> = parse_flag(&args, "--top")?;
+    let mut opts = InspectOptions {
+        show_types: args.iter().any(|a| a == "--types"),
+        show_summary: show_percentiles
+            || top.is_some()
+            || args.iter().any(|a| a == "--summary"),
+        show_percentiles,
+        show_diagnose: args.iter().any(|a| a == "--diagnose"),
+        json: args.iter().any(|a| a == "--json"),
+        delimiter: None,
+        quote: None,
+        escape: None,
+        no_quoting: args.iter().any(|a| a == "--no-quoting"),
+        fixed_width: flag_value(&args, "--fixed-width").map(String::from),
+        no_trim: args.iter().any(|a| a == "--no-trim"),
+        top,
+        max_uniques: parse_flag(&args, "--max-uniques")?,
+        columns: flag_value(&args, "--columns").map(str::to_string),
+        exclude_columns: flag_value(&args, "--exclude-columns").map(str::to_string),
+        infer_sample: parse_flag(&args, "--infer-sample")?,
+        infer_threshold: parse_flag(&args, "--infer-threshold")?.unwrap_or(0.9),
+        outlier_sigma: parse_flag(&args, "--outlier-sigma")?.unwrap_or(5.0),
+        missing_threshold: parse_flag(&args, "--missing-threshold")?.unwrap_or(0.05),
+        cardinality_threshold: parse_flag(&args, "--cardinality-threshold")?.unwrap_or(0.95),
+        sparse_threshold: parse_flag(&args, "--sparse-threshold")?.unwrap_or(0.1),
+        zero_threshold: parse_flag(&args, "--zero-threshold")?.unwrap_or(0.9),
+        limit: parse_flag(&args, "--limit")?,
+        skip: parse_flag(&args, "--skip")?.unwrap_or(0),
+        histogram_bins: None,
+        no_header: args.iter().any(|a| a == "--no-header"),
+        correlate: args.iter().any(|a| a == "--correlate"),
+        fail_on: None,
+        sheet: flag_value(&args, "--sheet").map(str::to_string),
+        quiet: args.iter().any(|a| a == "--quiet"),
+        color: false,
+        max_length: parse_flag(&args, "--max-length")?,
+        no_progress: args.iter().any(|a| a == "--no-progress"),
+        compare_schema: args.iter().any(|a| a == "--compare-schema"),
+        sample_rows: parse_flag(&args, "--sample")?,
+        tail_rows: parse_flag(&args, "--tail")?,
+        max_depth: parse_flag(&args, "--max-depth")?,
+        random_sample: parse_flag(&args, "--random-sample")?,
+        seed: parse_flag(&args, "--seed")?,
+        outlier_method: OutlierMethod::ZScore,
+        na_values: flag_value(&args, "--na-values")
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|na| !na.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        output: flag_value(&args, "--output").map(str::to_string),
+        clean_numbers: args.iter().any(|a| a == "--clean-numbers"),
+        decimal_separator: match flag_value(&args, "--decimal-separator") {
+            None | Some(".") => '.',
+            Some(",") => ',',
+            Some(other) => {
+                return Err(InspectError::Usage(format!(
+                    "invalid --decimal-separator: {} (expected . or ,)",
+                    other
+                )));
+            }
+        },
+        // whole strings, not comma-separated lists: `,` is a separator itself
+        currency_symbols: flag_value(&args, "--currency-symbols")
+            .map_or_else(|| CURRENCY_SYMBOLS.to_vec(), |symbols| symbols.chars().collect()),
+        thousands_separators: flag_value(&args, "--thousands-separators").map(|separators| separators.chars().collect()),
+        format: None,
+        pretty: false,
+        approx_distinct: args.iter().any(|a| a == "--approx-distinct"),
+        threads: parse_flag(&args, "--threads")?.unwrap_or(1),
+        strict: args.iter().any(|a| a == "--strict"),
+        encoding: flag_value(&args, "--encoding").map(str::to_string),
+        explain: args.iter().any(|a| a == "--explain"),
+        only_checks: check_names(&args, "--only-check")?,
+        disabled_checks: check_names(&args, "--disable-check")?,
+        group_by: flag_value(&args, "--group-by").map(str::to_string),
+        fingerprint: args.iter().any(|a| a == "--fingerprint"),
+        benford: args.iter().any(|a| a == "--benford"),
+        json_pretty: false,
+        date_min: None,
+        date_max: None,
+        range_min: parse_flag(&args, "--range-min")?,
+        range_max: parse_flag(&args, "--range-max")?,
+        coerce: Vec::new(),
+        validate: Vec::new(),
+    };
 
-    // positional arguments
-    let positional: Vec<&String> = args
-        .iter()
-        .filter(|a| !a.starts_with("--"))
-        .collect();
+    for spec in flag_values(&args, "--coerce") {
+        let coerced = spec.split_once('=').and_then(|(column, kind)| {
+            let column = column.trim();
+            let kind = CoercedType::parse(kind.trim())?;
+            (!column.is_empty()).then(|| (column.to_string(), kind))
+        });
+        let Some((column, kind)) = coerced else {
+            let types: Vec<&str> = CoercedType::ALL.iter().map(|t| t.as_str()).collect();
+            return Err(InspectError::Usage(format!(
+                "invalid --coerce: {} (expected <column>=<type>, with type one of {})",
+                spec,
+                types.join(", ")
+            )));
+        };
+        // the last one given for a column wins
+        opts.coerce.retain(|(c, _)| *c != column);
+        opts.coerce.push((column, kind));
+    }
+
+    // not split on commas like other lists: patterns have them (`\d{3,4}`)
+    let validate = args.windows(2).filter(|pair| pair[0] == "--validate").map(|pair| pair[1].as_str());
+    for spec in validate {
+        let Some((column, pattern)) = spec.split_once('=').filter(|(column, _)| !column.trim().is_empty()) else {
+            return Err(InspectError::Usage(format!(
+                "invalid --validate: {} (expected <column>=<regex>)",
+                spec
+            )));
+        };
+        let column = column.trim().to_string();
+        let pattern = Regex::new(pattern).map_err(|err| {
+            InspectError::Usage(format!("invalid --validate pattern for {}: {}", column, err))
+        })?;
+        // the last one given for a column wins
+        opts.validate.retain(|(c, _)| *c != column);
+        opts.validate.push((column, pattern));
+    }
+
+    // a separator that could be part of the number would make it ambiguous
+    let clashes = |c: &char| c.is_ascii_digit() || matches!(c, '-' | '+' | '%') || *c == opts.decimal_separator;
+    if let Some(c) = opts.currency_symbols.iter().find(|c| clashes(c) || c.is_whitespace()) {
+        return Err(InspectError::Usage(format!("invalid --currency-symbols: {:?} can't be a currency symbol", c)));
+    }
+    if let Some(c) = opts.thousands_separators.iter().flatten().find(|c| clashes(c) || opts.currency_symbols.contains(c)) {
+        return Err(InspectError::Usage(format!(
+            "invalid --thousands-separators: {:?} can't be a thousands separator",
+            c
+        )));
+    }
+    if opts.thousands_separators.as_ref().is_some_and(Vec::is_empty) {
+        return Err(InspectError::Usage("--thousands-separators needs at least one character".to_string()));
+    }
+
+    opts.outlier_method = match flag_value(&args, "--outlier-method").unwrap_or("zscore") {
+        "zscore" => OutlierMethod::ZScore,
+        "iqr" => OutlierMethod::Iqr,
+        other => {
+            return Err(InspectError::Usage(format!(
+                "invalid --outlier-method: {} (expected zscore or iqr)",
+                other
+            )));
+        }
+    };
+
+    // NO_COLOR (https://no-color.org) only applies to auto, so an explicit
+    // --color always still wins
+    opts.color = match flag_value(&args, "--color").unwrap_or("auto") {
+        "always" => true,
+        "never" => false,
+        "auto" => {
+            env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && opts.output.is_none()
+                && io::stdout().is_terminal()
+        }
+        other => {
+            return Err(InspectError::Usage(format!(
+                "invalid --color mode: {} (expected auto, always or never)",
+                other
+            )));
+        }
+    };
+
+    // the table is for reading; piped or written to a file, the summary keeps
+    // the one-line-per-column format unless --pretty asks otherwise
+    let pretty = args.iter().any(|a| a == "--pretty");
+    let plain = args.iter().any(|a| a == "--plain");
+    if pretty && plain {
+        return Err(InspectError::Usage(
+            "--pretty and --plain cannot be used together".to_string(),
+        ));
+    }
+    opts.pretty = pretty || (!plain && opts.output.is_none() && io::stdout().is_terminal());
+    // JSON for machines stays compact unless indenting is asked for
+    opts.json_pretty = pretty;
+
+    let json_array = match flag_value(&args, "--json-layout") {
+        None | Some("lines") => false,
+        Some("array") => true,
+        Some(other) => {
+            return Err(InspectError::Usage(format!(
+                "invalid --json-layout: {} (expected lines or array)",
+                other
+            )));
+        }
+    };
+    if json_array && !opts.json {
+        return Err(InspectError::Usage("--json-layout only applies with --json".to_string()));
+    }
+
+    let bins: Option<usize> = parse_flag(&args, "--bins")?;
+    if bins.is_some() || args.iter().any(|a| a == "--histogram") {
+        let bins = bins.unwrap_or(10);
+        if bins == 0 {
+            return Err(InspectError::Usage("--bins must be at least 1".to_string()));
+        }
+        opts.histogram_bins = Some(bins);
+    }
+
+    if opts.threads == 0 {
+        return Err(InspectError::Usage("--threads must be at least 1".to_string()));
+    }
+
+    if !opts.outlier_sigma.is_finite() || opts.outlier_sigma <= 0.0 {
+        return Err(InspectError::Usage(format!(
+            "--outlier-sigma must be positive, got {}",
+            opts.outlier_sigma
+        )));
+    }
+
+    if !(opts.infer_threshold > 0.0 && opts.infer_threshold <= 1.0) {
+        return Err(InspectError::Usage(format!(
+            "--infer-threshold must be in (0, 1], got {}",
+            opts.infer_threshold
+        )));
+    }
+
+    for (flag, value) in [
+        ("--missing-threshold", opts.missing_threshold),
+        ("--cardinality-threshold", opts.cardinality_threshold),
+        ("--sparse-threshold", opts.sparse_threshold),
+        ("--zero-threshold", opts.zero_threshold),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(InspectError::Usage(format!(
+                "{} must be in [0, 1], got {}",
+                flag, value
+            )));
+        }
+    }
+
+    for (flag, bound) in [("--date-min", &mut opts.date_min), ("--date-max", &mut opts.date_max)] {
+        if let Some(value) = flag_value(&args, flag) {
+            *bound = Some(parse_timestamp(value).ok_or_else(|| {
+                InspectError::Usage(format!(
+                    "invalid {}: {} (expected a date like 2024-01-31 or 2024-01-31T12:00:00)",
+                    flag, value
+                ))
+            })?);
+        }
+    }
+    if let (Some(min), Some(max)) = (opts.date_min, opts.date_max)
+        && min > max
+    {
+        return Err(InspectError::Usage("--date-min is after --date-max".to_string()));
+    }
+    if let (Some(min), Some(max)) = (opts.range_min, opts.range_max)
+        && min > max
+    {
+        return Err(InspectError::Usage(format!(
+            "--range-min {} is above --range-max {}",
+            min, max
+        )));
+    }
+
+    if let Some(value) = flag_value(&args, "--fail-on") {
+        let level = Severity::parse(value).ok_or_else(|| {
+            InspectError::Usage(format!(
+                "invalid --fail-on level: {} (expected info, warning or error)",
+                value
+            ))
+        })?;
+        opts.fail_on = Some(level);
+    }
+
+    if let Some(value) = flag_value(&args, "--format") {
+        let format = InputFormat::from_extension(value).ok_or_else(|| {
+            InspectError::Usage(format!(
+                "invalid --format: {} (expected csv, tsv, json, jsonl, yaml or toml)",
+                value
+            ))
+        })?;
+        opts.format = Some(format);
+    }
+    if opts.fixed_width.is_some() && opts.format.is_some() {
+        return Err(InspectError::Usage(
+            "--fixed-width and --format can't be combined".to_string(),
+        ));
+    }
+    if opts.no_trim && opts.fixed_width.is_none() {
+        return Err(InspectError::Usage("--no-trim only applies to --fixed-width".to_string()));
+    }
+
+    if let Some(value) = flag_value(&args, "--delimiter") {
+        let delimiter = parse_delimiter(value).ok_or_else(|| {
+            InspectError::Usage(format!(
+                "invalid delimiter: {} (expected a single character or \\t)",
+                value
+            ))
+        })?;
+        opts.delimiter = Some(delimiter);
+    }
+
+    for (flag, dialect) in [("--quote", &mut opts.quote), ("--escape", &mut opts.escape)] {
+        if let Some(value) = flag_value(&args, flag) {
+            *dialect = Some(parse_char(value).ok_or_else(|| {
+                InspectError::Usage(format!("invalid {}: {} (expected a single character)", flag, value))
+            })?);
+        }
+    }
+    if opts.no_quoting && (opts.quote.is_some() || opts.escape.is_some()) {
+        return Err(InspectError::Usage(
+            "--no-quoting can't be combined with --quote or --escape".to_string(),
+        ));
+    }
+
+    // positional arguments, skipping the values of flags that take one
+    let mut positional: Vec<&String> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg);
+        }
+    }
 
     if positional.is_empty() {
-        eprintln!("Usage: datainspect [--types] <file>");
-        std::process::exit(1);
+        return Err(InspectError::Usage(
+            "no input file given\nusage: datainspect <file> [options]".to_string(),
+        ));
     }
 
-    let filename = positional.last().unwrap();
+    if args.iter().any(|a| a == "--interactive") {
+        if positional.len() != 1 || opts.json || opts.compare_schema || opts.output.is_some() {
+            return Err(InspectError::Usage(
+                "--interactive takes a single file and no --json, --compare-schema or --output"
+                    .to_string(),
+            ));
+        }
+        // every check runs up front, so `diagnose` has the outlier pass to show
+        opts.show_diagnose = true;
+        let report = inspect_file(positional[0], &opts)?;
+
+        let mut out = io::stdout().lock();
+        if !opts.quiet {
+            print_preamble(&mut out, &report).map_err(write_error(&opts))?;
+        }
+        return explore(&report, &opts, &mut io::stdin().lock(), &mut out).map_err(write_error(&opts));
+    }
 
-    let path = Path::new(filename);
-    let extension = path
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("");
+    // created (or truncated) up front so a bad path fails before any input is read
+    let mut out: Box<dyn Write> = match &opts.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(write_error(&opts))?)),
+        None => Box::new(io::stdout().lock()),
+    };
 
-    match extension {
-        "csv" => inspect_csv(filename, show_types, show_summary, show_diagnose),
-        "json" => inspect_json(filename, show_types),
-        _ => {
-            eprintln!("Unsupported file type: {}", extension);
-            std::process::exit(1);
+    let filenames: Vec<&str> = positional.iter().map(|s| s.as_str()).collect();
+    let show_overview = args.iter().any(|a| a == "--overview");
+    if show_overview
+        && (opts.compare_schema || flag_value(&args, "--diff").is_some() || flag_value(&args, "--sql").is_some())
+    {
+        return Err(InspectError::Usage(
+            "--overview can't be combined with --compare-schema, --diff or --sql".to_string(),
+        ));
+    }
+    if args.iter().any(|a| a == "--count-only") {
+        let conflicting = ["--overview", "--compare-schema", "--diff", "--sql", "--json-schema", "--dry-run"];
+        if let Some(flag) = conflicting.iter().find(|&&flag| args.iter().any(|a| a == flag)) {
+            return Err(InspectError::Usage(format!("--count-only can't be combined with {}", flag)));
+        }
+        for filename in &filenames {
+            let count = count_rows(filename, &opts)?;
+            print_row_count(&mut out, filename, &count, &opts).map_err(write_error(&opts))?;
         }
+        return out.flush().map_err(write_error(&opts));
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum ColumnType {
-    Numeric,
-    Categorical,
-}
+    if let Some(baseline) = flag_value(&args, "--diff") {
+        if filenames.len() != 1 || opts.compare_schema {
+            return Err(InspectError::Usage(
+                "--diff compares a single file against the baseline and can't be combined with --compare-schema"
+                    .to_string(),
+            ));
+        }
+        let tolerance: f64 = parse_flag(&args, "--diff-tolerance")?.unwrap_or(0.05);
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return Err(InspectError::Usage(format!(
+                "--diff-tolerance must be zero or more, got {}",
+                tolerance
+            )));
+        }
+        let result = diff_stats(&mut out, baseline, filenames[0], tolerance, &opts);
+        out.flush().map_err(write_error(&opts))?;
+        return result;
+    }
 
-#[derive(Debug)]
-struct ColumnStats {
-    name: String,
-    kind: ColumnType,
-
-    total: usize,
-    missing: usize,
-
-    // num stats 
-    min: Option<f64>,
-    max: Option<f64>, 
-    mean: f64,
-    m2: f64, 
-
-    // categorical stats
-    uniques: HashSet<String>,
-
-    //diagnostics helpers
-    numeric_parse_failures: usize,
-
-    //outliers 
-    outlier_count: usize,
-}
-
-// Welford's ALGORITHM -> streaming mean + variance
-
-impl ColumnStats {
-    fn new(name: &str, kind: ColumnType) -> Self {
-        Self {
-            name: name.to_string(),
-            kind,
-            total: 0,
-            missing: 0,
-            min: None,
-            max: None,
-            mean: 0.0,
-            m2: 0.0,
-            uniques: HashSet::new(),
-            numeric_parse_failures: 0,
-            outlier_count: 0,
-        }
-    }
-
-    fn update(&mut self, value: &str) {
-        self.total += 1;
-
-        if value.is_empty() {
-            self.missing += 1;
-            return;
-        }
-
-        match self.kind {
-            ColumnType::Numeric => {
-                if let Ok(x) = value.parse::<f64>() {
-                    let previous_count = self.total - self.missing - 1;
-                    
-                    if previous_count >= 2 {
-                        let prev_stddev = (self.m2 / (previous_count as f64 - 1.0)).sqrt();
-                        if prev_stddev > 0.0 {
-                            let z = (x - self.mean).abs() / prev_stddev;
-                            if z >= 5.0 {
-                                self.outlier_count += 1;
-                            }
-                        }
-                    }
-                    
-                    // update stats with current value
-                    let count = previous_count + 1;
-                    let delta = x - self.mean;
-                    self.mean += delta / count as f64;
-                    self.m2 += delta * (x - self.mean);
-
-                    self.min = Some(self.min.map_or(x, |m| m.min(x)));
-                    self.max = Some(self.max.map_or(x, |m| m.max(x)));
-                } else {
-                    self.numeric_parse_failures += 1;
+    if let Some(table) = flag_value(&args, "--sql") {
+        if filenames.len() != 1 || opts.compare_schema || opts.json {
+            return Err(InspectError::Usage(
+                "--sql takes a single file and can't be combined with --compare-schema or --json".to_string(),
+            ));
+        }
+        let report = inspect_file(filenames[0], &opts)?;
+        return write_create_table(&mut out, table, &report)
+            .and_then(|_| out.flush())
+            .map_err(write_error(&opts));
+    }
+
+    if args.iter().any(|a| a == "--json-schema") {
+        if filenames.len() != 1 || opts.compare_schema || opts.json || show_overview || flag_value(&args, "--sql").is_some() {
+            return Err(InspectError::Usage(
+                "--json-schema takes a single file and can't be combined with --compare-schema, --json, --overview or --sql"
+                    .to_string(),
+            ));
+        }
+        let report = inspect_file(filenames[0], &opts)?;
+        let schema = report.json_schema(filenames[0]);
+        return write_json(&mut out, &schema, true)
+            .and_then(|_| writeln!(out))
+            .and_then(|_| out.flush())
+            .map_err(write_error(&opts));
+    }
+
+    if args.iter().any(|a| a == "--dry-run") {
+        if show_overview || opts.compare_schema || flag_value(&args, "--diff").is_some() || flag_value(&args, "--sql").is_some() {
+            return Err(InspectError::Usage(
+                "--dry-run can't be combined with --overview, --compare-schema, --diff or --sql".to_string(),
+            ));
+        }
+        for filename in &filenames {
+            let estimate = estimate_memory(filename, &opts)?;
+            print_memory_estimate(&mut out, filename, &estimate, &opts).map_err(write_error(&opts))?;
+        }
+        return out.flush().map_err(write_error(&opts));
+    }
+
+    if show_overview {
+        let result = overview(&mut out, &filenames, &opts);
+        out.flush().map_err(write_error(&opts))?;
+        return result;
+    }
+
+    if opts.compare_schema {
+        let result = compare_schemas(&mut out, &filenames, &opts);
+        out.flush().map_err(write_error(&opts))?;
+        return result;
+    }
+
+    // created up front as well, then filled in file by file
+    let junit_path = flag_value(&args, "--junit");
+    let junit_error = |source| InspectError::Output {
+        path: junit_path.unwrap_or_default().to_string(),
+        source,
+    };
+    let mut junit = match junit_path {
+        Some(path) => {
+            let mut file = BufWriter::new(File::create(path).map_err(junit_error)?);
+            writeln!(file, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+                .and_then(|_| writeln!(file, "<testsuites name=\"datainspect\">"))
+                .map_err(junit_error)?;
+            Some(file)
+        }
+        None => None,
+    };
+
+    let timing = args.iter().any(|a| a == "--timing");
+    let mut timings = Vec::new();
+    let mut failed = 0;
+    for (i, filename) in filenames.iter().enumerate() {
+        let started = Instant::now();
+        let report = inspect_file(filename, &opts)?;
+        if timing {
+            timings.push(FileTiming {
+                name: filename,
+                elapsed: started.elapsed(),
+                rows: report.rows,
+                // stdin and URLs have no size to read up front
+                bytes: std::fs::metadata(filename).ok().filter(|m| m.is_file()).map(|m| m.len()),
+            });
+        }
+        if let Some(file) = &mut junit {
+            // without --fail-on, warnings and errors fail their test case
+            let level = opts.fail_on.unwrap_or(Severity::Warning);
+            write_junit_suite(file, filename, &report, level).map_err(junit_error)?;
+        }
+
+        if opts.json && (json_array || filenames.len() > 1) {
+            write_json_report(&mut out, filename, &report, filenames.len(), i, json_array, &opts)
+                .map_err(write_error(&opts))?;
+        } else {
+            if filenames.len() > 1 && !opts.json {
+                if i > 0 {
+                    writeln!(out).map_err(write_error(&opts))?;
                 }
+                writeln!(out, "==> {} <==", filename).map_err(write_error(&opts))?;
             }
-            ColumnType::Categorical => {
-                self.uniques.insert(value.to_string());
-            }
+            emit_report(&mut out, &report, &opts).map_err(write_error(&opts))?;
+        }
+
+        // all diagnostics are collected either way, so this works without --diagnose
+        if let Some(level) = opts.fail_on {
+            failed += report.diagnostics().filter(|d| d.severity >= level).count();
         }
     }
 
-    fn stddev(&self) -> Option<f64> {
-        let count = self.total - self.missing;
-        if count > 1 {
-            Some((self.m2 / (count as f64 - 1.0)).sqrt())
-        } else {
-            None
+    if json_array {
+        let close = if opts.json_pretty { "\n]" } else { "]" };
+        writeln!(out, "{}", close).map_err(write_error(&opts))?;
+    }
+    out.flush().map_err(write_error(&opts))?;
+    if let Some(mut file) = junit {
+        writeln!(file, "</testsuites>").and_then(|_| file.flush()).map_err(junit_error)?;
+    }
+
+    if timing {
+        for line in timing_lines(&timings) {
+            eprintln!("{}", line);
         }
     }
+
+    match opts.fail_on {
+        Some(level) if failed > 0 => Err(InspectError::ChecksFailed { count: failed, level }),
+        _ => Ok(()),
+    }
 }
 
-fn inspect_csv(filename: &str, show_types: bool, show_summary: bool, show_diagnose: bool) {
-    let mut reader = Reader::from_path(filename)
-        .expect("Failed to open CSV file");
+// wall-clock time of one file's inspection (reading through building the
+// report, not printing it) for --timing
+struct FileTiming<'a> {
+    name: &'a str,
+    elapsed: Duration,
+    rows: usize,
+    bytes: Option<u64>,
+}
 
-    let headers = reader
-        .headers()
-        .expect("Failed to read CSV headers")
-        .clone();
+// for stderr, so the report on stdout stays as it would be without --timing;
+// a total follows when there are several files
+fn timing_lines(timings: &[FileTiming]) -> Vec<String> {
+    let line = |name: &str, elapsed: Duration, rows: usize, bytes: Option<u64>| {
+        let seconds = elapsed.as_secs_f64().max(1e-9);
+        let mut line = format!(
+            "Timing: {}: {:.3}s, {} rows ({:.0} rows/s)",
+            name,
+            elapsed.as_secs_f64(),
+            rows,
+            rows as f64 / seconds
+        );
+        if let Some(bytes) = bytes {
+            line += &format!(
+                ", {} ({}/s)",
+                HumanBytes(bytes),
+                HumanBytes((bytes as f64 / seconds) as u64)
+            );
+        }
+        line
+    };
 
-    let col_count = headers.len();
+    let mut lines: Vec<String> = timings.iter().map(|t| line(t.name, t.elapsed, t.rows, t.bytes)).collect();
+    if timings.len() > 1 {
+        let elapsed = timings.iter().map(|t| t.elapsed).sum();
+        let rows = timings.iter().map(|t| t.rows).sum();
+        let bytes = timings.iter().map(|t| t.bytes).sum();
+        lines.push(line("total", elapsed, rows, bytes));
+    }
+    lines
+}
 
-    let mut row_count = 0;
-    let mut inferred: Vec<Option<&'static str>> = vec![None; col_count];
-    let mut column_stats: Vec<Option<ColumnStats>> = (0..col_count).map(|_| None).collect();
+// with several files, each report says which file it describes
+#[derive(Serialize)]
+struct FileReport<'a> {
+    file: &'a str,
+    #[serde(flatten)]
+    report: &'a Report,
+}
 
-    for result in reader.records() {
-        let record = result.expect("Failed to read record");
-        row_count += 1;
+// One of the `files` reports: a line of its own, or the `index`th element of
+// the array opened by the first and closed after the loop.
+fn write_json_report(
+    out: &mut dyn Write,
+    filename: &str,
+    report: &Report,
+    files: usize,
+    index: usize,
+    array: bool,
+    opts: &InspectOptions,
+) -> io::Result<()> {
+    if array {
+        write!(out, "{}", if index == 0 { "[" } else { "," })?;
+        if opts.json_pretty {
+            writeln!(out)?;
+        }
+    }
+    if files > 1 {
+        write_json(out, &FileReport { file: filename, report }, opts.json_pretty)?;
+    } else {
+        write_json(out, report, opts.json_pretty)?;
+    }
+    if !array {
+        writeln!(out)?;
+    }
+    Ok(())
+}
 
-        for (i, value) in record.iter().enumerate() {
-            if inferred[i].is_none() {
-                let kind = if value.is_empty() {
-                    // temporarily unknown, treat as categorical for now
-                    ColumnType::Categorical
-                } else {
-                    match infer_type(value) {
-                        "integer" | "float" => ColumnType::Numeric,
-                        _ => ColumnType::Categorical,
-                    }
-                };
+// where the report goes: the --output path, or stdout
+fn write_error(opts: &InspectOptions) -> impl FnOnce(io::Error) -> InspectError + '_ {
+    move |source| InspectError::Output {
+        path: opts.output.as_deref().unwrap_or("stdout").to_string(),
+        source,
+    }
+}
 
-                inferred[i] = Some(match kind {
-                    ColumnType::Numeric => "numeric",
-                    ColumnType::Categorical => "categorical",
-                });
+// flags that take a value, so their argument isn't mistaken for the file
+const VALUE_FLAGS: &[&str] = &[
+    "--delimiter",
+    "--top",
+    "--max-uniques",
+    "--threads",
+    "--columns",
+    "--exclude-columns",
+    "--infer-sample",
+    "--infer-threshold",
+    "--outlier-sigma",
+    "--missing-threshold",
+    "--cardinality-threshold",
+    "--sparse-threshold",
+    "--zero-threshold",
+    "--limit",
+    "--skip",
+    "--bins",
+    "--fail-on",
+    "--sheet",
+    "--format",
+    "--color",
+    "--max-length",
+    "--sample",
+    "--tail",
+    "--random-sample",
+    "--max-depth",
+    "--coerce",
+    "--validate",
+    "--fixed-width",
+    "--decimal-separator",
+    "--currency-symbols",
+    "--thousands-separators",
+    "--seed",
+    "--outlier-method",
+    "--na-values",
+    "--output",
+    "--diff",
+    "--diff-tolerance",
+    "--config",
+    "--junit",
+    "--encoding",
+    "--sql",
+    "--only-check",
+    "--disable-check",
+    "--group-by",
+    "--quote",
+    "--escape",
+    "--json-layout",
+    "--date-min",
+    "--date-max",
+    "--range-min",
+    "--range-max",
+];
 
-                column_stats[i] = Some(ColumnStats::new(&headers[i], kind));
-            }
+// flags without a value that a config file may turn on
+const SWITCH_FLAGS: &[&str] = &[
+    "--summary",
+    "--percentiles",
+    "--pretty",
+    "--plain",
+    "--histogram",
+    "--correlate",
+    "--types",
+    "--explain",
+    "--fingerprint",
+    "--benford",
+    "--diagnose",
+    "--compare-schema",
+    "--json-schema",
+    "--overview",
+    "--dry-run",
+    "--count-only",
+    "--json",
+    "--no-progress",
+    "--timing",
+    "--quiet",
+    "--approx-distinct",
+    "--clean-numbers",
+    "--no-header",
+    "--strict",
+    "--no-quoting",
+    "--no-trim",
+];
 
-            if let Some(stats) = &mut column_stats[i] {
-                if stats.kind == ColumnType::Categorical && !value.is_empty() {
-                    if matches!(infer_type(value), "integer" | "float") {
-                        // Upgrade categorical → numeric
-                        stats.kind = ColumnType::Numeric;
-                        stats.uniques.clear(); // no longer needed
-                        inferred[i] = Some("numeric");
-                    }
-                }
+// Shell completion scripts, generated from the flag lists above and the help
+// text. Flags with a fixed set of values complete those, path flags and the
+// positional arguments complete file names.
 
-                stats.update(value);
-            }
+// switches that only make sense on the command line, not in a config file
+const COMMAND_LINE_FLAGS: &[&str] = &["--interactive", "--help", "--version"];
+
+const PATH_FLAGS: &[&str] = &["--output", "--config", "--junit", "--diff"];
+
+fn flag_choices(flag: &str) -> Option<Vec<&'static str>> {
+    Some(match flag {
+        "--format" => vec!["csv", "tsv", "json", "jsonl", "yaml", "toml"],
+        "--color" => vec!["auto", "always", "never"],
+        "--outlier-method" => vec!["zscore", "iqr"],
+        "--fail-on" => vec!["info", "warning", "error"],
+        "--json-layout" => vec!["lines", "array"],
+        "--only-check" | "--disable-check" => CHECKS.to_vec(),
+        _ => return None,
+    })
+}
+
+fn completion_flags() -> impl Iterator<Item = &'static str> {
+    VALUE_FLAGS.iter().chain(SWITCH_FLAGS).chain(COMMAND_LINE_FLAGS).copied()
+}
+
+// first line of the flag's description in HELP; a description that starts
+// on the line after the flag is picked up from there
+fn flag_description(flag: &str) -> &'static str {
+    let mut lines = HELP.lines();
+    while let Some(line) = lines.next() {
+        let Some(spec) = line.strip_prefix("  ").filter(|l| l.starts_with('-')) else {
+            continue;
+        };
+        let (spec, description) = spec.split_once("  ").unwrap_or((spec, ""));
+        if spec.split([' ', ',']).any(|word| word == flag) {
+            // a description running over several lines ends mid-list
+            let description = description.trim().trim_end_matches(',');
+            return if description.is_empty() {
+                lines.next().map_or("", |next| next.trim().trim_end_matches(','))
+            } else {
+                description
+            };
+        }
+    }
+    ""
+}
+
+fn bash_completions() -> String {
+    let flags: Vec<&str> = completion_flags().collect();
+    let mut cases = String::new();
+    for flag in VALUE_FLAGS {
+        if let Some(choices) = flag_choices(flag) {
+            cases += &format!(
+                "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return ;;\n",
+                flag,
+                choices.join(" ")
+            );
         }
     }
+    cases += &format!("        {}) COMPREPLY=($(compgen -f -- \"$cur\")); return ;;\n", PATH_FLAGS.join("|"));
+    // a number or a name: nothing to offer
+    let free: Vec<&str> = VALUE_FLAGS
+        .iter()
+        .copied()
+        .filter(|flag| flag_choices(flag).is_none() && !PATH_FLAGS.contains(flag))
+        .collect();
+    cases += &format!("        {}) COMPREPLY=(); return ;;\n", free.join("|"));
 
-    println!("File type: CSV");
-    println!("Rows: {}", row_count);
-    println!("Columns:");
-    for header in headers.iter() {
-        println!("  - {}", header);
+    format!(
+        r#"_datainspect() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    if [[ $COMP_CWORD -eq 2 && "$prev" == completions ]]; then
+        COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
+        return
+    fi
+    case "$prev" in
+{}    esac
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "{}" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -f -- "$cur"))
+    fi
+}}
+complete -o filenames -F _datainspect datainspect
+"#,
+        cases,
+        flags.join(" ")
+    )
+}
+
+fn zsh_completions() -> String {
+    // _arguments specs are single-quoted, and ] ends the description
+    let escape = |text: &str| text.replace('\'', "'\\''").replace('[', "\\[").replace(']', "\\]");
+    let mut specs = String::new();
+    for flag in completion_flags() {
+        let action = if !VALUE_FLAGS.contains(&flag) {
+            String::new()
+        } else if let Some(choices) = flag_choices(flag) {
+            format!(":value:({})", choices.join(" "))
+        } else if PATH_FLAGS.contains(&flag) {
+            ":file:_files".to_string()
+        } else {
+            ":value: ".to_string()
+        };
+        specs += &format!("    '{}[{}]{}' \\\n", flag, escape(flag_description(flag)), action);
     }
 
-    if show_types {
-        println!("Inferred types:");
-        for (header, dtype) in headers.iter().zip(inferred.iter()) {
-            println!("  - {}: {}", header, dtype.unwrap_or("unknown"));
+    format!(
+        "#compdef datainspect\n\nif [[ $words[2] == completions ]]; then\n    _arguments '2:shell:(bash zsh fish)'\n    return\nfi\n\n_arguments \\\n{}    '*:file:_files'\n",
+        specs
+    )
+}
+
+fn fish_completions() -> String {
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('\'', "\\'");
+    let mut script = String::from(
+        "complete -c datainspect -n '__fish_use_subcommand' -a completions -d 'Print a shell completion script'\n\
+         complete -c datainspect -n '__fish_seen_subcommand_from completions' -x -a 'bash zsh fish'\n",
+    );
+    for flag in completion_flags() {
+        let mut line = format!(
+            "complete -c datainspect -l {} -d '{}'",
+            &flag[2..],
+            escape(flag_description(flag))
+        );
+        if let Some(choices) = flag_choices(flag) {
+            line += &format!(" -x -a '{}'", choices.join(" "));
+        } else if PATH_FLAGS.contains(&flag) {
+            line += " -r -F";
+        } else if VALUE_FLAGS.contains(&flag) {
+            line += " -x";
         }
+        script += &line;
+        script.push('\n');
     }
+    script
+}
 
-    if show_summary {
-        println!("Summary:");
+// switches that cancel each other, so an explicit one overrides the other's default
+const EXCLUSIVE_SWITCHES: &[(&str, &str)] = &[("--pretty", "--plain")];
 
-        for stats_opt in column_stats.iter().flatten() {
-            match stats_opt.kind {
-                ColumnType::Numeric => {
-                    let count = stats_opt.total - stats_opt.missing;
+const CONFIG_FILE: &str = "datainspect.toml";
 
-                    if count > 0 {
-                        println!(
-                            "  - {} (numeric): count={} missing={} min={} max={} mean={} stddev={}",
-                            stats_opt.name,
-                            count,
-                            stats_opt.missing,
-                            stats_opt.min.unwrap(),
-                            stats_opt.max.unwrap(),
-                            stats_opt.mean,
-                            stats_opt.stddev().unwrap_or(0.0)
-                        );
-                    }
-                }
-                ColumnType::Categorical => {
-                    println!(
-                        "  - {} (categorical): count={} missing={} unique={}",
-                        stats_opt.name,
-                        stats_opt.total - stats_opt.missing,
-                        stats_opt.missing,
-                        stats_opt.uniques.len()
-                    );
-                }
+// Defaults from --config <path>, or datainspect.toml in the current directory.
+// Keys are the long flag names without the dashes (`delimiter = ";"`,
+// `summary = true`, `na-values = ["NA", "null"]`); a flag given on the command
+// line wins over its key.
+fn config_args(args: &[String]) -> Result<Vec<String>, InspectError> {
+    let path = match flag_value(args, "--config") {
+        Some(path) => path.to_string(),
+        None if Path::new(CONFIG_FILE).is_file() => CONFIG_FILE.to_string(),
+        None => return Ok(Vec::new()),
+    };
+    let text = std::fs::read_to_string(&path).map_err(|source| InspectError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    let table: toml::Table = text
+        .parse()
+        .map_err(|err: toml::de::Error| InspectError::Usage(format!("{}: invalid TOML: {}", path, err.message())))?;
+
+    let invalid = |key: &str, expected: &str| InspectError::Usage(format!("{}: {} must be {}", path, key, expected));
+    let given = |flag: &str| args.iter().any(|a| a == flag);
+    let mut defaults = Vec::new();
+    for (key, value) in &table {
+        let flag = format!("--{}", key);
+        if SWITCH_FLAGS.contains(&flag.as_str()) {
+            let on = value.as_bool().ok_or_else(|| invalid(key, "true or false"))?;
+            let overridden = EXCLUSIVE_SWITCHES
+                .iter()
+                .any(|&(a, b)| (flag == a && given(b)) || (flag == b && given(a)));
+            if on && !given(&flag) && !overridden {
+                defaults.push(flag);
+            }
+        } else if VALUE_FLAGS.contains(&flag.as_str()) && flag != "--config" {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(n) => n.to_string(),
+                toml::Value::Float(x) => x.to_string(),
+                toml::Value::Array(items) => items
+                    .iter()
+                    .map(|item| match item {
+                        toml::Value::String(s) => Ok(s.clone()),
+                        _ => Err(invalid(key, "a list of strings")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(","),
+                _ => return Err(invalid(key, "a string or a number")),
+            };
+            if !given(&flag) {
+                defaults.push(flag);
+                defaults.push(value);
             }
+        } else {
+            return Err(InspectError::Usage(format!("{}: unknown option: {}", path, key)));
         }
     }
+    Ok(defaults)
+}
 
-    if show_diagnose {
-        println!();
-        println!("Data Quality Report");
-        println!("--------------------");
-        println!();
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|v| v.as_str())
+}
 
-        for stats_opt in column_stats.iter().flatten() {
-            println!("{} ({:?})", stats_opt.name, stats_opt.kind);
-            diagnose_column(stats_opt, row_count);
-            println!();
+// Every flag on the command line must be a known one, and the ones taking a
+// value must have it; a typo would otherwise just leave its section out of
+// the report. A lone "-" is left to be read as a file name.
+fn check_args(args: &[String]) -> Result<(), InspectError> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            if iter.next().is_none() {
+                return Err(InspectError::Usage(format!("{} needs a value", arg)));
+            }
+        } else if arg.starts_with('-')
+            && arg != "-"
+            && !SWITCH_FLAGS.contains(&arg.as_str())
+            && !COMMAND_LINE_FLAGS.contains(&arg.as_str())
+        {
+            let suggestion = completion_flags()
+                .map(|flag| (edit_distance(arg, flag), flag))
+                .filter(|&(distance, _)| distance <= 2)
+                .min()
+                .map(|(_, flag)| format!(" (did you mean {}?)", flag))
+                .unwrap_or_default();
+            return Err(InspectError::Usage(format!(
+                "unknown flag: {}{}\nsee datainspect --help for the options",
+                arg, suggestion
+            )));
         }
     }
+    Ok(())
 }
 
-fn diagnose_column(stats: &ColumnStats, total_rows: usize) {
-    let mut warnings = Vec::new();
+// Levenshtein distance, for suggesting the flag a typo was meant to be
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
 
-    let missing_ratio = stats.missing as f64 / total_rows as f64;
+// every value of a repeatable flag, each one also split on commas
+fn flag_values<'a>(args: &'a [String], flag: &str) -> Vec<&'a str> {
+    args.windows(2)
+        .filter(|pair| pair[0] == flag)
+        .flat_map(|pair| pair[1].split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
 
-    // missing severity
-    if missing_ratio > 0.05 {
-        warnings.push(format!(
-            "! missing values: {}%",
-            (missing_ratio * 100.0).round() as usize
-        ));
+fn check_names(args: &[String], flag: &str) -> Result<Vec<String>, InspectError> {
+    flag_values(args, flag)
+        .into_iter()
+        .map(|name| {
+            if CHECKS.contains(&name) {
+                Ok(name.to_string())
+            } else {
+                Err(InspectError::Usage(format!(
+                    "unknown check for {}: {} (expected one of {})",
+                    flag,
+                    name,
+                    CHECKS.join(", ")
+                )))
+            }
+        })
+        .collect()
+}
+
+fn parse_flag<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<Option<T>, InspectError> {
+    flag_value(args, flag)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| InspectError::Usage(format!("invalid value for {}: {}", flag, value)))
+        })
+        .transpose()
+}
+
+// a single ASCII character, e.g. ' for --quote or \ for --escape
+fn parse_char(value: &str) -> Option<u8> {
+    match value.as_bytes() {
+        [byte] if byte.is_ascii() => Some(*byte),
+        _ => None,
     }
+}
 
-    match stats.kind {
-        ColumnType::Categorical => {
-            let non_missing = total_rows - stats.missing;
-            if non_missing > 0 {
-                let unique_ratio = stats.uniques.len() as f64 / non_missing as f64;
-                if unique_ratio > 0.95 {
-                    warnings.push(format!(
-                        "! high cardinality: {:.1}% unique (likely identifier)",
-                        unique_ratio * 100.0
-                    ));
-                }
+fn parse_delimiter(value: &str) -> Option<u8> {
+    match value {
+        "\\t" | "tab" => Some(b'\t'),
+        _ if value.len() == 1 => Some(value.as_bytes()[0]),
+        _ => None,
+    }
+}
+
+// Schema drift across files. Each file is checked against the one before it,
+// so a mismatch is reported on the file that introduced it.
+
+#[derive(Debug, Serialize)]
+struct FileSchema {
+    file: String,
+    columns: Vec<SchemaColumn>,
+    differences: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SchemaColumn {
+    name: String,
+    #[serde(rename = "type")]
+    kind: Option<ColumnType>,
+}
+
+fn compare_schemas(
+    out: &mut dyn Write,
+    filenames: &[&str],
+    opts: &InspectOptions,
+) -> Result<(), InspectError> {
+    let mut files: Vec<FileSchema> = Vec::new();
+
+    for filename in filenames {
+        let report = inspect_file(filename, opts)?;
+        let columns: Vec<SchemaColumn> = report
+            .columns
+            .into_iter()
+            .map(|c| SchemaColumn {
+                name: c.name,
+                kind: c.kind,
+            })
+            .collect();
+        let differences = match files.last() {
+            Some(previous) => schema_differences(&previous.columns, &columns),
+            None => Vec::new(),
+        };
+
+        files.push(FileSchema {
+            file: filename.to_string(),
+            columns,
+            differences,
+        });
+    }
+
+    print_schema_comparison(out, &files, opts).map_err(write_error(opts))?;
+
+    match files.iter().filter(|f| !f.differences.is_empty()).count() {
+        0 => Ok(()),
+        mismatched => Err(InspectError::SchemaMismatch(mismatched)),
+    }
+}
+
+fn print_schema_comparison(
+    out: &mut dyn Write,
+    files: &[FileSchema],
+    opts: &InspectOptions,
+) -> io::Result<()> {
+    if opts.json {
+        write_json(out, files, opts.json_pretty)?;
+        return writeln!(out);
+    }
+
+    writeln!(out, "Schema comparison:")?;
+    for (i, file) in files.iter().enumerate() {
+        if i == 0 {
+            writeln!(out, "  {}: {} columns", file.file, file.columns.len())?;
+        } else if file.differences.is_empty() {
+            writeln!(out, "  {}: ok", file.file)?;
+        } else {
+            writeln!(out, "  {}: differs from {}", file.file, files[i - 1].file)?;
+            for difference in &file.differences {
+                writeln!(out, "    ! {}", difference)?;
             }
         }
+    }
+    Ok(())
+}
+
+fn schema_differences(previous: &[SchemaColumn], current: &[SchemaColumn]) -> Vec<String> {
+    let kind_name = |kind: Option<ColumnType>| kind.map_or("unknown", ColumnType::as_str);
+    let find = |columns: &'_ [SchemaColumn], name: &str| columns.iter().position(|c| c.name == name);
+    let mut differences = Vec::new();
+
+    for column in previous {
+        if find(current, &column.name).is_none() {
+            differences.push(format!("missing column: {}", column.name));
+        }
+    }
+    for column in current {
+        match find(previous, &column.name) {
+            None => differences.push(format!("new column: {}", column.name)),
+            Some(i) if previous[i].kind != column.kind => differences.push(format!(
+                "type of {} changed: {} -> {}",
+                column.name,
+                kind_name(previous[i].kind),
+                kind_name(column.kind)
+            )),
+            Some(_) => {}
+        }
+    }
+
+    // order of the columns both files share
+    let shared = |a: &[SchemaColumn], b: &[SchemaColumn]| -> Vec<String> {
+        a.iter()
+            .filter(|c| find(b, &c.name).is_some())
+            .map(|c| c.name.clone())
+            .collect()
+    };
+    if shared(previous, current) != shared(current, previous) {
+        differences.push("column order changed".to_string());
+    }
+
+    differences
+}
+
+// A one-line inventory per file, like `ls -l` for datasets. A file that
+// fails to read gets its error on its line instead of stopping the scan; the
+// first such error is still returned once every file is listed.
+
+#[derive(Debug, Serialize)]
+struct FileOverview {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'static str>,
+    rows: usize,
+    columns: usize,
+    errors: usize,
+    warnings: usize,
+    infos: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl FileOverview {
+    // "ok", or the diagnostic counts worst first
+    fn quality(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("error: {}", error);
+        }
+        let counts: Vec<String> = [(self.errors, "error"), (self.warnings, "warning"), (self.infos, "info")]
+            .into_iter()
+            .filter(|&(count, _)| count > 0)
+            .map(|(count, label)| format!("{} {}{}", count, label, if count == 1 { "" } else { "s" }))
+            .collect();
+        if counts.is_empty() {
+            "ok".to_string()
+        } else {
+            counts.join(", ")
+        }
+    }
+}
 
-        ColumnType::Numeric => {
-            // near-constant numeric
-            if let (Some(min), Some(max)) = (stats.min, stats.max) {
-                if (max - min).abs() < 1e-12 {
-                    warnings.push("! near-constant numeric column".to_string());
+fn overview(out: &mut dyn Write, filenames: &[&str], opts: &InspectOptions) -> Result<(), InspectError> {
+    // the outlier pass only runs when diagnostics are asked for
+    let mut opts = opts.clone();
+    opts.show_diagnose = true;
+
+    let mut files = Vec::new();
+    let mut first_error = None;
+    let mut failed = 0;
+    for filename in filenames {
+        let count = |report: &Report, severity| report.diagnostics().filter(|d| d.severity == severity).count();
+        let file = match inspect_file(filename, &opts) {
+            Ok(report) => {
+                if let Some(level) = opts.fail_on {
+                    failed += report.diagnostics().filter(|d| d.severity >= level).count();
+                }
+                FileOverview {
+                    file: filename.to_string(),
+                    format: Some(report.file_type),
+                    rows: report.rows,
+                    columns: report.columns.len(),
+                    errors: count(&report, Severity::Error),
+                    warnings: count(&report, Severity::Warning),
+                    infos: count(&report, Severity::Info),
+                    score: Some(report.quality.score),
+                    error: None,
                 }
             }
-
-            // mixed-type numeric
-            if stats.numeric_parse_failures > 0 {
-                warnings.push("! mixed numeric and non-numeric values".to_string());
+            Err(err) => {
+                let file = FileOverview {
+                    file: filename.to_string(),
+                    format: None,
+                    rows: 0,
+                    columns: 0,
+                    errors: 0,
+                    warnings: 0,
+                    infos: 0,
+                    score: None,
+                    error: Some(err.to_string()),
+                };
+                first_error.get_or_insert(err);
+                file
             }
-            
-            // outliers 
-            if stats.outlier_count > 0 {
-                warnings.push(format!(
-                        "! extreme outliers detected: {} values >= 5σ",
-                        stats.outlier_count
-                ));
+        };
+        files.push(file);
+    }
+
+    print_overview(out, &files, &opts).map_err(write_error(&opts))?;
+
+    if let Some(err) = first_error {
+        return Err(err);
+    }
+    match opts.fail_on {
+        Some(level) if failed > 0 => Err(InspectError::ChecksFailed { count: failed, level }),
+        _ => Ok(()),
+    }
+}
+
+fn print_overview(out: &mut dyn Write, files: &[FileOverview], opts: &InspectOptions) -> io::Result<()> {
+    if opts.json {
+        write_json(out, files, opts.json_pretty)?;
+        return writeln!(out);
+    }
+
+    let rows: Vec<[String; 6]> = files
+        .iter()
+        .map(|f| {
+            let known = |text: String| if f.error.is_some() { "-".to_string() } else { text };
+            [
+                f.file.clone(),
+                f.format.unwrap_or("-").to_string(),
+                known(f.rows.to_string()),
+                known(f.columns.to_string()),
+                f.score.map_or("-".to_string(), |score| format!("{:.1}", score)),
+                f.quality(),
+            ]
+        })
+        .collect();
+    let header = ["FILE", "FORMAT", "ROWS", "COLUMNS", "SCORE", "QUALITY"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    // the last column is left unpadded; counts and scores are right-aligned
+    let line = |out: &mut dyn Write, cells: [&str; 6]| -> io::Result<()> {
+        let mut text = String::new();
+        for (i, cell) in cells.iter().enumerate() {
+            let width = widths[i];
+            match i {
+                5 => text.push_str(cell),
+                2..=4 => text.push_str(&format!("{:>width$}  ", cell)),
+                _ => text.push_str(&format!("{:<width$}  ", cell)),
             }
         }
+        writeln!(out, "{}", text)
+    };
+    line(out, header)?;
+    for row in &rows {
+        line(out, [&row[0], &row[1], &row[2], &row[3], &row[4], &row[5]])?;
+    }
+    Ok(())
+}
+
+// --count-only: one line per file, or one JSON object
+#[derive(Serialize)]
+struct FileCount<'a> {
+    file: &'a str,
+    #[serde(flatten)]
+    count: &'a RowCount,
+}
+
+fn print_row_count(out: &mut dyn Write, filename: &str, count: &RowCount, opts: &InspectOptions) -> io::Result<()> {
+    if opts.json {
+        write_json(out, &FileCount { file: filename, count }, opts.json_pretty)?;
+        return writeln!(out);
+    }
+    writeln!(
+        out,
+        "{}: {} row{}, {} column{}",
+        filename,
+        count.rows,
+        if count.rows == 1 { "" } else { "s" },
+        count.columns,
+        if count.columns == 1 { "" } else { "s" }
+    )
+}
+
+// --dry-run: what a full run would hold in memory, and how to bound it
+
+#[derive(Serialize)]
+struct FileEstimate<'a> {
+    file: &'a str,
+    #[serde(flatten)]
+    estimate: &'a MemoryEstimate,
+}
+
+// a column projected past this is worth capping
+const LARGE_COLUMN_BYTES: u64 = 64 * 1024 * 1024;
+
+fn print_memory_estimate(
+    out: &mut dyn Write,
+    filename: &str,
+    estimate: &MemoryEstimate,
+    opts: &InspectOptions,
+) -> io::Result<()> {
+    if opts.json {
+        write_json(out, &FileEstimate { file: filename, estimate }, opts.json_pretty)?;
+        return writeln!(out);
     }
 
-    // out
-    if warnings.is_empty() {
-        println!("  ok");
+    if estimate.complete {
+        writeln!(out, "{}: {} rows, read in full", filename, estimate.sampled_rows)?;
     } else {
-        for w in warnings {
-            println!("  {}", w);
+        writeln!(
+            out,
+            "{}: first {} rows sampled, about {} rows in {}",
+            filename,
+            estimate.sampled_rows,
+            estimate.projected_rows,
+            HumanBytes(estimate.file_bytes)
+        )?;
+    }
+    writeln!(out, "Estimated peak memory: {}", HumanBytes(estimate.total_bytes))?;
+    writeln!(out, "  duplicate-row hashes: {}", HumanBytes(estimate.row_hashes))?;
+    if estimate.value_buffers > 0 {
+        writeln!(out, "  numeric value buffers: {}", HumanBytes(estimate.value_buffers))?;
+    }
+    for column in &estimate.columns {
+        writeln!(
+            out,
+            "  {}: {} for ~{} distinct values ({} in the sample){}",
+            column.name,
+            HumanBytes(column.bytes),
+            column.projected_distinct,
+            column.sampled_distinct,
+            if column.capped { ", capped" } else { "" }
+        )?;
+    }
+
+    let large: Vec<&str> = estimate
+        .columns
+        .iter()
+        .filter(|c| !c.capped && c.bytes >= LARGE_COLUMN_BYTES)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !large.is_empty() {
+        writeln!(
+            out,
+            "  hint: --approx-distinct or --max-uniques <n> bounds the distinct values of {}",
+            large.join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+// Stats drift against a known-good baseline: every statistic of the columns
+// both files share, with changes beyond the tolerance flagged. Counts and
+// moments compare relative to the baseline value, the missing share by its
+// absolute difference.
+
+#[derive(Debug, Serialize)]
+struct StatsDiff {
+    baseline: String,
+    target: String,
+    tolerance: f64,
+    rows: StatChange,
+    columns: Vec<ColumnDiff>,
+    only_in_baseline: Vec<String>,
+    only_in_target: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ColumnDiff {
+    name: String,
+    // set when the inferred type differs, which always counts as a regression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    type_change: Option<(&'static str, &'static str)>,
+    changes: Vec<StatChange>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatChange {
+    stat: &'static str,
+    baseline: f64,
+    target: f64,
+    exceeded: bool,
+}
+
+impl StatChange {
+    fn relative(stat: &'static str, baseline: f64, target: f64, tolerance: f64) -> Self {
+        let exceeded = if baseline == 0.0 {
+            target != 0.0
+        } else {
+            ((target - baseline) / baseline).abs() > tolerance
+        };
+        StatChange { stat, baseline, target, exceeded }
+    }
+
+    fn absolute(stat: &'static str, baseline: f64, target: f64, tolerance: f64) -> Self {
+        let exceeded = (target - baseline).abs() > tolerance;
+        StatChange { stat, baseline, target, exceeded }
+    }
+
+    fn changed(&self) -> bool {
+        self.baseline != self.target
+    }
+}
+
+impl ColumnDiff {
+    fn exceeded(&self) -> usize {
+        self.type_change.iter().count() + self.changes.iter().filter(|c| c.exceeded).count()
+    }
+}
+
+fn diff_stats(
+    out: &mut dyn Write,
+    baseline: &str,
+    target: &str,
+    tolerance: f64,
+    opts: &InspectOptions,
+) -> Result<(), InspectError> {
+    let old = inspect_file(baseline, opts)?;
+    let new = inspect_file(target, opts)?;
+    let kind_name = |kind: Option<ColumnType>| kind.map_or("unknown", ColumnType::as_str);
+
+    let mut columns = Vec::new();
+    for column in &new.columns {
+        let Some(previous) = old.columns.iter().find(|c| c.name == column.name) else {
+            continue;
+        };
+
+        let mut changes = Vec::new();
+        if let (Some(a), Some(b)) = (&previous.summary, &column.summary) {
+            let missing_share = |count: usize, missing: usize| missing as f64 / (count + missing).max(1) as f64;
+            changes.push(StatChange::relative("count", a.count as f64, b.count as f64, tolerance));
+            changes.push(StatChange::absolute(
+                "missing share",
+                missing_share(a.count, a.missing),
+                missing_share(b.count, b.missing),
+                tolerance,
+            ));
+            for (stat, x, y) in [
+                ("mean", a.mean, b.mean),
+                ("stddev", a.stddev, b.stddev),
+                ("min", a.min, b.min),
+                ("max", a.max, b.max),
+                ("unique", a.unique.map(|u| u as f64), b.unique.map(|u| u as f64)),
+            ] {
+                if let (Some(x), Some(y)) = (x, y) {
+                    changes.push(StatChange::relative(stat, x, y, tolerance));
+                }
+            }
         }
+
+        columns.push(ColumnDiff {
+            name: column.name.clone(),
+            type_change: (previous.kind != column.kind)
+                .then(|| (kind_name(previous.kind), kind_name(column.kind))),
+            changes,
+        });
+    }
+
+    let only_in = |a: &Report, b: &Report| -> Vec<String> {
+        a.columns
+            .iter()
+            .filter(|c| !b.columns.iter().any(|other| other.name == c.name))
+            .map(|c| c.name.clone())
+            .collect()
+    };
+    let diff = StatsDiff {
+        baseline: baseline.to_string(),
+        target: target.to_string(),
+        tolerance,
+        rows: StatChange::relative("rows", old.rows as f64, new.rows as f64, tolerance),
+        only_in_baseline: only_in(&old, &new),
+        only_in_target: only_in(&new, &old),
+        columns,
+    };
+
+    print_stats_diff(out, &diff, opts).map_err(write_error(opts))?;
+
+    let exceeded = usize::from(diff.rows.exceeded)
+        + diff.columns.iter().map(ColumnDiff::exceeded).sum::<usize>()
+        + diff.only_in_baseline.len()
+        + diff.only_in_target.len();
+    match exceeded {
+        0 => Ok(()),
+        count => Err(InspectError::StatsDiffer(count)),
     }
 }
 
-fn inspect_json(filename: &str, show_types: bool) {
-    let contents = std::fs::read_to_string(filename)
-        .expect("Failed to read JSON file");
+fn print_stats_diff(out: &mut dyn Write, diff: &StatsDiff, opts: &InspectOptions) -> io::Result<()> {
+    if opts.json {
+        write_json(out, diff, opts.json_pretty)?;
+        return writeln!(out);
+    }
+
+    writeln!(
+        out,
+        "Diff of {} against {} (tolerance {}):",
+        diff.target, diff.baseline, diff.tolerance
+    )?;
+    print_stat_change(out, &diff.rows, "  ")?;
+    for column in &diff.columns {
+        let changed: Vec<&StatChange> = column.changes.iter().filter(|c| c.changed()).collect();
+        if column.type_change.is_none() && changed.is_empty() {
+            writeln!(out, "  {}: ok", column.name)?;
+            continue;
+        }
+        writeln!(out, "  {}:", column.name)?;
+        if let Some((from, to)) = column.type_change {
+            writeln!(out, "    ! type: {} -> {}", from, to)?;
+        }
+        for change in changed {
+            print_stat_change(out, change, "    ")?;
+        }
+    }
+    for name in &diff.only_in_baseline {
+        writeln!(out, "  ! only in {}: {}", diff.baseline, name)?;
+    }
+    for name in &diff.only_in_target {
+        writeln!(out, "  ! only in {}: {}", diff.target, name)?;
+    }
+    Ok(())
+}
 
-    let json: serde_json::Value = serde_json::from_str(&contents)
-        .expect("Invalid JSON");
+// "! mean: 10.5 -> 12 (+14.3%)", the marker only when beyond the tolerance
+fn print_stat_change(out: &mut dyn Write, change: &StatChange, indent: &str) -> io::Result<()> {
+    let marker = if change.exceeded { "! " } else { "" };
+    let delta = if change.stat == "missing share" {
+        format!("{:+.1} pts", (change.target - change.baseline) * 100.0)
+    } else if change.baseline != 0.0 {
+        format!("{:+.1}%", (change.target - change.baseline) / change.baseline.abs() * 100.0)
+    } else {
+        "from 0".to_string()
+    };
+    if change.stat == "missing share" {
+        writeln!(
+            out,
+            "{}{}{}: {:.1}% -> {:.1}% ({})",
+            indent,
+            marker,
+            change.stat,
+            change.baseline * 100.0,
+            change.target * 100.0,
+            delta
+        )
+    } else {
+        writeln!(
+            out,
+            "{}{}{}: {} -> {} ({})",
+            indent, marker, change.stat, change.baseline, change.target, delta
+        )
+    }
+}
 
-    // Normalize JSON file
-    let records: Vec<serde_json::Map<String, serde_json::Value>> =
-        match json {
-            serde_json::Value::Array(arr) => {
-                // If array of objects → many records
-                let objects: Vec<_> = arr.into_iter()
-                    .filter_map(|v| v.as_object().cloned())
-                    .collect();
+// Column types as PostgreSQL spells them. Integers that fit in 32 bits are
+// INTEGER, those past 64 bits NUMERIC, datetimes with a UTC offset or Z are
+// TIMESTAMPTZ, text is sized by its longest value, and a column without
+// missing values is NOT NULL. Names are always quoted, so reserved words and
+// mixed case survive; the table name is used as given, e.g. `staging.orders`.
+fn write_create_table(out: &mut dyn Write, table: &str, report: &Report) -> io::Result<()> {
+    if report.skipped_rows > 0 {
+        writeln!(
+            out,
+            "-- inferred from rows {} to {}",
+            report.skipped_rows + 1,
+            report.skipped_rows + report.rows
+        )?;
+    } else if report.limited {
+        writeln!(out, "-- inferred from the first {} rows", report.rows)?;
+    }
+    writeln!(out, "CREATE TABLE {} (", table)?;
 
-                if objects.is_empty() {
-                    // Array of primitives → single record
-                    vec![serde_json::Map::new()]
+    for (i, column) in report.columns.iter().enumerate() {
+        let summary = column.summary.as_ref();
+        let zoned = summary.is_some_and(|s| s.zoned);
+        let sql_type = match column.kind {
+            // the summary's min and max leave out integers past ±2^53, which
+            // only an exact type holds next to fractions or past the i64 range
+            Some(ColumnType::Numeric) if column.large_integers.is_some() => {
+                if column.large_integers_past_i64 || column.numeric_type != Some("integer") {
+                    "NUMERIC".to_string()
                 } else {
-                    objects
+                    "BIGINT".to_string()
                 }
             }
-            serde_json::Value::Object(obj) => {
-                // Single object → single record
-                vec![obj]
-            }
-            _ => {
-                eprintln!("Unsupported JSON structure");
-                std::process::exit(1);
+            Some(ColumnType::Numeric) if column.numeric_type == Some("integer") => {
+                let fits = |x: Option<f64>| x.is_none_or(|x| (i32::MIN as f64..=i32::MAX as f64).contains(&x));
+                if summary.is_none_or(|s| fits(s.min) && fits(s.max)) {
+                    "INTEGER".to_string()
+                } else {
+                    "BIGINT".to_string()
+                }
             }
+            Some(ColumnType::Numeric) => "DOUBLE PRECISION".to_string(),
+            Some(ColumnType::DateTime) if zoned => "TIMESTAMPTZ".to_string(),
+            Some(ColumnType::DateTime) => "TIMESTAMP".to_string(),
+            Some(ColumnType::Boolean) => "BOOLEAN".to_string(),
+            Some(ColumnType::Categorical) | None => match summary.and_then(|s| s.max_length) {
+                Some(length) if length > 0 => format!("VARCHAR({})", length),
+                _ => "TEXT".to_string(),
+            },
         };
+        let not_null = summary.is_some_and(|s| s.count > 0 && s.missing == 0);
+        writeln!(
+            out,
+            "    \"{}\" {}{}{}",
+            column.name.replace('"', "\"\""),
+            sql_type,
+            if not_null { " NOT NULL" } else { "" },
+            if i + 1 < report.columns.len() { "," } else { "" }
+        )?;
+    }
 
-    println!("File type: JSON");
-    println!("Records: {}", records.len());
-
-    if let Some(first) = records.first() {
-        println!("Fields:");
-        for (key, value) in first.iter() {
-            if show_types {
-                let dtype = match value {
-                    serde_json::Value::Number(n) if n.is_i64() => "integer",
-                    serde_json::Value::Number(_) => "float",
-                    serde_json::Value::Bool(_) => "boolean",
-                    serde_json::Value::String(_) => "string",
-                    serde_json::Value::Null => "null",
-                    serde_json::Value::Array(_) => "array",
-                    serde_json::Value::Object(_) => "object",
-                };
-                println!("  - {}: {}", key, dtype);
-            } else {
-                println!("  - {}", key);
+    writeln!(out, ");")
+}
+
+// JUnit XML for CI dashboards: one test suite per file, with a test case for
+// the dataset-level checks and one per column. Each diagnostic at or above
+// `level` is a failure of its test case; the rest go to system-out.
+fn write_junit_suite(out: &mut dyn Write, filename: &str, report: &Report, level: Severity) -> io::Result<()> {
+    let mut cases: Vec<(String, Vec<&Diagnostic>)> =
+        vec![("rows".to_string(), report.diagnostics.iter().collect())];
+    for column in &report.columns {
+        cases.push((
+            format!("column {}", column.name),
+            column.diagnostics.iter().flatten().collect(),
+        ));
+    }
+
+    let failures: usize = cases
+        .iter()
+        .map(|(_, diagnostics)| diagnostics.iter().filter(|d| d.severity >= level).count())
+        .sum();
+    let suite = xml_escape(filename);
+    writeln!(
+        out,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\">",
+        suite,
+        cases.len(),
+        failures
+    )?;
+    for (name, diagnostics) in &cases {
+        let name = xml_escape(name);
+        if diagnostics.is_empty() {
+            writeln!(out, "    <testcase classname=\"{}\" name=\"{}\"/>", suite, name)?;
+            continue;
+        }
+        writeln!(out, "    <testcase classname=\"{}\" name=\"{}\">", suite, name)?;
+        let (failing, passing): (Vec<&Diagnostic>, Vec<&Diagnostic>) =
+            diagnostics.iter().partition(|d| d.severity >= level);
+        for diagnostic in failing {
+            writeln!(
+                out,
+                "      <failure type=\"{}\" message=\"{}\"/>",
+                diagnostic.severity.as_str(),
+                xml_escape(&diagnostic.message)
+            )?;
+        }
+        if !passing.is_empty() {
+            writeln!(out, "      <system-out>")?;
+            for diagnostic in passing {
+                writeln!(out, "{}: {}", diagnostic.severity.as_str(), xml_escape(&diagnostic.message))?;
             }
+            writeln!(out, "      </system-out>")?;
         }
+        writeln!(out, "    </testcase>")?;
     }
+    writeln!(out, "  </testsuite>")
 }
 
-
-#[allow(dead_code)]
-fn infer_type(value: &str) -> &'static str {
-    if value.parse::<i64>().is_ok() {
-        "integer"
-    } else if value.parse::<f64>().is_ok() {
-        "float"
-    } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
-        "boolean"
-    } else {
-        "string"
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // not allowed in XML 1.0 at all
+            c if c.is_control() && !matches!(c, '\n' | '\t' | '\r') => escaped.push('?'),
+            c => escaped.push(c),
+        }
     }
+    escaped
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn usage_error(result: Result<(), InspectError>) -> String {
+        match result {
+            Err(InspectError::Usage(message)) => message,
+            other => panic!("expected a usage error, got {:?}", other),
+        }
+    }
+
+    // a file under the temp dir, unique to this test process
+    fn fixture(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("datainspect-main-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    fn report(csv: &str) -> Report {
+        datainspect::inspect_reader(csv.as_bytes(), InputFormat::Csv, &InspectOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn unknown_and_incomplete_flags_are_rejected() {
+        assert!(check_args(&args("data.csv --summary --top 5 -")).is_ok());
+
+        let message = usage_error(check_args(&args("data.csv --sumary")));
+        assert!(message.starts_with("unknown flag: --sumary (did you mean --summary?)"), "{}", message);
+        let message = usage_error(check_args(&args("data.csv --frobnicate")));
+        assert!(message.starts_with("unknown flag: --frobnicate\n"), "{}", message);
+        assert_eq!(usage_error(check_args(&args("data.csv --top"))), "--top needs a value");
+        assert_eq!(edit_distance("--limt", "--limit"), 1);
+    }
+
+    #[test]
+    fn junit_fails_the_test_cases_with_diagnostics_at_the_level() {
+        let report = report("id,a<b\n1,1\n2,x\n3,3\n4,4\n5,5\n6,6\n7,7\n8,8\n9,9\n10,10\n");
+        let mut out = Vec::new();
+        write_junit_suite(&mut out, "data&co.csv", &report, Severity::Warning).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+
+        let suite = "  <testsuite name=\"data&amp;co.csv\" tests=\"3\" failures=\"2\" errors=\"0\">\n";
+        assert!(xml.starts_with(suite), "{}", xml);
+        let column = "<testcase classname=\"data&amp;co.csv\" name=\"column a&lt;b\">\n      \
+                      <failure type=\"warning\" message=\"mixed numeric";
+        assert!(xml.contains(column), "{}", xml);
+        assert!(xml.contains("<system-out>\ninfo: likely primary key"), "{}", xml);
+        assert!(xml.ends_with("  </testsuite>\n"));
+
+        // below the level a diagnostic is only printed
+        let mut out = Vec::new();
+        write_junit_suite(&mut out, "data.csv", &report, Severity::Error).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("failures=\"0\""), "{}", xml);
+        assert!(xml.contains("<system-out>\nwarning: mixed numeric"), "{}", xml);
+    }
+
+    #[test]
+    fn diff_flags_the_changes_past_the_tolerance() {
+        let baseline = fixture("diff-a.csv", "id,price,code,old\n1,10,1,x\n2,10,2,y\n3,10,3,z\n4,10,4,w\n");
+        let target = fixture("diff-b.csv", "id,price,code,new\n1,10,a,x\n2,10,b,y\n3,10,c,z\n4,14,d,w\n");
+        let mut out = Vec::new();
+        let result = diff_stats(&mut out, &baseline, &target, 0.1, &InspectOptions::default());
+        let text = String::from_utf8(out).unwrap();
+        // price's stddev and max, the type of code, and one column on each side
+        assert!(matches!(result, Err(InspectError::StatsDiffer(5))), "{:?}\n{}", result, text);
+        assert!(text.contains("  id: ok\n"), "{}", text);
+        assert!(text.contains("    mean: 10 -> 11 (+10.0%)\n"), "{}", text);
+        assert!(text.contains("    ! stddev: 0 -> 2 (from 0)\n"), "{}", text);
+        assert!(text.contains("    ! max: 10 -> 14 (+40.0%)\n"), "{}", text);
+        assert!(text.contains("    ! type: numeric -> categorical\n"), "{}", text);
+        assert!(text.contains(&format!("  ! only in {}: old\n", baseline)), "{}", text);
+        assert!(text.contains(&format!("  ! only in {}: new\n", target)), "{}", text);
+
+        // a file against itself has nothing to report
+        let mut out = Vec::new();
+        assert!(diff_stats(&mut out, &baseline, &baseline, 0.0, &InspectOptions::default()).is_ok());
+        std::fs::remove_file(&baseline).unwrap();
+        std::fs::remove_file(&target).unwrap();
+    }
+
+    #[test]
+    fn completions_cover_every_flag_with_its_help_text() {
+        for flag in completion_flags() {
+            assert!(!flag_description(flag).is_empty(), "{} has no description in HELP", flag);
+        }
+        // a description starting on the line after a long flag
+        assert!(flag_description("--exclude-columns").starts_with("Skip these columns"));
+
+        let bash = bash_completions();
+        let choices = "        --outlier-method) COMPREPLY=($(compgen -W \"zscore iqr\" -- \"$cur\")); return ;;\n";
+        assert!(bash.contains(choices));
+        assert!(bash.contains("--output|--config|--junit|--diff) COMPREPLY=($(compgen -f"));
+
+        let zsh = zsh_completions();
+        assert!(zsh.starts_with("#compdef datainspect\n"));
+        assert!(zsh.contains("'--fail-on["));
+        assert!(zsh.contains(":value:(info warning error)' \\\n"));
+
+        let fish = fish_completions();
+        assert_eq!(fish.lines().count(), 2 + completion_flags().count());
+        assert!(fish.contains("complete -c datainspect -l output -d '"));
+        let output = fish.lines().find(|line| line.starts_with("complete -c datainspect -l output ")).unwrap();
+        assert!(output.ends_with(" -r -F"), "{}", output);
+    }
+
+    #[test]
+    fn delimiters_are_one_byte_or_a_name_for_tab() {
+        assert_eq!(parse_delimiter(";"), Some(b';'));
+        assert_eq!(parse_delimiter("\\t"), Some(b'\t'));
+        assert_eq!(parse_delimiter("tab"), Some(b'\t'));
+        assert_eq!(parse_delimiter(";;"), None);
+        assert_eq!(parse_delimiter(""), None);
+    }
+
+    #[test]
+    fn help_and_version_flags_are_known_and_documented() {
+        // -h and -V are answered before the flags are checked
+        for line in ["--help", "--version", "data.csv --version"] {
+            assert!(check_args(&args(line)).is_ok(), "{}", line);
+        }
+        assert!(HELP.contains("-V, --version"));
+        assert!(HELP.contains("-h, --help"));
+    }
+
+    #[test]
+    fn schemas_are_compared_file_to_file() {
+        let first = fixture("day1.csv", "id,name,score\n11,a,1.5\n");
+        let same = fixture("day2.csv", "id,name,score\n12,b,2.5\n");
+        let changed = fixture("day3.csv", "name,id,extra\nc,x,1\n");
+        let opts = InspectOptions::default();
+
+        let mut out = Vec::new();
+        compare_schemas(&mut out, &[&first, &same], &opts).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, format!("Schema comparison:\n  {}: 3 columns\n  {}: ok\n", first, same));
+
+        let mut out = Vec::new();
+        let result = compare_schemas(&mut out, &[&first, &same, &changed], &opts);
+        assert!(matches!(result, Err(InspectError::SchemaMismatch(1))), "{:?}", result);
+        let out = String::from_utf8(out).unwrap();
+        let expected = format!(
+            "  {}: differs from {}\n    ! missing column: score\n    ! type of id changed: numeric -> categorical\n    \
+             ! new column: extra\n    ! column order changed\n",
+            changed, same
+        );
+        assert!(out.ends_with(&expected), "{}", out);
+    }
+
+    #[test]
+    fn write_errors_name_the_output_path_or_stdout() {
+        let broken = || io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+        let opts = InspectOptions {
+            output: Some("out/report.json".to_string()),
+            ..InspectOptions::default()
+        };
+        assert_eq!(write_error(&opts)(broken()).to_string(), "failed to write out/report.json: permission denied");
+        let stdout = InspectOptions::default();
+        assert_eq!(write_error(&stdout)(broken()).to_string(), "failed to write stdout: permission denied");
+        assert!(check_args(&args("data.csv --output report.json --json")).is_ok());
+    }
+
+    #[test]
+    fn config_keys_become_flags_the_command_line_overrides() {
+        let config = fixture(
+            "config.toml",
+            "summary = true\ndelimiter = \";\"\ntop = 5\nna-values = [\"NA\", \"null\"]\nplain = true\n",
+        );
+        let defaults = config_args(&args(&format!("data.csv --config {} --top 3 --pretty", config))).unwrap();
+        let defaults: Vec<&str> = defaults.iter().map(String::as_str).collect();
+        assert_eq!(defaults, ["--delimiter", ";", "--na-values", "NA,null", "--summary"]);
+
+        let bad = fixture("bad.toml", "sumary = true\n");
+        let message = usage_error(config_args(&args(&format!("--config {}", bad))).map(|_| ()));
+        assert_eq!(message, format!("{}: unknown option: sumary", bad));
+        let bad = fixture("bad-value.toml", "summary = \"yes\"\n");
+        let message = usage_error(config_args(&args(&format!("--config {}", bad))).map(|_| ()));
+        assert_eq!(message, format!("{}: summary must be true or false", bad));
+    }
+
+    #[test]
+    fn check_names_must_be_known() {
+        let names = check_names(&args("--only-check missing,outliers --only-check ragged"), "--only-check").unwrap();
+        assert_eq!(names, ["missing", "outliers", "ragged"]);
+        let message = usage_error(check_names(&args("--disable-check outlier"), "--disable-check").map(|_| ()));
+        assert!(message.starts_with("unknown check for --disable-check: outlier (expected one of duplicates, "), "{}", message);
+    }
+
+    #[test]
+    fn json_reports_are_lines_or_one_array() {
+        let first = report("n\n1\n");
+        let second = report("n\n2\n3\n");
+        let opts = InspectOptions {
+            json: true,
+            ..InspectOptions::default()
+        };
+        let write = |array: bool| {
+            let mut out = Vec::new();
+            write_json_report(&mut out, "a.csv", &first, 2, 0, array, &opts).unwrap();
+            write_json_report(&mut out, "b.csv", &second, 2, 1, array, &opts).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let lines = write(false);
+        let records: Vec<serde_json::Value> = lines.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!((&records[1]["file"], &records[1]["rows"]), (&"b.csv".into(), &2.into()));
+
+        // run closes the array once every file is written
+        let array: serde_json::Value = serde_json::from_str(&(write(true) + "]")).unwrap();
+        assert_eq!(array.as_array().unwrap().len(), 2);
+        assert_eq!(array[0]["file"], "a.csv");
+    }
+
+    #[test]
+    fn the_overview_is_one_line_per_file_and_carries_on_past_errors() {
+        let clean = fixture("clean.csv", "tag,n\na,11\nb,12\na,13\nb,14\n");
+        let noisy = fixture("noisy.csv", "tag,n\na,11\na,11\n,13\n,x\n");
+        let missing = fixture("gone.csv", "");
+        std::fs::remove_file(&missing).unwrap();
+
+        let mut out = Vec::new();
+        let result = overview(&mut out, &[&clean, &missing, &noisy], &InspectOptions::default());
+        assert!(result.is_err());
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", out);
+        assert!(lines[1].contains("CSV") && lines[1].contains("100.0"), "{}", out);
+        assert!(lines[2].contains("error: file not found"), "{}", out);
+        assert!(lines[3].contains("CSV") && lines[3].ends_with("5 warnings"), "{}", out);
+    }
+
+    #[test]
+    fn timing_reports_each_file_and_a_total_with_throughput() {
+        let timing = |name, millis, bytes| FileTiming {
+            name,
+            elapsed: Duration::from_millis(millis),
+            rows: 1000,
+            bytes,
+        };
+        let lines = timing_lines(&[timing("a.csv", 500, Some(1_048_576))]);
+        assert_eq!(lines, ["Timing: a.csv: 0.500s, 1000 rows (2000 rows/s), 1.00 MiB (2.00 MiB/s)"]);
+
+        // stdin has no size, so its bytes and the total's drop out
+        let lines = timing_lines(&[timing("a.csv", 500, Some(1_048_576)), timing("-", 1500, None)]);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "Timing: -: 1.500s, 1000 rows (667 rows/s)");
+        assert_eq!(lines[2], "Timing: total: 2.000s, 2000 rows (1000 rows/s)");
+    }
+}